@@ -1,34 +1,89 @@
 //! Just commit large entries repeatedly. Intended to be used for generating a flamegraph
 //! to diagnose why committing entries is slow.
+//!
+//! If the `SNAPSHOTS` environment variable is set, both conductors run the app and
+//! headless snapshots are written to the file it names until the second conductor
+//! has every commit. The run fails if that doesn't happen within the timeout.
 
 #![allow(unused_imports)]
 
 use std::io::Write;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use colored::*;
 use holochain::sweettest::SweetConductorBatch;
+use holochain_diagnostics::holochain::conductor::conductor::RwShare;
 use holochain_diagnostics::holochain::prelude::*;
 use holochain_diagnostics::holochain::sweettest::{
     self, SweetConductor, SweetDnaFile, SweetInlineZomes,
 };
 use holochain_diagnostics::holochain::test_utils::inline_zomes::{simple_crud_zome, AppString};
+use holochain_diagnostics::ui::gossip_dashboard::{
+    headless_output, HeadlessDashboard, HeadlessState, Node,
+};
 use holochain_diagnostics::*;
 
+/// How often to write a snapshot when running headless
+const SNAPSHOT_RATE: Duration = Duration::from_millis(1000);
+/// How long to wait for convergence when running headless
+const HEADLESS_TIMEOUT: Duration = Duration::from_secs(60 * 10);
+
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     holochain_trace::test_run().ok();
     let start = Instant::now();
+    let headless = std::env::var("SNAPSHOTS").is_ok();
 
     // let config = config_no_networking();
     let config = config_standard();
     let mut conductors = SweetConductorBatch::from_config(2, config).await;
     conductors.exchange_peer_info().await;
-    let conductor = &mut conductors[0];
     let (dna, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
-    let app = conductor.setup_app("app", &[dna]).await.unwrap();
-    let (cell,) = app.into_tuple();
-    let zome = cell.zome(SweetInlineZomes::COORDINATOR);
+    let cells = if headless {
+        conductors
+            .setup_app("app", &[dna])
+            .await
+            .unwrap()
+            .cells_flattened()
+    } else {
+        let app = conductors[0].setup_app("app", &[dna]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        vec![cell]
+    };
+    let zomes: Vec<_> = cells
+        .iter()
+        .map(|cell| cell.zome(SweetInlineZomes::COORDINATOR))
+        .collect();
+    let conductors: Vec<_> = conductors.into_iter().map(Arc::new).collect();
+    let conductor = &conductors[0];
+    let zome = &zomes[0];
+
+    let state = if headless {
+        let mut nodes = vec![];
+        for (conductor, zome) in conductors.iter().zip(zomes.iter()) {
+            nodes.push(Node::new(conductor.clone(), zome.clone()).await);
+        }
+        Some(RwShare::new(HeadlessState::new(nodes)))
+    } else {
+        None
+    };
+    let done_committing = Arc::new(AtomicBool::new(false));
+    let headless_task = state.clone().map(|state| {
+        let done_committing = done_committing.clone();
+        tokio::spawn(async move {
+            HeadlessDashboard::new(Instant::now(), SNAPSHOT_RATE, headless_output()?)
+                .run(
+                    state,
+                    move |snapshot| {
+                        done_committing.load(Ordering::Relaxed) && snapshot.is_converged()
+                    },
+                    HEADLESS_TIMEOUT,
+                )
+                .await
+        })
+    });
 
     let setup_time = start.elapsed();
     println!("setup done in {:?}", setup_time);
@@ -41,10 +96,13 @@ async fn main() {
     // commit entries for roughly 10x as long as it took to setup the apps
     while start.elapsed().as_millis() < setup_time.as_millis() * 100 {
         let content = random_bytes(&mut rng, entry_size);
-        let _: ActionHash = conductor.call(&zome, "create_bytes", content).await;
+        let _: ActionHash = conductor.call(zome, "create_bytes", content).await;
         print!(".");
         std::io::stdout().flush().ok();
         total_committed += 1;
+        if let Some(state) = &state {
+            state.share_mut(|state| state.add_commits(1));
+        }
     }
     let time = start.elapsed() - setup_time;
     println!();
@@ -54,4 +112,10 @@ async fn main() {
         time,
         (entry_size * total_committed) as f64 / time.as_micros() as f64,
     );
+
+    done_committing.store(true, Ordering::Relaxed);
+    if let Some(headless_task) = headless_task {
+        headless_task.await??;
+    }
+    Ok(())
 }
@@ -30,6 +30,11 @@ const GET_RATE: Duration = Duration::from_millis(200);
 
 const REFRESH_RATE: Duration = Duration::from_millis(50);
 
+/// How often to write a snapshot when running without the UI
+const SNAPSHOT_RATE: Duration = Duration::from_millis(1000);
+/// How long to wait for convergence when running without the UI
+const HEADLESS_TIMEOUT: Duration = Duration::from_secs(60 * 10);
+
 /// Config for each conductor
 fn config() -> ConductorConfig {
     // config_historical_and_agent_gossip_only()
@@ -88,7 +93,8 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let app = setup_app(seeded_rng(None)).await;
 
     let mut tasks = vec![];
-    if COMMIT_RATE > Duration::ZERO {
+    // Without the UI there is no other way to commit
+    if COMMIT_RATE > Duration::ZERO || !show_ui {
         tasks.push(spawn_commit_task(app.clone()));
     }
     tasks.push(spawn_get_task(app.clone()));
@@ -101,8 +107,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
             r = ui_task => { r.unwrap().unwrap() }
         }
     } else {
+        // Write JSON-lines snapshots to the file named by SNAPSHOTS, or stdout,
+        // finishing with a summary once every commit has been seen everywhere.
+        let headless = HeadlessDashboard::new(Instant::now(), SNAPSHOT_RATE, headless_output()?);
+        let headless_task = headless.run(
+            app.state.clone(),
+            |snapshot| snapshot.total_commits >= MAX_COMMITS && snapshot.is_converged(),
+            HEADLESS_TIMEOUT,
+        );
         tokio::select! {
             r = tasks => { r.into_iter().collect::<Result<Vec<_>, _>>().unwrap();  }
+            r = headless_task => { r?; }
         }
     }
 
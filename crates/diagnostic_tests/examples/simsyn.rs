@@ -1,4 +1,8 @@
 //! Simulate behavior of a typical Syn app
+//!
+//! If the `SNAPSHOTS` environment variable is set, each node stops after a fixed
+//! number of commits and headless snapshots are written to the file it names until
+//! every node has every commit. The run fails if that doesn't happen within the timeout.
 
 use std::{
     io::Write,
@@ -6,11 +10,16 @@ use std::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use diagnostic_tests::syn_zome;
-use holochain_diagnostics::{holochain::sweettest::*, random_bytes, seeded_rng, AgentPubKey};
+use holochain_diagnostics::{
+    holochain::{conductor::conductor::RwShare, sweettest::*},
+    random_bytes, seeded_rng,
+    ui::gossip_dashboard::{self, headless_output, HeadlessDashboard, HeadlessState},
+    AgentPubKey,
+};
 use tokio_stream::{StreamExt, StreamMap};
 
 const NODES: usize = 10;
@@ -19,18 +28,56 @@ const COMMIT_SIZE: usize = 1_000_000;
 const SEND_RATE: Duration = Duration::from_millis(2000);
 const COMMIT_RATE: Duration = Duration::from_millis(2000);
 
+/// The number of commits to make when running headless
+const HEADLESS_COMMITS: usize = 20;
+/// How often to write a snapshot when running headless
+const SNAPSHOT_RATE: Duration = Duration::from_millis(1000);
+/// How long to wait for convergence when running headless
+const HEADLESS_TIMEOUT: Duration = Duration::from_secs(60 * 10);
+
 static SIGNALS_SENT: AtomicUsize = AtomicUsize::new(0);
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let (app, signal_rxs) = App::setup().await;
+    let headless = std::env::var("SNAPSHOTS").is_ok();
+
+    let state = if headless {
+        let mut nodes = vec![];
+        for node in &app.nodes {
+            nodes
+                .push(gossip_dashboard::Node::new(node.conductor.clone(), node.zome.clone()).await);
+        }
+        Some(RwShare::new(HeadlessState::new(nodes)))
+    } else {
+        None
+    };
 
     let mut handles = vec![];
     handles.push(task_signal_handler(app.clone(), signal_rxs));
     handles.push(task_signal_sender(app.clone()));
-    handles.push(task_commit(app.clone()));
-
-    futures::future::join_all(handles).await;
+    handles.push(task_commit(app.clone(), state.clone()));
+    let tasks = futures::future::join_all(handles);
+
+    match state {
+        Some(state) => {
+            let headless =
+                HeadlessDashboard::new(Instant::now(), SNAPSHOT_RATE, headless_output()?);
+            let headless_task = headless.run(
+                state,
+                |snapshot| snapshot.total_commits >= HEADLESS_COMMITS && snapshot.is_converged(),
+                HEADLESS_TIMEOUT,
+            );
+            tokio::select! {
+                _ = tasks => {}
+                r = headless_task => { r?; }
+            }
+        }
+        None => {
+            tasks.await;
+        }
+    }
+    Ok(())
 }
 
 #[derive(Clone)]
@@ -63,14 +110,22 @@ impl App {
     }
 }
 
-fn task_commit(app: App) -> tokio::task::JoinHandle<()> {
+/// Commit at the commit rate. With headless state, the commits are counted
+/// and stop after [`HEADLESS_COMMITS`].
+fn task_commit(app: App, state: Option<RwShare<HeadlessState>>) -> tokio::task::JoinHandle<()> {
     let mut rng = seeded_rng(None);
     tokio::spawn(async move {
         let mut n = 0;
         loop {
+            if state.is_some() && n >= HEADLESS_COMMITS {
+                break;
+            }
             let node: &Node = &app.nodes[n % NODES];
             let data = random_bytes(&mut rng, COMMIT_SIZE);
             let _: () = node.conductor.call(&node.zome, "commit", data).await;
+            if let Some(state) = &state {
+                state.share_mut(|state| state.add_commits(1));
+            }
 
             println!(
                 "\ncommitted. signals so far: {}",
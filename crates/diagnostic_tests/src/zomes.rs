@@ -110,6 +110,7 @@ pub fn syn_zome() -> InlineIntegrityZome {
                 api.remote_signal(RemoteSignal {
                     agents,
                     signal: ExternIO::encode(msg).unwrap(),
+                    reliable: false,
                 })?;
                 Ok(())
             },
@@ -127,6 +128,9 @@ pub fn syn_zome() -> InlineIntegrityZome {
                 // empty access converts to unrestricted
                 access: ().into(),
                 functions: GrantedFunctions::Listed(functions),
+                expires_at: None,
+                max_uses: None,
+                curry_payloads: Default::default(),
             };
             api.create(CreateInput::new(
                 EntryDefLocation::CapGrant,
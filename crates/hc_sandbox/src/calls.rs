@@ -33,6 +33,7 @@ use crate::ports::get_admin_ports;
 use crate::run::run_async;
 use crate::CmdRunner;
 use clap::Parser;
+use tokio::process::Child;
 
 #[doc(hidden)]
 #[derive(Debug, Parser)]
@@ -225,25 +226,7 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
         let ports = get_admin_ports(paths.clone()).await?;
         let mut cmds = Vec::with_capacity(ports.len());
         for (port, path) in ports.into_iter().zip(paths.into_iter()) {
-            match CmdRunner::try_new(port).await {
-                Ok(cmd) => cmds.push((cmd, None, None)),
-                Err(e) => {
-                    if let holochain_websocket::WebsocketError::Io(e) = &e {
-                        if let std::io::ErrorKind::ConnectionRefused
-                        | std::io::ErrorKind::AddrNotAvailable = e.kind()
-                        {
-                            let (port, holochain, lair) =
-                                run_async(holochain_path, path, None).await?;
-                            cmds.push((CmdRunner::new(port).await, Some(holochain), Some(lair)));
-                            continue;
-                        }
-                    }
-                    bail!(
-                        "Failed to connect to running conductor or start one {:?}",
-                        e
-                    )
-                }
-            }
+            cmds.push(connect_or_run(holochain_path, path, port).await?);
         }
         cmds
     } else {
@@ -259,6 +242,32 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Connect to the conductor of a sandbox on its admin port,
+/// or start the conductor if it isn't running.
+pub(crate) async fn connect_or_run(
+    holochain_path: &Path,
+    path: PathBuf,
+    port: u16,
+) -> anyhow::Result<(CmdRunner, Option<Child>, Option<Child>)> {
+    match CmdRunner::try_new(port).await {
+        Ok(cmd) => Ok((cmd, None, None)),
+        Err(e) => {
+            if let holochain_websocket::WebsocketError::Io(e) = &e {
+                if let std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::AddrNotAvailable = e.kind()
+                {
+                    let (port, holochain, lair) = run_async(holochain_path, path, None).await?;
+                    return Ok((CmdRunner::new(port).await, Some(holochain), lair));
+                }
+            }
+            bail!(
+                "Failed to connect to running conductor or start one {:?}",
+                e
+            )
+        }
+    }
+}
+
 async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Result<()> {
     match call {
         AdminRequestCli::AddAdminWs(args) => {
@@ -450,6 +459,7 @@ pub async fn install_app_bundle(cmd: &mut CmdRunner, args: InstallApp) -> anyhow
         source: AppBundleSource::Path(path),
         membrane_proofs: Default::default(),
         network_seed,
+        quotas: Default::default(),
     };
 
     let r = AdminRequest::InstallApp(Box::new(payload));
@@ -495,7 +505,7 @@ pub async fn list_dnas(cmd: &mut CmdRunner) -> anyhow::Result<Vec<DnaHash>> {
 
 /// Calls [`AdminRequest::GenerateAgentPubKey`].
 pub async fn generate_agent_pub_key(cmd: &mut CmdRunner) -> anyhow::Result<AgentPubKey> {
-    let resp = cmd.command(AdminRequest::GenerateAgentPubKey).await?;
+    let resp = cmd.command(AdminRequest::GenerateAgentPubKey(None)).await?;
     Ok(
         expect_match!(resp => AdminResponse::AgentPubKeyGenerated, "Failed to generate agent pubkey"),
     )
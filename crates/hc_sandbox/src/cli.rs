@@ -77,6 +77,10 @@ pub enum HcSandboxSubcommand {
     /// Make a call to a conductor's admin interface.
     Call(crate::calls::Call),
 
+    /// Run a YAML scenario of app installs, zome calls and
+    /// consistency checks across existing sandboxes.
+    Scenario(crate::scenario::Scenario),
+
     /// List sandboxes found in `$(pwd)/.hc`.
     List {
         /// Show more verbose information.
@@ -159,6 +163,9 @@ impl HcSandbox {
             HcSandboxSubcommand::Call(call) => {
                 crate::calls::call(&self.holochain_path, call).await?
             }
+            HcSandboxSubcommand::Scenario(scenario) => {
+                crate::scenario::run(&self.holochain_path, scenario).await?
+            }
             // HcSandboxSubcommand::Task => todo!("Running custom tasks is coming soon"),
             HcSandboxSubcommand::List { verbose } => {
                 crate::save::list(std::env::current_dir()?, verbose)?
@@ -48,6 +48,7 @@ pub mod generate;
 pub mod run;
 pub mod sandbox;
 pub mod save;
+pub mod scenario;
 pub use cli::HcSandbox;
 
 mod ports;
@@ -49,6 +49,11 @@ pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender>
     websocket_client_by_port(port).await.map(|p| p.0)
 }
 
+pub(crate) async fn get_app_api(port: u16) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(port);
+    websocket_client_by_port(port).await.map(|p| p.0)
+}
+
 async fn websocket_client_by_port(
     port: u16,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
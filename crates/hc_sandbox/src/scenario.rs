@@ -0,0 +1,745 @@
+//! Run declarative scenarios across multiple sandboxed conductors.
+//!
+//! A scenario is a YAML file which gives names to some of the sandboxes
+//! listed in `$(pwd)/.hc` (by their index, as shown by `hc sandbox list`),
+//! then lists the steps to run against them, in order:
+//!
+//! ```yaml
+//! conductors:
+//!   alice: 0
+//!   bob: 1
+//! steps:
+//!   - install_app:
+//!       conductor: alice
+//!       app_id: forum
+//!       path: forum.happ
+//!   - install_app:
+//!       conductor: bob
+//!       app_id: forum
+//!       path: forum.happ
+//!   - call:
+//!       conductor: alice
+//!       app_id: forum
+//!       role: forum
+//!       zome: posts
+//!       fn: create_post
+//!       payload:
+//!         title: Hello
+//!       save_as: post_hash
+//!   - wait_for_consistency:
+//!       app_id: forum
+//!       role: forum
+//!   - call:
+//!       conductor: bob
+//!       app_id: forum
+//!       role: forum
+//!       zome: posts
+//!       fn: get_post_title
+//!       payload: $post_hash
+//!       expect: Hello
+//! ```
+//!
+//! Any string of the form `$name` in a payload or an expected result is
+//! replaced by the result of the call saved under that name.
+//! Paths are relative to the directory of the scenario file.
+//! The scenario fails at the first step that fails or whose result doesn't
+//! match what was expected.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use anyhow::bail;
+use anyhow::Context;
+use clap::Parser;
+use holochain_conductor_api::AdminRequest;
+use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
+use holochain_conductor_api::CellInfo;
+use holochain_conductor_api::ZomeCall;
+use holochain_types::prelude::*;
+use holochain_websocket::WebsocketSender;
+use serde::Deserialize;
+use tokio::process::Child;
+
+use crate::calls::attach_app_interface;
+use crate::calls::connect_or_run;
+use crate::calls::install_app_bundle;
+use crate::calls::list_app_ws;
+use crate::calls::AddAppWs;
+use crate::calls::InstallApp;
+use crate::expect_match;
+use crate::ports::get_admin_ports;
+use crate::ports::get_app_api;
+use crate::CmdRunner;
+
+/// How long zome calls made by a scenario are valid for.
+const ZOME_CALL_EXPIRY: Duration = Duration::from_secs(60 * 5);
+
+/// How often to check for consistency while waiting for it.
+const CONSISTENCY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Run a scenario file against existing sandboxes.
+#[derive(Debug, Parser, Clone)]
+pub struct Scenario {
+    /// Path to the scenario YAML file.
+    pub path: PathBuf,
+
+    /// Ports to running conductor admin interfaces.
+    /// If set, the indices in the scenario refer to these ports
+    /// instead of the sandboxes in `$(pwd)/.hc`.
+    #[arg(short, long, value_delimiter = ',')]
+    pub running: Vec<u16>,
+}
+
+/// The contents of a scenario file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioFile {
+    /// Names for the conductors taking part, mapped to their sandbox index.
+    pub conductors: BTreeMap<String, usize>,
+    /// The steps to run, in order.
+    /// Each step is a map with a single key naming it, rather than a YAML tag.
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    pub steps: Vec<Step>,
+}
+
+/// A single step of a scenario.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    /// Install and enable an app on a conductor.
+    InstallApp(InstallAppStep),
+    /// Call a zome function, optionally saving and checking the result.
+    Call(CallStep),
+    /// Wait until the conductors have integrated the same ops for a cell.
+    WaitForConsistency(WaitForConsistencyStep),
+    /// Wait for this many milliseconds.
+    SleepMs(u64),
+}
+
+/// Install and enable an app on a conductor.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InstallAppStep {
+    /// The name of the conductor.
+    pub conductor: String,
+    /// The id to install the app with.
+    pub app_id: InstalledAppId,
+    /// Path to the *.happ bundle file.
+    pub path: PathBuf,
+    /// Optional network seed override for every DNA in the app.
+    #[serde(default)]
+    pub network_seed: Option<NetworkSeed>,
+}
+
+/// Call a zome function.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CallStep {
+    /// The name of the conductor.
+    pub conductor: String,
+    /// The app to call into.
+    pub app_id: InstalledAppId,
+    /// The role of the cell to call.
+    pub role: RoleName,
+    /// The zome to call.
+    pub zome: String,
+    /// The function to call.
+    #[serde(rename = "fn")]
+    pub fn_name: String,
+    /// The payload of the call. Defaults to null, i.e. `()`.
+    #[serde(default)]
+    pub payload: serde_yaml::Value,
+    /// Save the result under this name.
+    #[serde(default)]
+    pub save_as: Option<String>,
+    /// Fail the scenario if the result is not equal to this.
+    #[serde(default)]
+    pub expect: Option<serde_yaml::Value>,
+}
+
+/// Wait until every conductor has integrated the same ops, compared by
+/// their hashes, for a cell and has no ops left to validate or integrate.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaitForConsistencyStep {
+    /// The app whose cell to compare.
+    pub app_id: InstalledAppId,
+    /// The role of the cell to compare.
+    pub role: RoleName,
+    /// The names of the conductors to compare. Defaults to all of them.
+    #[serde(default)]
+    pub conductors: Vec<String>,
+    /// How long to wait before failing the scenario.
+    #[serde(default = "default_consistency_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_consistency_timeout_secs() -> u64 {
+    60
+}
+
+/// A key pair which has been granted a capability to call the zome
+/// functions of a cell, used to sign zome calls.
+struct Signer {
+    agent: AgentPubKey,
+    cap_secret: CapSecret,
+    sec_key: sodoken::BufReadSized<{ sodoken::sign::SECRETKEYBYTES }>,
+}
+
+struct ScenarioConductor {
+    admin: CmdRunner,
+    app: Option<WebsocketSender>,
+    signers: HashMap<CellId, Signer>,
+    // Conductors started by the scenario are stopped when it ends.
+    _holochain: Option<Child>,
+    _lair: Option<Child>,
+}
+
+struct ScenarioRunner {
+    conductors: BTreeMap<String, ScenarioConductor>,
+    saved: HashMap<String, rmpv::Value>,
+    base_dir: PathBuf,
+}
+
+/// Run a scenario file.
+pub async fn run(holochain_path: &Path, args: Scenario) -> anyhow::Result<()> {
+    let Scenario { path, running } = args;
+    let scenario: ScenarioFile = serde_yaml::from_str(
+        &std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read scenario {}", path.display()))?,
+    )?;
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let mut runner =
+        ScenarioRunner::connect(holochain_path, scenario.conductors, running, base_dir).await?;
+    let num_steps = scenario.steps.len();
+    for (i, step) in scenario.steps.into_iter().enumerate() {
+        runner
+            .step(step)
+            .await
+            .with_context(|| format!("Step {} of {} failed", i + 1, num_steps))?;
+    }
+    msg!("Scenario passed ({} steps)", num_steps);
+    Ok(())
+}
+
+impl ScenarioRunner {
+    async fn connect(
+        holochain_path: &Path,
+        names: BTreeMap<String, usize>,
+        running: Vec<u16>,
+        base_dir: PathBuf,
+    ) -> anyhow::Result<Self> {
+        let mut conductors = BTreeMap::new();
+        if running.is_empty() {
+            let paths = crate::save::load(std::env::current_dir()?)?;
+            for (name, index) in names {
+                let path = paths
+                    .get(index)
+                    .ok_or_else(|| anyhow!("Conductor {} has no sandbox at index {}", name, index))?
+                    .clone();
+                let port = get_admin_ports(vec![path.clone()])
+                    .await?
+                    .pop()
+                    .ok_or_else(|| anyhow!("Sandbox {} has no admin port", path.display()))?;
+                let (admin, holochain, lair) = connect_or_run(holochain_path, path, port).await?;
+                conductors.insert(name, ScenarioConductor::new(admin, holochain, lair));
+            }
+        } else {
+            for (name, index) in names {
+                let port = *running.get(index).ok_or_else(|| {
+                    anyhow!("Conductor {} has no running port at index {}", name, index)
+                })?;
+                conductors.insert(
+                    name,
+                    ScenarioConductor::new(CmdRunner::try_new(port).await?, None, None),
+                );
+            }
+        }
+        Ok(Self {
+            conductors,
+            saved: HashMap::new(),
+            base_dir,
+        })
+    }
+
+    fn conductor(&mut self, name: &str) -> anyhow::Result<&mut ScenarioConductor> {
+        self.conductors
+            .get_mut(name)
+            .ok_or_else(|| anyhow!("Unknown conductor {}", name))
+    }
+
+    async fn step(&mut self, step: Step) -> anyhow::Result<()> {
+        match step {
+            Step::InstallApp(InstallAppStep {
+                conductor,
+                app_id,
+                path,
+                network_seed,
+            }) => {
+                let path = self.base_dir.join(path);
+                let app = install_app_bundle(
+                    &mut self.conductor(&conductor)?.admin,
+                    InstallApp {
+                        app_id: Some(app_id),
+                        agent_key: None,
+                        path,
+                        network_seed,
+                    },
+                )
+                .await?;
+                msg!("{}: installed app {}", conductor, app.installed_app_id);
+            }
+            Step::Call(call) => self.call(call).await?,
+            Step::WaitForConsistency(step) => self.wait_for_consistency(step).await?,
+            Step::SleepMs(ms) => tokio::time::sleep(Duration::from_millis(ms)).await,
+        }
+        Ok(())
+    }
+
+    async fn call(&mut self, step: CallStep) -> anyhow::Result<()> {
+        let CallStep {
+            conductor: name,
+            app_id,
+            role,
+            zome,
+            fn_name,
+            payload,
+            save_as,
+            expect,
+        } = step;
+        let payload = substitute(yaml_to_value(payload)?, &self.saved)?;
+        let expect = match expect {
+            Some(expect) => Some(substitute(yaml_to_value(expect)?, &self.saved)?),
+            None => None,
+        };
+
+        let conductor = self.conductor(&name)?;
+        let cell_id = conductor.cell_id(&app_id, &role).await?;
+        let result = conductor
+            .call_zome(cell_id, zome.clone(), fn_name.clone(), payload)
+            .await?;
+        msg!("{}: {}/{} returned {}", name, zome, fn_name, result);
+
+        if let Some(expect) = expect {
+            if result != expect {
+                bail!(
+                    "{}/{} on {} returned {} but {} was expected",
+                    zome,
+                    fn_name,
+                    name,
+                    result,
+                    expect
+                );
+            }
+        }
+        if let Some(save_as) = save_as {
+            self.saved.insert(save_as, result);
+        }
+        Ok(())
+    }
+
+    async fn wait_for_consistency(&mut self, step: WaitForConsistencyStep) -> anyhow::Result<()> {
+        let WaitForConsistencyStep {
+            app_id,
+            role,
+            conductors,
+            timeout_secs,
+        } = step;
+        let names = if conductors.is_empty() {
+            self.conductors.keys().cloned().collect()
+        } else {
+            conductors
+        };
+        let timeout = Duration::from_secs(timeout_secs);
+        let start = std::time::Instant::now();
+        loop {
+            let mut states = Vec::with_capacity(names.len());
+            for name in names.iter() {
+                let conductor = self.conductor(name)?;
+                let cell_id = conductor.cell_id(&app_id, &role).await?;
+                states.push((name, conductor.integration_state(cell_id).await?));
+            }
+            let consistent = states
+                .iter()
+                .all(|(_, (pending, integrated))| *pending == 0 && *integrated == states[0].1 .1);
+            if consistent {
+                msg!(
+                    "Consistent after {:?} with {} integrated ops",
+                    start.elapsed(),
+                    states.first().map(|s| s.1 .1.len()).unwrap_or(0)
+                );
+                return Ok(());
+            }
+            if start.elapsed() >= timeout {
+                let all: HashSet<&DhtOpHash> = states
+                    .iter()
+                    .flat_map(|(_, (_, integrated))| integrated)
+                    .collect();
+                let states = states
+                    .iter()
+                    .map(|(name, (pending, integrated))| {
+                        format!(
+                            "{}: {} integrated, {} missing, {} pending",
+                            name,
+                            integrated.len(),
+                            all.len() - integrated.len(),
+                            pending
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                bail!("Not consistent after {:?} ({})", timeout, states);
+            }
+            tokio::time::sleep(CONSISTENCY_POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl ScenarioConductor {
+    fn new(admin: CmdRunner, holochain: Option<Child>, lair: Option<Child>) -> Self {
+        Self {
+            admin,
+            app: None,
+            signers: HashMap::new(),
+            _holochain: holochain,
+            _lair: lair,
+        }
+    }
+
+    /// Connect to an app interface, attaching one if there are none.
+    async fn app(&mut self) -> anyhow::Result<&mut WebsocketSender> {
+        if self.app.is_none() {
+            let port = match list_app_ws(&mut self.admin).await?.first() {
+                Some(port) => *port,
+                None => attach_app_interface(&mut self.admin, AddAppWs { port: None }).await?,
+            };
+            self.app = Some(get_app_api(port).await?);
+        }
+        Ok(self.app.as_mut().expect("Connected above"))
+    }
+
+    async fn app_request(&mut self, request: AppRequest) -> anyhow::Result<AppResponse> {
+        let response: Result<AppResponse, _> = self.app().await?.request(request).await;
+        Ok(response?)
+    }
+
+    async fn cell_id(
+        &mut self,
+        app_id: &InstalledAppId,
+        role: &RoleName,
+    ) -> anyhow::Result<CellId> {
+        let response = self
+            .app_request(AppRequest::AppInfo {
+                installed_app_id: app_id.clone(),
+            })
+            .await?;
+        let app_info = expect_match!(response => AppResponse::AppInfo, "Failed to get app info")
+            .ok_or_else(|| anyhow!("App {} is not installed", app_id))?;
+        app_info
+            .cell_info
+            .get(role)
+            .into_iter()
+            .flatten()
+            .find_map(|cell| match cell {
+                CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("App {} has no provisioned cell for role {}", app_id, role))
+    }
+
+    /// Generate a key pair and grant it a capability to call any
+    /// zome function of the cell.
+    async fn signer(&mut self, cell_id: &CellId) -> anyhow::Result<&Signer> {
+        if !self.signers.contains_key(cell_id) {
+            let pub_key = sodoken::BufWriteSized::new_no_lock();
+            let sec_key = sodoken::BufWriteSized::new_no_lock();
+            sodoken::sign::keypair(pub_key.clone(), sec_key.clone()).await?;
+            let agent = AgentPubKey::from_raw_32(pub_key.read_lock().to_vec());
+            let cap_secret = CapSecret::from(random_bytes::<64>().await?);
+
+            let response = self
+                .admin
+                .command(AdminRequest::GrantZomeCallCapability(Box::new(
+                    GrantZomeCallCapabilityPayload {
+                        cell_id: cell_id.clone(),
+                        cap_grant: ZomeCallCapGrant {
+                            tag: "hc-sandbox-scenario".into(),
+                            access: CapAccess::Assigned {
+                                secret: cap_secret,
+                                assignees: [agent.clone()].into_iter().collect(),
+                            },
+                            functions: GrantedFunctions::All,
+                            expires_at: None,
+                            max_uses: None,
+                            curry_payloads: Default::default(),
+                        },
+                    },
+                )))
+                .await?;
+            if !matches!(response, AdminResponse::ZomeCallCapabilityGranted) {
+                bail!(
+                    "Failed to grant a zome call capability, got: {:?}",
+                    response
+                );
+            }
+            self.signers.insert(
+                cell_id.clone(),
+                Signer {
+                    agent,
+                    cap_secret,
+                    sec_key: sec_key.to_read_sized(),
+                },
+            );
+        }
+        Ok(&self.signers[cell_id])
+    }
+
+    async fn call_zome(
+        &mut self,
+        cell_id: CellId,
+        zome: String,
+        fn_name: String,
+        payload: rmpv::Value,
+    ) -> anyhow::Result<rmpv::Value> {
+        let signer = self.signer(&cell_id).await?;
+        let (nonce, expires_at) = (
+            Nonce256Bits::from(random_bytes::<32>().await?),
+            (Timestamp::now() + ZOME_CALL_EXPIRY)?,
+        );
+        let unsigned = ZomeCallUnsigned {
+            provenance: signer.agent.clone(),
+            cell_id,
+            zome_name: zome.into(),
+            fn_name: fn_name.into(),
+            cap_secret: Some(signer.cap_secret),
+            payload: ExternIO::encode(payload)?,
+            nonce,
+            expires_at,
+        };
+        let signature = sodoken::BufWriteSized::new_no_lock();
+        sodoken::sign::detached(
+            signature.clone(),
+            unsigned.data_to_sign()?.to_vec(),
+            signer.sec_key.clone(),
+        )
+        .await?;
+        let signature = Signature::try_from(signature.read_lock().to_vec())?;
+
+        let response = self
+            .app_request(AppRequest::CallZome(Box::new(ZomeCall {
+                cell_id: unsigned.cell_id,
+                zome_name: unsigned.zome_name,
+                fn_name: unsigned.fn_name,
+                payload: unsigned.payload,
+                cap_secret: unsigned.cap_secret,
+                provenance: unsigned.provenance,
+                signature,
+                nonce: unsigned.nonce,
+                expires_at: unsigned.expires_at,
+            })))
+            .await?;
+        let result = expect_match!(response => AppResponse::ZomeCalled, "Zome call failed");
+        Ok(result.decode()?)
+    }
+
+    /// The number of ops still pending validation or integration,
+    /// and the hashes of the integrated ops, for a cell.
+    async fn integration_state(
+        &mut self,
+        cell_id: CellId,
+    ) -> anyhow::Result<(usize, HashSet<DhtOpHash>)> {
+        let response = self
+            .admin
+            .command(AdminRequest::DumpFullState {
+                cell_id: Box::new(cell_id),
+                dht_ops_cursor: None,
+            })
+            .await?;
+        let dump =
+            expect_match!(response => AdminResponse::FullStateDumped, "Failed to dump state");
+        let integration = dump.integration_dump;
+        let pending = integration.validation_limbo.len() + integration.integration_limbo.len();
+        // The hashes are only needed to compare conductors with nothing pending.
+        let integrated = if pending == 0 {
+            integration
+                .integrated
+                .iter()
+                .map(DhtOpHash::with_data_sync)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        Ok((pending, integrated))
+    }
+}
+
+fn yaml_to_value(yaml: serde_yaml::Value) -> anyhow::Result<rmpv::Value> {
+    Ok(serde_yaml::from_value(yaml)?)
+}
+
+/// Replace every string of the form `$name` with the value saved as `name`.
+fn substitute(
+    value: rmpv::Value,
+    saved: &HashMap<String, rmpv::Value>,
+) -> anyhow::Result<rmpv::Value> {
+    Ok(match value {
+        rmpv::Value::String(s) => match s.as_str().and_then(|s| s.strip_prefix('$')) {
+            Some(name) => saved
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow!("No result was saved as {}", name))?,
+            None => rmpv::Value::String(s),
+        },
+        rmpv::Value::Array(values) => rmpv::Value::Array(
+            values
+                .into_iter()
+                .map(|v| substitute(v, saved))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        rmpv::Value::Map(entries) => rmpv::Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| Ok((k, substitute(v, saved)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        value => value,
+    })
+}
+
+async fn random_bytes<const N: usize>() -> anyhow::Result<[u8; N]> {
+    let buf = sodoken::BufWriteSized::<N>::new_no_lock();
+    sodoken::random::bytes_buf(buf.clone()).await?;
+    let bytes = buf.read_lock().to_vec();
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("Failed to generate random bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn substitute_saved_results() {
+        let saved: HashMap<String, rmpv::Value> = [
+            ("hash".to_string(), rmpv::Value::Binary(vec![1, 2, 3])),
+            ("count".to_string(), rmpv::Value::from(3)),
+        ]
+        .into_iter()
+        .collect();
+        let payload = yaml_to_value(
+            serde_yaml::from_str(
+                "
+                hash: $hash
+                nested:
+                  - $count
+                  - unchanged
+                  - 1
+                ",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let expected = rmpv::Value::Map(vec![
+            (
+                rmpv::Value::from("hash"),
+                rmpv::Value::Binary(vec![1, 2, 3]),
+            ),
+            (
+                rmpv::Value::from("nested"),
+                rmpv::Value::Array(vec![
+                    rmpv::Value::from(3),
+                    rmpv::Value::from("unchanged"),
+                    rmpv::Value::from(1),
+                ]),
+            ),
+        ]);
+        assert_eq!(substitute(payload, &saved).unwrap(), expected);
+
+        // Names which weren't saved are an error.
+        assert!(substitute(rmpv::Value::from("$missing"), &saved).is_err());
+        // Keys are never substituted.
+        let map = rmpv::Value::Map(vec![(rmpv::Value::from("$hash"), rmpv::Value::Nil)]);
+        assert_eq!(substitute(map.clone(), &saved).unwrap(), map);
+    }
+
+    #[test]
+    fn parse_scenario_file() {
+        let scenario: ScenarioFile = serde_yaml::from_str(
+            "
+            conductors:
+              alice: 0
+              bob: 1
+            steps:
+              - install_app:
+                  conductor: alice
+                  app_id: forum
+                  path: forum.happ
+              - call:
+                  conductor: alice
+                  app_id: forum
+                  role: forum
+                  zome: posts
+                  fn: create_post
+                  payload:
+                    title: Hello
+                  save_as: post_hash
+              - wait_for_consistency:
+                  app_id: forum
+                  role: forum
+              - sleep_ms: 100
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(scenario.conductors["alice"], 0);
+        assert_eq!(scenario.conductors["bob"], 1);
+        assert_eq!(scenario.steps.len(), 4);
+        assert!(matches!(
+            &scenario.steps[0],
+            Step::InstallApp(InstallAppStep { conductor, network_seed: None, .. })
+                if conductor == "alice"
+        ));
+        match &scenario.steps[1] {
+            Step::Call(call) => {
+                assert_eq!(call.fn_name, "create_post");
+                assert_eq!(call.save_as.as_deref(), Some("post_hash"));
+                assert!(call.expect.is_none());
+            }
+            step => panic!("Expected a call, got {:?}", step),
+        }
+        match &scenario.steps[2] {
+            Step::WaitForConsistency(wait) => {
+                assert!(wait.conductors.is_empty());
+                assert_eq!(wait.timeout_secs, default_consistency_timeout_secs());
+            }
+            step => panic!("Expected to wait for consistency, got {:?}", step),
+        }
+        assert!(matches!(scenario.steps[3], Step::SleepMs(100)));
+
+        // Unknown fields are rejected rather than ignored.
+        assert!(serde_yaml::from_str::<ScenarioFile>(
+            "
+            conductors: {}
+            steps:
+              - call:
+                  conductor: alice
+                  app_id: forum
+                  role: forum
+                  zome: posts
+                  fn: create_post
+                  expected: Hello
+            ",
+        )
+        .is_err());
+    }
+}
@@ -204,6 +204,53 @@ async fn generate_sandbox_and_call_list_dna() {
     hc_admin.kill().await.unwrap();
 }
 
+/// Generates a new sandbox with a single app deployed and runs a scenario
+/// against it which installs a second app and calls into both
+#[tokio::test(flavor = "multi_thread")]
+async fn generate_sandbox_and_run_scenario() {
+    clean_sandboxes().await;
+    package_fixture_if_not_packaged().await;
+
+    holochain_trace::test_run().ok();
+    let mut cmd = get_sandbox_command();
+    cmd.env("RUST_BACKTRACE", "1")
+        .arg(format!(
+            "--holochain-path={}",
+            get_holochain_bin_path().to_str().unwrap()
+        ))
+        .arg("--piped")
+        .arg("generate")
+        .arg("--in-process-lair")
+        .arg("--run=0")
+        .arg("tests/fixtures/my-app/")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    let mut hc_admin = cmd.spawn().expect("Failed to spawn holochain");
+    let mut child_stdin = hc_admin.stdin.take().unwrap();
+    child_stdin.write_all(b"test-phrase\n").await.unwrap();
+    drop(child_stdin);
+
+    let launch_info = get_launch_info(hc_admin.stdout.take().unwrap()).await;
+
+    let mut cmd = get_sandbox_command();
+    cmd.env("RUST_BACKTRACE", "1")
+        .arg("scenario")
+        .arg(format!("--running={}", launch_info.admin_port))
+        .arg("tests/fixtures/scenario.yaml")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::inherit());
+    let mut hc_scenario = cmd.spawn().expect("Failed to spawn scenario");
+
+    let exit_code = hc_scenario.wait().await.unwrap();
+    assert!(exit_code.success());
+
+    hc_admin.kill().await.unwrap();
+}
+
 fn get_hc_command() -> Command {
     Command::new(match which("hc") {
         Ok(p) => p,
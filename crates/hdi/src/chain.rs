@@ -12,6 +12,9 @@ use holo_hash::AgentPubKey;
 /// for something that is being validated.
 ///
 /// Check the [`ChainFilter`] docs for more info.
+///
+/// If the author has forked their chain within the range, validation
+/// fails with the two actions at the fork.
 pub fn must_get_agent_activity(
     author: AgentPubKey,
     filter: ChainFilter,
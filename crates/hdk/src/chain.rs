@@ -31,3 +31,14 @@ pub fn get_agent_activity(
 pub fn query(filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
     HDK.with(|h| h.borrow().query(filter))
 }
+
+/// Counts the records on the source chain which [`query`] would return for the
+/// same filter, without bringing any of them into wasm memory.
+/// The `limit` of the filter is ignored, so with a `cursor` this counts all of
+/// the records after it.
+///
+/// Combined with [`ChainQueryFilter::limit`] and [`ChainQueryFilter::cursor`]
+/// this allows long chains to be walked one page at a time.
+pub fn query_count(filter: ChainQueryFilter) -> ExternResult<usize> {
+    HDK.with(|h| h.borrow().query_count(filter))
+}
@@ -294,6 +294,28 @@ pub fn get_details<H: Into<AnyDhtHash>>(
         .unwrap())
 }
 
+/// Get the metadata the DHT holds for any hash without fetching the data itself.
+///
+/// The authorities for the hash report the create, update and delete actions they hold,
+/// the validation status of the record or entry, the number of live links on the hash
+/// and, for entries, whether the entry is live. Their answers are merged into a single
+/// [`DhtMetadata`] which also counts how many authorities hold anything for the hash.
+///
+/// Returns [`None`] if no authority could be found that holds data for the hash.
+pub fn get_meta<H: Into<AnyDhtHash>>(
+    hash: H,
+    options: GetOptions,
+) -> ExternResult<Option<DhtMetadata>> {
+    Ok(HDK
+        .with(|h| {
+            h.borrow()
+                .get_meta(vec![GetInput::new(hash.into(), options)])
+        })?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
 /// Implements a whole lot of sane defaults for a struct or enum that should behave as an entry.
 /// All the entry def fields are available as dedicated methods on the type and matching From impls
 /// are provided for each. This allows for both Foo::entry_def() and EntryDef::from(Foo::new())
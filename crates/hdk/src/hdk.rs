@@ -27,6 +27,7 @@ pub trait HdkT: HdiT {
         get_agent_activity_input: GetAgentActivityInput,
     ) -> ExternResult<AgentActivity>;
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+    fn query_count(&self, filter: ChainQueryFilter) -> ExternResult<usize>;
     // Ed25519
     fn sign(&self, sign: Sign) -> ExternResult<Signature>;
     fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -36,6 +37,7 @@ pub trait HdkT: HdiT {
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+    fn get_meta(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<DhtMetadata>>>;
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -99,6 +101,7 @@ mockall::mock! {
             get_agent_activity_input: GetAgentActivityInput,
         ) -> ExternResult<AgentActivity>;
         fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+        fn query_count(&self, filter: ChainQueryFilter) -> ExternResult<usize>;
         // Ed25519
         fn sign(&self, sign: Sign) -> ExternResult<Signature>;
         fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -108,6 +111,7 @@ mockall::mock! {
         fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
         fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
         fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+        fn get_meta(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<DhtMetadata>>>;
         // CounterSigning
         fn accept_countersigning_preflight_request(
             &self,
@@ -280,6 +284,9 @@ impl HdkT for ErrHdk {
     fn query(&self, _: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         Self::err()
     }
+    fn query_count(&self, _: ChainQueryFilter) -> ExternResult<usize> {
+        Self::err()
+    }
     fn sign(&self, _: Sign) -> ExternResult<Signature> {
         Self::err()
     }
@@ -301,6 +308,9 @@ impl HdkT for ErrHdk {
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
+    fn get_meta(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<DhtMetadata>>> {
+        Self::err()
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -477,6 +487,9 @@ impl HdkT for HostHdk {
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         host_call::<ChainQueryFilter, Vec<Record>>(__hc__query_1, filter)
     }
+    fn query_count(&self, filter: ChainQueryFilter) -> ExternResult<usize> {
+        host_call::<ChainQueryFilter, usize>(__hc__query_count_1, filter)
+    }
     fn sign(&self, sign: Sign) -> ExternResult<Signature> {
         host_call::<Sign, Signature>(__hc__sign_1, sign)
     }
@@ -498,6 +511,9 @@ impl HdkT for HostHdk {
     fn get_details(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         host_call::<Vec<GetInput>, Vec<Option<Details>>>(__hc__get_details_1, get_inputs)
     }
+    fn get_meta(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<DhtMetadata>>> {
+        host_call::<Vec<GetInput>, Vec<Option<DhtMetadata>>>(__hc__get_meta_1, get_inputs)
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -153,6 +153,36 @@ where
         h.borrow().remote_signal(RemoteSignal {
             signal: ExternIO::encode(input).map_err(|e| wasm_error!(e))?,
             agents,
+            reliable: false,
+        })
+    })
+}
+
+/// ## Reliable Remote Signal
+/// Send a signal to a list of other agents, like [ `remote_signal` ],
+/// but make sure it is eventually delivered.
+///
+/// Signals that cannot be delivered right away are queued by this
+/// agent's conductor and retried whenever the recipient's agent info
+/// is seen again on the network. Queued signals are dropped once they
+/// are older than a day, or if too many are waiting to be delivered.
+///
+/// The recipient's conductor acknowledges each delivered signal and the
+/// state of every queued signal can be queried by the client with the
+/// `RemoteSignalDeliveries` app request.
+///
+/// ### Non-blocking
+/// This is a non-blocking call just like [ `remote_signal` ] and needs
+/// the same `recv_remote_signal` function and cap grant on the recipients.
+pub fn remote_signal_reliable<I>(input: I, agents: Vec<AgentPubKey>) -> ExternResult<()>
+where
+    I: serde::Serialize + std::fmt::Debug,
+{
+    HDK.with(|h| {
+        h.borrow().remote_signal(RemoteSignal {
+            signal: ExternIO::encode(input).map_err(|e| wasm_error!(e))?,
+            agents,
+            reliable: true,
         })
     })
 }
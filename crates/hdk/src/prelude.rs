@@ -6,6 +6,7 @@ pub use crate::capability::update_cap_grant;
 pub use crate::chain::get_agent_activity;
 pub use crate::chain::must_get_agent_activity;
 pub use crate::chain::query;
+pub use crate::chain::query_count;
 pub use crate::countersigning::accept_countersigning_preflight_request;
 pub use crate::countersigning::session_times_from_millis;
 pub use crate::ed25519::sign;
@@ -20,6 +21,7 @@ pub use crate::entry::delete;
 pub use crate::entry::delete_entry;
 pub use crate::entry::get;
 pub use crate::entry::get_details;
+pub use crate::entry::get_meta;
 pub use crate::entry::must_get_action;
 pub use crate::entry::must_get_entry;
 pub use crate::entry::must_get_valid_record;
@@ -51,6 +53,7 @@ pub use crate::p2p::call;
 pub use crate::p2p::call_remote;
 pub use crate::p2p::emit_signal;
 pub use crate::p2p::remote_signal;
+pub use crate::p2p::remote_signal_reliable;
 pub use crate::random::*;
 pub use crate::time::schedule;
 pub use crate::time::sleep;
@@ -127,6 +130,7 @@ macro_rules! holochain_externs {
             capability_info:1,
             get:1,
             get_details:1,
+            get_meta:1,
             get_links:1,
             get_link_details:1,
             get_agent_activity:1,
@@ -135,6 +139,7 @@ macro_rules! holochain_externs {
             must_get_action:1,
             accept_countersigning_preflight_request:1,
             query:1,
+            query_count:1,
             call_remote:1,
             block_agent:1,
             unblock_agent:1,
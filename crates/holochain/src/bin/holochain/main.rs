@@ -80,7 +80,7 @@ async fn async_main() {
         return;
     }
 
-    let config = get_conductor_config(&opt);
+    let (config_path, config) = get_conductor_config(&opt);
 
     if let Some(t) = &config.tracing_override {
         std::env::set_var("CUSTOM_FILTER", t);
@@ -91,7 +91,7 @@ async fn async_main() {
 
     kitsune_p2p_types::metrics::init_sys_info_poll();
 
-    let conductor = conductor_handle_from_config(&opt, config).await;
+    let conductor = conductor_handle_from_config(&opt, config_path, config).await;
 
     info!("Conductor successfully initialized.");
 
@@ -106,6 +106,10 @@ async fn async_main() {
     #[cfg(unix)]
     let _ = notify(true, &[NotifyState::Ready]);
 
+    // reload the config file on SIGHUP
+    #[cfg(unix)]
+    tokio::task::spawn(reload_config_on_hangup(conductor.clone()));
+
     // wait for a unix signal or ctrl-c instruction to
     // shutdown holochain
     tokio::signal::ctrl_c()
@@ -116,7 +120,7 @@ async fn async_main() {
     handle_shutdown(shutdown_result);
 }
 
-fn get_conductor_config(opt: &Opt) -> ConductorConfig {
+fn get_conductor_config(opt: &Opt) -> (ConfigFilePath, ConductorConfig) {
     let config_path = opt.config_path.clone();
     let config_path_default = config_path.is_none();
     let config_path: ConfigFilePath = config_path.map(Into::into).unwrap_or_default();
@@ -124,7 +128,7 @@ fn get_conductor_config(opt: &Opt) -> ConductorConfig {
 
     let config: ConductorConfig = if opt.interactive {
         // Load config, offer to create default config if missing
-        interactive::load_config_or_prompt_for_default(config_path)
+        interactive::load_config_or_prompt_for_default(config_path.clone())
             .expect("Could not load conductor config")
             .unwrap_or_else(|| {
                 println!("Cannot continue without configuration");
@@ -134,10 +138,14 @@ fn get_conductor_config(opt: &Opt) -> ConductorConfig {
         load_config(&config_path, config_path_default)
     };
 
-    config
+    (config_path, config)
 }
 
-async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> ConductorHandle {
+async fn conductor_handle_from_config(
+    opt: &Opt,
+    config_path: ConfigFilePath,
+    config: ConductorConfig,
+) -> ConductorHandle {
     // read the passphrase to prepare for usage
     let passphrase = match &config.keystore {
         KeystoreConfig::DangerTestKeystore => None,
@@ -171,6 +179,7 @@ async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> Con
     // Initialize the Conductor
     match Conductor::builder()
         .config(config)
+        .config_path(config_path.into())
         .passphrase(passphrase)
         .build()
         .await
@@ -183,6 +192,25 @@ async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> Con
     }
 }
 
+/// Reload the conductor config each time the process receives SIGHUP
+#[cfg(unix)]
+async fn reload_config_on_hangup(conductor: ConductorHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!("Could not handle SIGHUP: {:?}", e);
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        if let Err(e) = conductor.clone().reload_config().await {
+            error!("Could not reload the conductor config: {:?}", e);
+        }
+    }
+}
+
 /// Load config, throw friendly error on failure
 fn load_config(config_path: &ConfigFilePath, config_path_default: bool) -> ConductorConfig {
     match ConductorConfig::load_yaml(config_path.as_ref()) {
@@ -201,6 +201,24 @@ pub trait CellConductorReadHandleT: Send + Sync {
         expires: Timestamp,
     ) -> ConductorApiResult<WitnessNonceResult>;
 
+    /// Count a use of a capability grant committed by this cell, unless it
+    /// has already been used `max_uses` times. Returns whether it was counted.
+    async fn use_cap_grant(
+        &self,
+        grant_hash: EntryHash,
+        max_uses: u32,
+        expires_at: Option<Timestamp>,
+    ) -> ConductorApiResult<bool>;
+
+    /// Send a remote signal from this cell in reliable mode, queueing it
+    /// for redelivery if the recipient can't be reached.
+    fn send_reliable_remote_signal(
+        &self,
+        to_agent: AgentPubKey,
+        zome_name: ZomeName,
+        signal: ExternIO,
+    );
+
     /// Find the first cell ID across all apps the given cell id is in that
     /// is assigned to the given role.
     async fn find_cell_with_role_alongside_cell(
@@ -259,6 +277,32 @@ impl CellConductorReadHandleT for CellConductorApi {
             .await?)
     }
 
+    async fn use_cap_grant(
+        &self,
+        grant_hash: EntryHash,
+        max_uses: u32,
+        expires_at: Option<Timestamp>,
+    ) -> ConductorApiResult<bool> {
+        Ok(self
+            .conductor_handle
+            .use_cap_grant(self.cell_id.clone(), grant_hash, max_uses, expires_at)
+            .await?)
+    }
+
+    fn send_reliable_remote_signal(
+        &self,
+        to_agent: AgentPubKey,
+        zome_name: ZomeName,
+        signal: ExternIO,
+    ) {
+        self.conductor_handle.send_reliable_remote_signal(
+            self.cell_id.clone(),
+            to_agent,
+            zome_name,
+            signal,
+        )
+    }
+
     async fn find_cell_with_role_alongside_cell(
         &self,
         cell_id: &CellId,
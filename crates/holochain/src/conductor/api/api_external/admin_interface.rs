@@ -74,6 +74,10 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AdminInterfacesAdded)
             }
+            ReloadConfig => {
+                let reload = self.conductor_handle.clone().reload_config().await?;
+                Ok(AdminResponse::ConfigReloaded(reload))
+            }
             RegisterDna(payload) => {
                 trace!(register_dna_payload = ?payload);
                 let RegisterDnaPayload { modifiers, source } = *payload;
@@ -160,18 +164,41 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppUninstalled)
             }
+            SetAppQuotas {
+                installed_app_id,
+                quotas,
+            } => {
+                self.conductor_handle
+                    .set_app_quotas(installed_app_id, quotas)
+                    .await?;
+                Ok(AdminResponse::AppQuotasSet)
+            }
             ListDnas => {
                 let dna_list = self.conductor_handle.list_dnas();
                 Ok(AdminResponse::DnasListed(dna_list))
             }
-            GenerateAgentPubKey => {
-                let agent_pub_key = self
+            GenerateAgentPubKey(derivation_path) => {
+                let keystore = self.conductor_handle.keystore();
+                let agent_pub_key = match derivation_path {
+                    Some(derivation_path) => keystore.derive_agent_pub_key(derivation_path).await?,
+                    None => keystore.new_sign_keypair_random().await?,
+                };
+                Ok(AdminResponse::AgentPubKeyGenerated(agent_pub_key))
+            }
+            ExportDeviceSeed { passphrase } => {
+                let seed = self
                     .conductor_handle
                     .keystore()
-                    .clone()
-                    .new_sign_keypair_random()
+                    .export_device_seed(passphrase.0.into_bytes().into())
                     .await?;
-                Ok(AdminResponse::AgentPubKeyGenerated(agent_pub_key))
+                Ok(AdminResponse::DeviceSeedExported(seed))
+            }
+            ImportDeviceSeed { seed, passphrase } => {
+                self.conductor_handle
+                    .keystore()
+                    .import_device_seed(seed, passphrase.0.into_bytes().into())
+                    .await?;
+                Ok(AdminResponse::DeviceSeedImported)
             }
             ListCellIds => {
                 let cell_ids = self
@@ -281,6 +308,20 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::ZomeCallCapabilityGranted)
             }
+            ListCapabilityGrants {
+                cell_id,
+                include_revoked,
+            } => Ok(AdminResponse::CapabilityGrantsInfo(
+                self.conductor_handle
+                    .list_capability_grants(cell_id, include_revoked)
+                    .await?,
+            )),
+            RevokeZomeCallCapability { action_hash } => {
+                self.conductor_handle
+                    .revoke_zome_call_capability(action_hash)
+                    .await?;
+                Ok(AdminResponse::ZomeCallCapabilityRevoked)
+            }
             DeleteCloneCell(payload) => {
                 self.conductor_handle
                     .clone()
@@ -291,6 +332,54 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
             StorageInfo => Ok(AdminResponse::StorageInfo(
                 self.conductor_handle.storage_info().await?,
             )),
+            ClearDhtCache { dna_hash } => Ok(AdminResponse::DhtCacheCleared(
+                self.conductor_handle.clear_dht_cache(&dna_hash).await?,
+            )),
+            ExportDhtOps {
+                dna_hash,
+                signing_agent,
+                path,
+                filter,
+            } => {
+                let op_count = self
+                    .conductor_handle
+                    .export_dht_ops(dna_hash, signing_agent, path, filter)
+                    .await?;
+                Ok(AdminResponse::DhtOpsExported(op_count))
+            }
+            ImportDhtOps { path } => {
+                let (dna_hash, signer, op_count) =
+                    self.conductor_handle.import_dht_ops(path).await?;
+                Ok(AdminResponse::DhtOpsImported {
+                    dna_hash,
+                    signer,
+                    op_count,
+                })
+            }
+            SetZomeCallRecording { cell_id, enabled } => {
+                self.conductor_handle
+                    .set_zome_call_recording(cell_id, enabled);
+                Ok(AdminResponse::ZomeCallRecordingSet)
+            }
+            GetZomeCallRecordings { cell_id } => Ok(AdminResponse::ZomeCallRecordings(
+                self.conductor_handle.zome_call_recordings(&cell_id),
+            )),
+            ListCountersigningSessions => Ok(AdminResponse::CountersigningSessions(
+                self.conductor_handle.list_countersigning_sessions().await?,
+            )),
+            AbandonCountersigningSession { cell_id } => {
+                Ok(AdminResponse::CountersigningSessionAbandoned(
+                    self.conductor_handle
+                        .abandon_countersigning_session(cell_id)
+                        .await?,
+                ))
+            }
+            IntrospectCell { cell_id } => Ok(AdminResponse::CellIntrospection(
+                self.conductor_handle.introspect_cell(cell_id).await?,
+            )),
+            GetWasmMeteringStats { dna_hash } => Ok(AdminResponse::WasmMeteringStats(
+                self.conductor_handle.wasm_metering_stats(dna_hash.as_ref()),
+            )),
         }
     }
 }
@@ -457,6 +546,49 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn generate_derived_agent_pub_key() -> Result<()> {
+        holochain_trace::test_run().ok();
+        let env_dir = test_db_dir();
+        let handle = Conductor::builder().test(env_dir.path(), &[]).await?;
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let generate = |derivation_path: Option<Vec<u32>>| {
+            let admin_api = admin_api.clone();
+            async move {
+                match admin_api
+                    .handle_admin_request(AdminRequest::GenerateAgentPubKey(derivation_path))
+                    .await
+                {
+                    AdminResponse::AgentPubKeyGenerated(agent) => agent,
+                    r => panic!("unexpected response {:?}", r),
+                }
+            }
+        };
+
+        // The same path derives the same key, other paths and random keys differ.
+        let derived = generate(Some(vec![1, 0])).await;
+        assert_eq!(derived, generate(Some(vec![1, 0])).await);
+        assert_ne!(derived, generate(Some(vec![1, 1])).await);
+        assert_ne!(derived, generate(None).await);
+
+        // A device seed has been created, so another one can't be imported.
+        assert_matches!(
+            admin_api
+                .handle_admin_request(AdminRequest::ImportDeviceSeed {
+                    seed: vec![],
+                    passphrase: Passphrase("passphrase".into()),
+                })
+                .await,
+            AdminResponse::Error(_)
+        );
+
+        tokio::time::timeout(std::time::Duration::from_secs(1), handle.shutdown())
+            .await
+            .ok();
+        Ok(())
+    }
+
     // @todo fix test by using new InstallApp call
     // #[tokio::test(flavor = "multi_thread")]
     // async fn install_list_dna_app() {
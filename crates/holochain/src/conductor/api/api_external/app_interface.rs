@@ -5,6 +5,7 @@ use crate::conductor::api::error::SerializationError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
 use crate::conductor::ConductorHandle;
+use crate::core::workflow::ZomeCallResult;
 
 use holochain_serialized_bytes::prelude::*;
 
@@ -50,6 +51,35 @@ impl RealAppInterfaceApi {
     }
 }
 
+/// Turn the result of a zome call made over an app interface into the
+/// response, using `ok` for a successful call.
+fn zome_call_app_response(
+    result: ZomeCallResult,
+    call: &ZomeCall,
+    ok: impl FnOnce(ExternIO) -> AppResponse,
+) -> AppResponse {
+    match result {
+        Ok(ZomeCallResponse::Ok(output)) => ok(output),
+        Ok(ZomeCallResponse::Unauthorized(zome_call_authorization, _, zome_name, fn_name, _)) => AppResponse::Error(
+            ExternalApiWireError::ZomeCallUnauthorized(format!(
+                "Call was not authorized with reason {:?}, cap secret {:?} to call the function {} in zome {}",
+                zome_call_authorization, call.cap_secret, fn_name, zome_name
+            )),
+        ),
+        Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
+            "Interface zome calls should never be routed to the network. This is a bug. Got {}",
+            e
+        ),
+        Ok(ZomeCallResponse::CountersigningSession(e)) => AppResponse::Error(
+            ExternalApiWireError::CountersigningSessionError(format!(
+                "A countersigning session has failed to start on this zome call because: {}",
+                e
+            )),
+        ),
+        Err(e) => AppResponse::Error(e.into()),
+    }
+}
+
 #[async_trait::async_trait]
 impl AppInterfaceApi for RealAppInterfaceApi {
     /// Routes the [AppRequest] to the [AppResponse]
@@ -64,26 +94,22 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     .await?,
             )),
             AppRequest::CallZome(call) => {
-                match self.conductor_handle.call_zome(*call.clone()).await? {
-                    Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCalled(Box::new(output))),
-                    Ok(ZomeCallResponse::Unauthorized(zome_call_authorization, _, zome_name, fn_name, _)) => Ok(AppResponse::Error(
-                        ExternalApiWireError::ZomeCallUnauthorized(format!(
-                            "Call was not authorized with reason {:?}, cap secret {:?} to call the function {} in zome {}",
-                            zome_call_authorization, call.cap_secret, fn_name, zome_name
-                        )),
-                    )),
-                    Ok(ZomeCallResponse::NetworkError(e)) => unreachable!(
-                        "Interface zome calls should never be routed to the network. This is a bug. Got {}",
-                        e
-                    ),
-                    Ok(ZomeCallResponse::CountersigningSession(e)) => Ok(AppResponse::Error(
-                        ExternalApiWireError::CountersigningSessionError(format!(
-                            "A countersigning session has failed to start on this zome call because: {}",
-                            e
-                        )),
-                    )),
-                    Err(e) => Ok(AppResponse::Error(e.into())),
-                }
+                let result = self.conductor_handle.call_zome(*call.clone()).await?;
+                Ok(zome_call_app_response(result, &call, |output| {
+                    AppResponse::ZomeCalled(Box::new(output))
+                }))
+            }
+            AppRequest::CallZomeMetered(call) => {
+                let (result, metering_points) = self
+                    .conductor_handle
+                    .call_zome_metered(*call.clone())
+                    .await?;
+                Ok(zome_call_app_response(result, &call, |output| {
+                    AppResponse::ZomeCalledMetered {
+                        output: Box::new(output),
+                        metering_points,
+                    }
+                }))
             }
             AppRequest::CreateCloneCell(payload) => {
                 let clone_cell = self
@@ -115,6 +141,22 @@ impl AppInterfaceApi for RealAppInterfaceApi {
             AppRequest::ListWasmHostFunctions => Ok(AppResponse::ListWasmHostFunctions(
                 self.conductor_handle.list_wasm_host_functions().await?,
             )),
+            AppRequest::IntrospectCell {
+                installed_app_id,
+                cell_id,
+            } => Ok(AppResponse::CellIntrospection(
+                self.conductor_handle
+                    .introspect_app_cell(&installed_app_id, cell_id)
+                    .await?,
+            )),
+            AppRequest::RemoteSignalDeliveries {
+                installed_app_id,
+                cell_id,
+            } => Ok(AppResponse::RemoteSignalDeliveries(
+                self.conductor_handle
+                    .remote_signal_deliveries(&installed_app_id, cell_id)
+                    .await?,
+            )),
         }
     }
 }
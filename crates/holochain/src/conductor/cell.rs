@@ -9,12 +9,16 @@ use super::interface::SignalBroadcaster;
 use super::space::Space;
 use super::ConductorHandle;
 use crate::conductor::api::CellConductorApi;
+use crate::conductor::api::CellConductorApiT;
 use crate::conductor::cell::error::CellResult;
 use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::host_call_tape::HostCallTape;
+use crate::core::ribosome::metering::ZomeCallMeter;
 use crate::core::ribosome::real_ribosome::RealRibosome;
+use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::countersigning_workflow::countersigning_success;
@@ -566,6 +570,7 @@ impl Cell {
                     &self.space.countersigning_workspace,
                     self.queue_triggers.countersigning.clone(),
                 )
+                .await
                 .map_err(Box::new)?;
                 Ok(())
             }
@@ -636,14 +641,17 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let db = self.space.dht_db.clone();
+        authority::handle_get_meta(db.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -813,6 +821,25 @@ impl Cell {
         &self,
         call: ZomeCall,
         workspace_lock: Option<SourceChainWorkspace>,
+    ) -> CellResult<ZomeCallResult> {
+        self.call_zome_inner(call, workspace_lock, None).await
+    }
+
+    /// Call a zome function, and also return the wasm metering points
+    /// used by the function itself.
+    pub async fn call_zome_metered(&self, call: ZomeCall) -> CellResult<(ZomeCallResult, u64)> {
+        let meter = ZomeCallMeter::new();
+        let result = self
+            .call_zome_inner(call, None, Some(meter.clone()))
+            .await?;
+        Ok((result, meter.points()))
+    }
+
+    async fn call_zome_inner(
+        &self,
+        call: ZomeCall,
+        workspace_lock: Option<SourceChainWorkspace>,
+        meter: Option<ZomeCallMeter>,
     ) -> CellResult<ZomeCallResult> {
         // Only check if init has run if this call is not coming from
         // an already running init call.
@@ -859,6 +886,7 @@ impl Cell {
             signal_tx,
             conductor_handle,
             is_root_zome_call,
+            meter,
         };
         Ok(call_zome_workflow(
             workspace_lock,
@@ -872,6 +900,63 @@ impl Cell {
         .map_err(Box::new)?)
     }
 
+    /// Re-execute a recorded zome call against the recorded results of its
+    /// host function calls. Authorization isn't checked again, and the
+    /// workspace of the replayed call is never flushed.
+    pub(crate) async fn replay_zome_call(
+        &self,
+        recording: ZomeCallRecording,
+    ) -> CellResult<ZomeCallResult> {
+        let keystore = self.conductor_api.keystore().clone();
+        let ribosome = self.get_ribosome()?;
+        let ZomeCallUnsigned {
+            provenance,
+            cell_id,
+            zome_name,
+            fn_name,
+            cap_secret,
+            payload,
+            nonce,
+            expires_at,
+        } = recording.call;
+        let call = ZomeCall {
+            cell_id,
+            zome_name,
+            fn_name,
+            cap_secret,
+            payload,
+            provenance,
+            signature: recording.signature,
+            nonce,
+            expires_at,
+        };
+        let invocation =
+            ZomeCallInvocation::try_from_interface_call(self.conductor_api.clone(), call).await?;
+        let workspace = SourceChainWorkspace::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.cache().clone(),
+            keystore.clone(),
+            self.id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await?;
+        let host_access = ZomeCallHostAccess::new(
+            workspace.into(),
+            keystore,
+            self.holochain_p2p_cell.clone(),
+            self.signal_broadcaster(),
+            CellConductorApi::new(self.conductor_handle.clone(), self.id.clone())
+                .into_call_zome_handle(),
+        )
+        .with_host_call_tape(HostCallTape::replay(recording.host_calls));
+        Ok(tokio::task::spawn_blocking(move || {
+            ribosome.call_zome_function(host_access, invocation)
+        })
+        .await?)
+    }
+
     /// Check if each Zome's init callback has been run, and if not, run it.
     #[tracing::instrument(skip(self))]
     async fn check_or_run_zome_init(&self) -> CellResult<()> {
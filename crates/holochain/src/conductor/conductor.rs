@@ -67,6 +67,7 @@ use crate::core::queue_consumer::QueueConsumerMap;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CHANNEL_BOUND;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CONCURRENT_LIMIT;
+use crate::core::ribosome::module_cache::ModuleDiskCache;
 use crate::core::ribosome::RibosomeT;
 use crate::core::workflow::ZomeCallResult;
 use crate::{
@@ -86,6 +87,7 @@ use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::IntegrationStateDump;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::WasmMeteringStats;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::lair_keystore::spawn_lair_keystore_in_proc;
 use holochain_keystore::test_keystore::spawn_test_keystore;
@@ -107,7 +109,7 @@ use holochain_types::prelude::{wasm, *};
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use rusqlite::Transaction;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tokio::sync::mpsc::error::SendError;
@@ -144,7 +146,16 @@ pub use scheduler_impls::*;
 pub use startup_shutdown_impls::*;
 pub use state_impls::*;
 
+mod app_quotas;
+mod cap_grants;
+mod countersigning_session;
+mod dht_cache;
+mod dht_op_export;
 mod graft_records_onto_source_chain;
+mod introspection;
+mod reload_config;
+mod remote_signal_outbox;
+mod zome_call_recording;
 
 /// A list of Cells which failed to start, and why
 pub type CellStartupErrors = Vec<(CellId, CellError)>;
@@ -190,6 +201,14 @@ pub struct Conductor {
     /// The config used to create this Conductor
     pub config: ConductorConfig,
 
+    /// The file the config was loaded from, if any, which is read again
+    /// when the config is reloaded.
+    config_path: Option<std::path::PathBuf>,
+
+    /// The config as it applies to the running conductor, which differs from
+    /// [`Conductor::config`] by the changes applied when reloading the config.
+    running_config: RwShare<ConductorConfig>,
+
     /// The map of dna hash spaces.
     pub(crate) spaces: Spaces,
 
@@ -227,6 +246,16 @@ pub struct Conductor {
     scheduler: Arc<parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>>,
 
     pub(crate) services: RwShare<Option<ConductorServices>>,
+
+    /// The most recent zome calls made to each cell which has zome call
+    /// recording switched on. Recording is on for exactly the cells in this map.
+    zome_call_recordings: RwShare<HashMap<CellId, VecDeque<ZomeCallRecording>>>,
+
+    /// The remote signals sent in reliable mode, kept until they expire.
+    remote_signal_outbox: remote_signal_outbox::RemoteSignalOutbox,
+
+    /// The quotas of the installed apps and the zome calls running in them.
+    app_quotas: app_quotas::AppQuotaTracker,
 }
 
 impl Conductor {
@@ -251,6 +280,7 @@ mod startup_shutdown_impls {
         #[allow(clippy::too_many_arguments)]
         pub(crate) fn new(
             config: ConductorConfig,
+            config_path: Option<std::path::PathBuf>,
             ribosome_store: RwShare<RibosomeStore>,
             keystore: MetaLairClient,
             holochain_p2p: holochain_p2p::HolochainP2pRef,
@@ -258,10 +288,17 @@ mod startup_shutdown_impls {
             post_commit: tokio::sync::mpsc::Sender<PostCommitArgs>,
             outcome_sender: OutcomeSender,
         ) -> Self {
+            let remote_signal_outbox = remote_signal_outbox::RemoteSignalOutbox::new(
+                spaces.conductor_db.clone(),
+                keystore.clone(),
+                holochain_p2p.clone(),
+            );
             Self {
                 spaces,
                 running_cells: RwShare::new(HashMap::new()),
+                running_config: RwShare::new(config.clone()),
                 config,
+                config_path,
                 shutting_down: Arc::new(AtomicBool::new(false)),
                 app_interfaces: RwShare::new(HashMap::new()),
                 task_manager: TaskManagerClient::new(outcome_sender),
@@ -270,10 +307,13 @@ mod startup_shutdown_impls {
                 admin_websocket_ports: RwShare::new(Vec::new()),
                 scheduler: Arc::new(parking_lot::Mutex::new(None)),
                 ribosome_store,
+                remote_signal_outbox,
                 keystore,
                 holochain_p2p,
                 post_commit,
                 services: RwShare::new(None),
+                zome_call_recordings: RwShare::new(HashMap::new()),
+                app_quotas: app_quotas::AppQuotaTracker::new(),
             }
         }
 
@@ -318,7 +358,21 @@ mod startup_shutdown_impls {
             outcome_rx: OutcomeReceiver,
             admin_configs: Vec<AdminInterfaceConfig>,
         ) -> ConductorResult<CellStartupErrors> {
+            match ModuleDiskCache::new(self.root_db_dir().as_ref()) {
+                Ok(cache) => self
+                    .ribosome_store()
+                    .share_mut(|d| d.set_module_disk_cache(cache)),
+                Err(e) => tracing::warn!(?e, "Compiled wasm modules won't be cached on disk"),
+            }
             self.load_dnas().await?;
+            self.gc_module_cache();
+            if let Err(e) = self.prune_cap_grant_uses().await {
+                tracing::warn!(?e, "Failed to forget the uses of deleted cap grants");
+            }
+            if let Err(e) = self.backfill_link_target_hashes().await {
+                tracing::warn!(?e, "Failed to fill in the targets of links");
+            }
+            self.app_quotas.refresh(&self.get_state().await?);
 
             // Start the task manager
             self.outcomes_task.share_mut(|lock| {
@@ -552,8 +606,8 @@ mod dna_impls {
                 .share_mut(|d| d.add_entry_defs(entry_defs));
         }
 
-        pub(crate) fn add_ribosome_to_store(&self, ribosome: RealRibosome) {
-            self.ribosome_store.share_mut(|d| d.add_ribosome(ribosome));
+        pub(crate) fn add_ribosome_to_store(&self, ribosome: RealRibosome) -> RealRibosome {
+            self.ribosome_store.share_mut(|d| d.add_ribosome(ribosome))
         }
 
         pub(crate) async fn load_wasms_into_dna_files(
@@ -734,9 +788,37 @@ mod dna_impls {
             let ribosome = RealRibosome::new(dna)?;
             let entry_defs = self.register_dna_wasm(ribosome.clone()).await?;
             self.register_dna_entry_defs(entry_defs);
-            self.add_ribosome_to_store(ribosome);
+            let ribosome = self.add_ribosome_to_store(ribosome);
+            precompile_in_background(ribosome);
             Ok(())
         }
+
+        /// Remove compiled modules from the disk cache
+        /// which no registered DNA uses any more.
+        pub(crate) fn gc_module_cache(&self) {
+            let (cache, referenced) = self
+                .ribosome_store()
+                .share_ref(|d| (d.module_disk_cache().cloned(), d.wasm_hashes()));
+            let cache = match cache {
+                Some(cache) => cache,
+                None => return,
+            };
+            match cache.gc(referenced) {
+                Ok(0) => (),
+                Ok(removed) => tracing::info!("Removed {} unused compiled wasm modules", removed),
+                Err(e) => tracing::warn!(?e, "Failed to clean up compiled wasm modules"),
+            }
+        }
+    }
+
+    /// Compile the zomes of a DNA on a blocking thread,
+    /// so the first calls into them don't have to.
+    pub(super) fn precompile_in_background(ribosome: RealRibosome) {
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = ribosome.precompile_zomes() {
+                tracing::warn!(dna = %ribosome.dna_hash(), ?e, "Failed to precompile zomes");
+            }
+        });
     }
 }
 
@@ -800,6 +882,72 @@ mod network_impls {
             .await?)
         }
 
+        /// Count a use of a capability grant with limited uses.
+        pub(crate) async fn use_cap_grant(
+            &self,
+            cell_id: CellId,
+            grant_hash: EntryHash,
+            max_uses: u32,
+            expires_at: Option<Timestamp>,
+        ) -> ConductorResult<bool> {
+            Ok(holochain_state::cap_grant_use::use_cap_grant(
+                &self.spaces.conductor_db,
+                cell_id,
+                grant_hash,
+                max_uses,
+                expires_at,
+            )
+            .await?)
+        }
+
+        /// Forget the counted uses of the capability grants
+        /// which the installed cells have deleted.
+        pub(crate) async fn prune_cap_grant_uses(&self) -> ConductorResult<()> {
+            let cell_ids: HashSet<CellId> = self
+                .get_state()
+                .await?
+                .installed_apps()
+                .values()
+                .flat_map(|app| app.all_cells().cloned())
+                .collect();
+            for cell_id in cell_ids {
+                let authored_db = self.spaces.authored_db(cell_id.dna_hash())?;
+                let pruned = holochain_state::cap_grant_use::prune_cap_grant_uses(
+                    &self.spaces.conductor_db,
+                    &authored_db,
+                    cell_id.clone(),
+                )
+                .await?;
+                if pruned > 0 {
+                    tracing::debug!(?cell_id, "Forgot the uses of {} deleted cap grants", pruned);
+                }
+            }
+            Ok(())
+        }
+
+        /// Fill in the link targets which chain queries by linked agent
+        /// read, for links authored before they were stored separately.
+        pub(crate) async fn backfill_link_target_hashes(&self) -> ConductorResult<()> {
+            let dna_hashes: HashSet<DnaHash> = self
+                .get_state()
+                .await?
+                .installed_apps()
+                .values()
+                .flat_map(|app| app.all_cells().map(|cell_id| cell_id.dna_hash().clone()))
+                .collect();
+            for dna_hash in dna_hashes {
+                let filled = self
+                    .spaces
+                    .authored_db(&dna_hash)?
+                    .async_commit(holochain_state::mutations::backfill_link_target_hashes)
+                    .await?;
+                if filled > 0 {
+                    tracing::debug!(?dna_hash, "Filled in the targets of {} links", filled);
+                }
+            }
+            Ok(())
+        }
+
         /// Block some target.
         pub async fn block(&self, input: Block) -> DatabaseResult<()> {
             self.spaces.block(input).await
@@ -845,6 +993,11 @@ mod network_impls {
             &self,
             payload: &NetworkInfoRequestPayload,
         ) -> ConductorResult<Vec<NetworkInfo>> {
+            use crate::core::workflow::publish_dht_ops_workflow::num_still_needing_publish;
+            use holochain_conductor_api::PeerGossipInfo;
+            use holochain_p2p::dht::ArqStrat;
+            use holochain_p2p::dht_arc::DhtArcSet;
+            use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::Tx2Cert;
             use holochain_sqlite::sql::sql_cell::SUM_OF_RECEIVED_BYTES_SINCE_TIMESTAMP;
 
             let NetworkInfoRequestPayload {
@@ -866,10 +1019,10 @@ mod network_impls {
                 let current_number_of_peers = conn.p2p_count_agents()?;
 
                 // query arc size and extrapolated coverage and estimate total peers
-                let (arc_size, total_network_peers) = match conn
+                let (arc_size, total_network_peers, extrapolated_coverage) = match conn
                     .p2p_get_agent(&KitsuneAgent::new(agent_pub_key.get_raw_36().to_vec()))?
                 {
-                    None => (0.0, 0),
+                    None => (0.0, 0, None),
                     Some(agent) => {
                         let arc_size = agent.storage_arc.coverage();
                         let agents_in_arc = conn.p2p_gossip_query_agents(
@@ -883,10 +1036,35 @@ mod network_impls {
                         } else {
                             (number_of_agents_in_arc as f64 / arc_size) as u32
                         };
-                        (arc_size, total_network_peers)
+                        let coverage = conn.p2p_extrapolated_coverage(DhtArcSet::from(
+                            agent.storage_arc.inner(),
+                        ))?;
+                        let extrapolated_coverage = if coverage.is_empty() {
+                            None
+                        } else {
+                            Some(coverage.iter().sum::<f64>() / coverage.len() as f64)
+                        };
+                        (arc_size, total_network_peers, extrapolated_coverage)
                     }
                 };
 
+                // the arc converges on the size at which it's held by
+                // the midline of the redundancy target
+                let redundancy_target = self
+                    .config
+                    .network
+                    .clone()
+                    .unwrap_or_default()
+                    .tuning_params
+                    .gossip_redundancy_target;
+                let target_arc_size = if total_network_peers == 0 {
+                    1.0
+                } else {
+                    (ArqStrat::from_params(redundancy_target).midline_coverage()
+                        / total_network_peers as f64)
+                        .min(1.0)
+                };
+
                 // get sum of bytes from dht and cache db since last time
                 // request was made or since the beginning of time
                 let last_time_queried = match last_time_queried {
@@ -943,6 +1121,63 @@ mod network_impls {
                     })
                     .count() as u32;
 
+                // summarize the remembered gossip rounds with each peer node
+                let peer_gossip_info = {
+                    let now = tokio::time::Instant::now();
+                    let timestamp_now = Timestamp::now();
+                    diagnostics
+                        .metrics
+                        .read()
+                        .peer_node_histories()
+                        .iter()
+                        .map(|(node_id, node_history)| {
+                            let rounds = &node_history.completed_rounds;
+                            let errored_rounds = rounds.iter().filter(|round| round.error).count();
+                            PeerGossipInfo {
+                                node_id: Tx2Cert::from(node_id.to_vec()).as_str().to_string(),
+                                last_round_time: rounds
+                                    .iter()
+                                    .map(|round| round.end_time)
+                                    .max()
+                                    .map(|end_time| {
+                                        timestamp_now.saturating_sub(
+                                            &now.saturating_duration_since(end_time),
+                                        )
+                                    }),
+                                completed_rounds: (rounds.len() - errored_rounds) as u32,
+                                errored_rounds: errored_rounds as u32,
+                                bytes_in: node_history.bytes_in,
+                                bytes_out: node_history.bytes_out,
+                                round_in_progress: node_history.current_round.is_some(),
+                            }
+                        })
+                        .collect()
+                };
+
+                // count the authored ops that will be published again
+                let publish_queue_length = self
+                    .get_or_create_authored_db(dna)?
+                    .async_reader(|txn| num_still_needing_publish(&txn))
+                    .await? as u32;
+
+                // count the ops from the network that are still being
+                // validated or integrated
+                let validation_backlog = dht_db
+                    .async_reader(|txn| {
+                        let mut stmt = txn.prepare(
+                            "
+                            SELECT type, COUNT(hash) FROM DhtOp
+                            WHERE when_integrated IS NULL
+                            GROUP BY type
+                            ",
+                        )?;
+                        let backlog = stmt
+                            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                            .collect::<Result<HashMap<DhtOpType, u32>, _>>()?;
+                        DatabaseResult::Ok(backlog)
+                    })
+                    .await?;
+
                 ConductorResult::Ok(NetworkInfo {
                     fetch_pool_info,
                     current_number_of_peers,
@@ -950,6 +1185,11 @@ mod network_impls {
                     total_network_peers,
                     bytes_since_last_time_queried,
                     completed_rounds_since_last_time_queried,
+                    target_arc_size,
+                    extrapolated_coverage,
+                    peer_gossip_info,
+                    publish_queue_length,
+                    validation_backlog,
                 })
             }))
             .await
@@ -985,6 +1225,7 @@ mod network_impls {
 
             Ok(StorageInfo {
                 blobs: app_data_blobs,
+                apps: app_quotas::app_storage_info(self, &state).await?,
             })
         }
 
@@ -1038,6 +1279,14 @@ mod network_impls {
                 PutAgentInfoSigned {
                     peer_data, respond, ..
                 } => {
+                    use holochain_p2p::AgentPubKeyExt;
+                    let agents: Vec<AgentPubKey> = peer_data
+                        .iter()
+                        .map(|info| AgentPubKey::from_kitsune(&info.agent))
+                        .collect();
+                    // Agents coming back online may have reliable remote
+                    // signals waiting for them.
+                    self.remote_signal_outbox.retry(&dna_hash, &agents);
                     let sender = self.p2p_batch_sender(&dna_hash);
                     let (result_sender, response) = tokio::sync::oneshot::channel();
                     let _ = sender
@@ -1225,9 +1474,21 @@ mod network_impls {
         /// Invoke a zome function on a Cell
         pub async fn call_zome(&self, call: ZomeCall) -> ConductorApiResult<ZomeCallResult> {
             let cell = self.cell_by_id(&call.cell_id, true).await?;
+            let _permit = self.app_quotas.start_zome_call(&call.cell_id)?;
             Ok(cell.call_zome(call, None).await?)
         }
 
+        /// Make a zome call, and also return the wasm metering points
+        /// used by the zome function itself.
+        pub async fn call_zome_metered(
+            &self,
+            call: ZomeCall,
+        ) -> ConductorApiResult<(ZomeCallResult, u64)> {
+            let cell = self.cell_by_id(&call.cell_id, true).await?;
+            let _permit = self.app_quotas.start_zome_call(&call.cell_id)?;
+            Ok(cell.call_zome_metered(call).await?)
+        }
+
         pub(crate) async fn call_zome_with_workspace(
             &self,
             call: ZomeCall,
@@ -1319,6 +1580,7 @@ mod app_impls {
                 installed_app_id,
                 membrane_proofs,
                 network_seed,
+                quotas,
             } = payload;
 
             let bundle = {
@@ -1369,7 +1631,8 @@ mod app_impls {
             crate::conductor::conductor::genesis_cells(self.clone(), cells_to_create).await?;
 
             let roles = ops.role_assignments;
-            let app = InstalledAppCommon::new(installed_app_id, agent_key, roles, manifest)?;
+            let mut app = InstalledAppCommon::new(installed_app_id, agent_key, roles, manifest)?;
+            app.set_quotas(quotas);
 
             // Update the db
             let stopped_app = self.add_disabled_app_to_db(app).await?;
@@ -2017,7 +2280,9 @@ mod state_impls {
         where
             F: FnOnce(ConductorState) -> ConductorResult<ConductorState> + 'static,
         {
-            self.spaces.update_state(f).await
+            let state = self.spaces.update_state(f).await?;
+            self.app_quotas.refresh(&state);
+            Ok(state)
         }
 
         /// Update the internal state with a pure function mapping old state to new,
@@ -2032,7 +2297,9 @@ mod state_impls {
             O: Send + 'static,
         {
             self.check_running()?;
-            self.spaces.update_state_prime(f).await
+            let (state, output) = self.spaces.update_state_prime(f).await?;
+            self.app_quotas.refresh(&state);
+            Ok((state, output))
         }
     }
 }
@@ -2138,10 +2405,29 @@ mod misc_impls {
 
             let cell = self.cell_by_id(&cell_id, false).await?;
             source_chain.flush(cell.holochain_p2p_dna()).await?;
+            self.recount_authored(&cell_id);
 
             Ok(())
         }
 
+        /// List the zome call capability grants on a cell's chain.
+        pub async fn list_capability_grants(
+            &self,
+            cell_id: CellId,
+            include_revoked: bool,
+        ) -> ConductorApiResult<Vec<holochain_conductor_api::CapGrantInfo>> {
+            cap_grants::list_cap_grants(self, cell_id, include_revoked).await
+        }
+
+        /// Revoke a zome call capability grant by deleting it from the chain
+        /// of the running cell which created it.
+        pub async fn revoke_zome_call_capability(
+            &self,
+            action_hash: ActionHash,
+        ) -> ConductorApiResult<()> {
+            cap_grants::revoke_cap_grant(self, action_hash).await
+        }
+
         /// Create a JSON dump of the cell's state
         pub async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String> {
             let cell = self.cell_by_id(cell_id, false).await?;
@@ -2229,8 +2515,17 @@ mod misc_impls {
                     .push(agent_info_signed);
             }
             for (space, agent_infos) in space_map {
-                let db = self.p2p_agents_db(&DnaHash::from_kitsune(&space));
+                let dna_hash = DnaHash::from_kitsune(&space);
+                let db = self.p2p_agents_db(&dna_hash);
                 inject_agent_infos(db, agent_infos.iter()).await?;
+                // Agents coming back online may have reliable remote
+                // signals waiting for them.
+                use holochain_p2p::AgentPubKeyExt;
+                let agents: Vec<AgentPubKey> = agent_infos
+                    .iter()
+                    .map(|info| AgentPubKey::from_kitsune(&info.agent))
+                    .collect();
+                self.remote_signal_outbox.retry(&dna_hash, &agents);
             }
             Ok(())
         }
@@ -2250,6 +2545,218 @@ mod misc_impls {
             .await
         }
 
+        /// Export the integrated DHT ops of a DNA, selected by the filter,
+        /// into a file signed by the given agent.
+        /// Returns the number of ops exported.
+        pub async fn export_dht_ops(
+            &self,
+            dna_hash: DnaHash,
+            signing_agent: AgentPubKey,
+            path: std::path::PathBuf,
+            filter: DhtOpExportFilter,
+        ) -> ConductorApiResult<usize> {
+            dht_op_export::export_dht_ops(self, dna_hash, signing_agent, path, filter).await
+        }
+
+        /// Import a file written by [`Conductor::export_dht_ops`], sending its ops
+        /// through the incoming ops workflow to be validated.
+        /// Returns the DNA and signer of the file, and the number of ops imported.
+        pub async fn import_dht_ops(
+            &self,
+            path: std::path::PathBuf,
+        ) -> ConductorApiResult<(DnaHash, AgentPubKey, usize)> {
+            dht_op_export::import_dht_ops(self, path).await
+        }
+
+        /// Switch recording of zome calls made to a cell on or off.
+        /// Switching recording off discards the cell's recordings.
+        pub fn set_zome_call_recording(&self, cell_id: CellId, enabled: bool) {
+            zome_call_recording::set_zome_call_recording(self, cell_id, enabled)
+        }
+
+        /// The wasm metering points used by each kind of call into each zome
+        /// since the conductor started, for one DNA or for all of them.
+        pub fn wasm_metering_stats(&self, dna_hash: Option<&DnaHash>) -> Vec<WasmMeteringStats> {
+            self.ribosome_store().share_ref(|d| {
+                d.list()
+                    .into_iter()
+                    .filter(|hash| dna_hash.map_or(true, |dna_hash| dna_hash == hash))
+                    .filter_map(|hash| Some(d.get_ribosome(&hash)?.metering_stats.stats(&hash)))
+                    .flatten()
+                    .collect()
+            })
+        }
+
+        /// The most recent zome calls made to a cell while recording was on,
+        /// oldest first.
+        pub fn zome_call_recordings(&self, cell_id: &CellId) -> Vec<ZomeCallRecording> {
+            zome_call_recording::zome_call_recordings(self, cell_id)
+        }
+
+        /// Whether zome calls made to a cell are being recorded.
+        pub(crate) fn is_recording_zome_calls(&self, cell_id: &CellId) -> bool {
+            self.zome_call_recordings
+                .share_ref(|r| r.contains_key(cell_id))
+        }
+
+        /// Keep the recording of a zome call, if recording is still on for its cell.
+        pub(crate) fn add_zome_call_recording(&self, recording: ZomeCallRecording) {
+            zome_call_recording::add_zome_call_recording(self, recording)
+        }
+
+        /// Re-execute a recorded zome call, feeding the zome the recorded
+        /// results of its host function calls instead of calling the host.
+        /// Nothing the replayed call writes is committed.
+        pub async fn replay_zome_call(
+            &self,
+            recording: ZomeCallRecording,
+        ) -> ConductorApiResult<ZomeCallResult> {
+            zome_call_recording::replay_zome_call(self, recording).await
+        }
+
+        /// The countersigning sessions that running cells have locked their
+        /// chains for.
+        pub async fn list_countersigning_sessions(
+            &self,
+        ) -> ConductorApiResult<Vec<holochain_conductor_api::CountersigningSessionInfo>> {
+            countersigning_session::list_countersigning_sessions(self).await
+        }
+
+        /// Abandon the countersigning session a cell has locked its chain for
+        /// by unlocking its chain.
+        pub async fn abandon_countersigning_session(
+            &self,
+            cell_id: CellId,
+        ) -> ConductorApiResult<Option<EntryHash>> {
+            countersigning_session::abandon_session(self, cell_id).await
+        }
+
+        /// List what each zome of a cell exposes: its zome functions,
+        /// callbacks, scheduled functions and entry and link types.
+        pub async fn introspect_cell(
+            &self,
+            cell_id: CellId,
+        ) -> ConductorApiResult<holochain_conductor_api::CellIntrospection> {
+            introspection::introspect_cell(self, cell_id).await
+        }
+
+        /// Introspect a cell, as long as it belongs to the given app.
+        pub async fn introspect_app_cell(
+            &self,
+            installed_app_id: &InstalledAppId,
+            cell_id: CellId,
+        ) -> ConductorApiResult<holochain_conductor_api::CellIntrospection> {
+            introspection::introspect_app_cell(self, installed_app_id, cell_id).await
+        }
+
+        /// Send a remote signal from a cell in reliable mode: if it can't be
+        /// delivered now it is queued and retried when the recipient is back.
+        pub(crate) fn send_reliable_remote_signal(
+            &self,
+            from: CellId,
+            to_agent: AgentPubKey,
+            zome_name: ZomeName,
+            signal: ExternIO,
+        ) {
+            self.remote_signal_outbox
+                .send(from, to_agent, zome_name, signal)
+        }
+
+        /// The remote signals a cell of the given app has sent in reliable
+        /// mode which are still kept, oldest first.
+        pub async fn remote_signal_deliveries(
+            &self,
+            installed_app_id: &InstalledAppId,
+            cell_id: CellId,
+        ) -> ConductorApiResult<Vec<holochain_conductor_api::RemoteSignalDelivery>> {
+            remote_signal_outbox::remote_signal_deliveries(self, installed_app_id, cell_id).await
+        }
+
+        /// Replace the limits on the resources an installed app may use.
+        pub async fn set_app_quotas(
+            &self,
+            installed_app_id: InstalledAppId,
+            quotas: AppQuotas,
+        ) -> ConductorResult<()> {
+            app_quotas::set_app_quotas(self, installed_app_id, quotas).await
+        }
+
+        /// Evict ops from the caches of the apps of this cell which are
+        /// over their cache quota.
+        pub(crate) async fn enforce_cache_quota(&self, cell_id: &CellId) -> ConductorResult<()> {
+            app_quotas::enforce_cache_quota(self, cell_id).await
+        }
+
+        /// Refuse to write what a zome call into this cell authored if it
+        /// would take an app of the cell over its authored data quota.
+        /// Other zome calls into the apps of the cell wait to check what they
+        /// authored until the returned write is counted or dropped.
+        pub(crate) async fn check_authored_quota(
+            &self,
+            cell_id: &CellId,
+            scratch: &holochain_state::scratch::SyncScratch,
+        ) -> ConductorResult<Option<app_quotas::AuthoredWrite>> {
+            app_quotas::check_authored_quota(self, cell_id, scratch).await
+        }
+
+        /// Count the data authored by the agent of this cell again, after
+        /// its source chain was written to other than by a zome call.
+        pub(crate) fn recount_authored(&self, cell_id: &CellId) {
+            self.app_quotas.recount_authored(cell_id);
+        }
+
+        /// Delete everything in the cache of a DNA.
+        /// Returns the number of ops deleted.
+        pub async fn clear_dht_cache(&self, dna_hash: &DnaHash) -> ConductorResult<usize> {
+            dht_cache::clear_dht_cache(self, dna_hash).await
+        }
+
+        /// Evict ops from the caches of all DNAs until they are within the
+        /// limits of the `dht_cache` config.
+        pub(crate) async fn evict_dht_caches(&self) -> ConductorResult<()> {
+            dht_cache::evict_dht_caches(self).await
+        }
+
+        /// Evict ops from the caches periodically, for as long as the conductor runs.
+        pub(crate) fn start_dht_cache_eviction(self: Arc<Self>) {
+            let tm = self.task_manager();
+            tm.add_conductor_task_ignored("dht_cache_eviction", move |stop| async move {
+                let ticks = futures::stream::unfold(
+                    tokio::time::interval(dht_cache::DHT_CACHE_EVICTION_INTERVAL),
+                    |mut interval| async move {
+                        interval.tick().await;
+                        Some(((), interval))
+                    },
+                );
+                stop.fuse_with(Box::pin(ticks))
+                    .for_each(|()| {
+                        let conductor = self.clone();
+                        async move {
+                            if let Err(e) = conductor.evict_dht_caches().await {
+                                tracing::warn!(?e, "Could not evict ops from the DHT caches");
+                            }
+                            if let Err(e) = app_quotas::enforce_cache_quotas(&conductor).await {
+                                tracing::warn!(?e, "Could not enforce the app cache quotas");
+                            }
+                        }
+                    })
+                    .await;
+                Ok(())
+            });
+        }
+
+        /// Read the config file this conductor was started with again and
+        /// apply the changes which can be applied while running.
+        pub async fn reload_config(
+            self: Arc<Self>,
+        ) -> ConductorResult<holochain_conductor_api::conductor::ConductorConfigReload> {
+            let config_path = self.config_path.clone().ok_or_else(|| {
+                ConductorError::other("The conductor was not started from a config file")
+            })?;
+            let config = ConductorConfig::load_yaml(&config_path)?;
+            reload_config::reload_config(self, config).await
+        }
+
         /// Update coordinator zomes on an existing dna.
         pub async fn update_coordinators(
             &self,
@@ -2279,8 +2786,11 @@ mod misc_impls {
             .await?;
 
             // Update RibosomeStore.
-            self.ribosome_store()
-                .share_mut(|d| d.add_ribosome(ribosome));
+            let ribosome = self.add_ribosome_to_store(ribosome);
+            dna_impls::precompile_in_background(ribosome);
+
+            // The modules compiled from the replaced coordinators aren't needed any more.
+            self.gc_module_cache();
 
             // TODO: Remove old wasm code? (Maybe this needs to be done on restart as it could be in use).
 
@@ -2663,6 +3173,16 @@ impl Conductor {
                 let role_name = role_name.clone();
                 move |mut state| {
                     let app = state.get_app_mut(&app_id)?;
+                    if let Some(max) = app.quotas().max_clone_cells {
+                        let clone_cells =
+                            app.clone_cells().count() + app.disabled_clone_cells().count();
+                        if clone_cells as u32 >= max {
+                            return Err(ConductorError::AppQuotaExceeded(
+                                app_id.clone(),
+                                format!("{} clone cells", max),
+                            ));
+                        }
+                    }
                     let app_role = app.role(&role_name)?;
                     if app_role.is_clone_limit_reached() {
                         return Err(ConductorError::AppError(AppError::CloneLimitExceeded(
@@ -2867,7 +3387,9 @@ pub(crate) async fn genesis_cells(
 
     // unwrap safe because of the partition
     // TODO: Reference count the databases created here and clean them up on error.
-    let _success = success.into_iter().map(Result::unwrap);
+    for cell_id in success.into_iter().map(Result::unwrap) {
+        conductor.recount_authored(&cell_id);
+    }
 
     // If there were errors, cleanup and return the errors
     if !errors.is_empty() {
@@ -0,0 +1,457 @@
+use super::*;
+use holochain_conductor_api::AppStorageInfo;
+use holochain_sqlite::stats::get_allocated_size;
+use holochain_state::query::to_blob;
+use holochain_state::scratch::SyncScratch;
+use tokio::sync::OwnedMutexGuard;
+
+/// The quotas of the apps which have any, and the zome calls running in them.
+///
+/// The quotas are kept here as well as in the conductor state, so that zome
+/// calls don't have to read the state to find the apps of their cell.
+/// They are refreshed whenever the state is updated.
+#[derive(Clone)]
+pub(crate) struct AppQuotaTracker {
+    /// The quotas and cells of each app with quotas.
+    apps: RwShare<HashMap<InstalledAppId, (AppQuotas, HashSet<CellId>)>>,
+    /// The number of zome calls running in each app with quotas.
+    zome_calls: RwShare<HashMap<InstalledAppId, u32>>,
+    /// The number of installed apps with cells of each DNA, which share
+    /// the DNA's cache.
+    cache_sharers: RwShare<HashMap<DnaHash, usize>>,
+    /// The bytes authored by the agent of each cell of an app with an
+    /// authored data quota, counted once and then kept up to date by the
+    /// zome calls which write.
+    /// A count is dropped to be counted again when the chain of its cell is
+    /// written to some other way.
+    authored: RwShare<HashMap<CellId, usize>>,
+    /// Held by a zome call into an app with an authored data quota from
+    /// checking what it authored against the quota until it has been written.
+    authored_locks: RwShare<HashMap<InstalledAppId, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+/// Counts a zome call as running in the apps of its cell until dropped.
+pub(crate) struct AppZomeCallPermit {
+    zome_calls: RwShare<HashMap<InstalledAppId, u32>>,
+    apps: Vec<InstalledAppId>,
+}
+
+impl Drop for AppZomeCallPermit {
+    fn drop(&mut self) {
+        self.zome_calls.share_mut(|calls| {
+            for app_id in &self.apps {
+                if let Some(count) = calls.get_mut(app_id) {
+                    *count -= 1;
+                    if *count == 0 {
+                        calls.remove(app_id);
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Data authored by a zome call which fits in the authored data quotas of
+/// the apps of its cell. Other zome calls into these apps wait to check
+/// their own data until this is dropped.
+pub(crate) struct AuthoredWrite {
+    authored: RwShare<HashMap<CellId, usize>>,
+    cell_id: CellId,
+    size: usize,
+    _locks: Vec<OwnedMutexGuard<()>>,
+}
+
+impl AuthoredWrite {
+    /// Count the data now that it has been written.
+    pub(crate) fn written(self) {
+        self.authored.share_mut(|authored| {
+            if let Some(count) = authored.get_mut(&self.cell_id) {
+                *count += self.size;
+            }
+        });
+    }
+}
+
+impl AppQuotaTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            apps: RwShare::new(HashMap::new()),
+            zome_calls: RwShare::new(HashMap::new()),
+            cache_sharers: RwShare::new(HashMap::new()),
+            authored: RwShare::new(HashMap::new()),
+            authored_locks: RwShare::new(HashMap::new()),
+        }
+    }
+
+    /// Take the quotas and cells of the apps from the conductor state.
+    pub(crate) fn refresh(&self, state: &ConductorState) {
+        let apps = state
+            .installed_apps()
+            .iter()
+            .filter(|(_, app)| app.quotas().is_limited())
+            .map(|(app_id, app)| {
+                (
+                    app_id.clone(),
+                    (app.quotas().clone(), app.all_cells().cloned().collect()),
+                )
+            })
+            .collect::<HashMap<_, (AppQuotas, HashSet<CellId>)>>();
+        let authored_apps: HashSet<InstalledAppId> = apps
+            .iter()
+            .filter(|(_, (quotas, _))| quotas.max_authored_bytes.is_some())
+            .map(|(app_id, _)| app_id.clone())
+            .collect();
+        let authored_cells: HashSet<CellId> = authored_apps
+            .iter()
+            .flat_map(|app_id| apps[app_id].1.iter().cloned())
+            .collect();
+        let mut cache_sharers = HashMap::new();
+        for app in state.installed_apps().values() {
+            let dna_hashes: HashSet<_> =
+                app.all_cells().map(|cell_id| cell_id.dna_hash()).collect();
+            for dna_hash in dna_hashes {
+                *cache_sharers.entry(dna_hash.clone()).or_default() += 1;
+            }
+        }
+        self.apps.share_mut(|a| *a = apps);
+        self.cache_sharers.share_mut(|s| *s = cache_sharers);
+        // Counts of cells which no longer have a quota would go stale.
+        self.authored
+            .share_mut(|a| a.retain(|cell_id, _| authored_cells.contains(cell_id)));
+        self.authored_locks
+            .share_mut(|l| l.retain(|app_id, _| authored_apps.contains(app_id)));
+    }
+
+    /// The DNAs of these cells, with the number of apps sharing each DNA's cache.
+    fn cache_shares(&self, cells: &[CellId]) -> Vec<(DnaHash, usize)> {
+        let dna_hashes: HashSet<_> = cells.iter().map(|cell_id| cell_id.dna_hash()).collect();
+        self.cache_sharers.share_ref(|sharers| {
+            dna_hashes
+                .into_iter()
+                .map(|dna_hash| {
+                    let count = sharers.get(dna_hash).copied().unwrap_or(1);
+                    (dna_hash.clone(), count)
+                })
+                .collect()
+        })
+    }
+
+    /// Count the bytes authored by the agent of a cell again the next time
+    /// they are needed, after they were written to without being checked.
+    pub(crate) fn recount_authored(&self, cell_id: &CellId) {
+        self.authored.share_mut(|authored| {
+            authored.remove(cell_id);
+        });
+    }
+
+    /// Wait until no other zome call is writing to these apps.
+    /// The locks are taken in order, so that two calls can't each wait on
+    /// a lock the other holds.
+    async fn lock_authored(&self, mut app_ids: Vec<InstalledAppId>) -> Vec<OwnedMutexGuard<()>> {
+        app_ids.sort();
+        let locks: Vec<_> = self.authored_locks.share_mut(|locks| {
+            app_ids
+                .into_iter()
+                .map(|app_id| locks.entry(app_id).or_default().clone())
+                .collect()
+        });
+        let mut guards = Vec::with_capacity(locks.len());
+        for lock in locks {
+            guards.push(lock.lock_owned().await);
+        }
+        guards
+    }
+
+    /// The quotas and cells of the apps with quotas which contain this cell.
+    pub(crate) fn apps_of_cell(
+        &self,
+        cell_id: &CellId,
+    ) -> Vec<(InstalledAppId, AppQuotas, Vec<CellId>)> {
+        self.apps.share_ref(|apps| {
+            apps.iter()
+                .filter(|(_, (_, cells))| cells.contains(cell_id))
+                .map(|(app_id, (quotas, cells))| {
+                    (
+                        app_id.clone(),
+                        quotas.clone(),
+                        cells.iter().cloned().collect(),
+                    )
+                })
+                .collect()
+        })
+    }
+
+    /// Count a zome call into this cell as running, unless one of the apps
+    /// of the cell already runs as many zome calls as its quota allows.
+    pub(crate) fn start_zome_call(&self, cell_id: &CellId) -> ConductorResult<AppZomeCallPermit> {
+        let limits: Vec<(InstalledAppId, Option<u32>)> = self
+            .apps_of_cell(cell_id)
+            .into_iter()
+            .map(|(app_id, quotas, _)| (app_id, quotas.max_concurrent_zome_calls))
+            .collect();
+        self.zome_calls.share_mut(|calls| {
+            for (app_id, max) in &limits {
+                if let Some(max) = max {
+                    if calls.get(app_id).copied().unwrap_or_default() >= *max {
+                        return Err(ConductorError::AppQuotaExceeded(
+                            app_id.clone(),
+                            format!("{} concurrent zome calls", max),
+                        ));
+                    }
+                }
+            }
+            for (app_id, _) in &limits {
+                *calls.entry(app_id.clone()).or_default() += 1;
+            }
+            Ok(())
+        })?;
+        Ok(AppZomeCallPermit {
+            zome_calls: self.zome_calls.clone(),
+            apps: limits.into_iter().map(|(app_id, _)| app_id).collect(),
+        })
+    }
+
+    fn zome_calls(&self, app_id: &InstalledAppId) -> u32 {
+        self.zome_calls
+            .share_ref(|calls| calls.get(app_id).copied().unwrap_or_default())
+    }
+}
+
+/// The bytes of actions and entries authored by the agent of a cell,
+/// with each entry counted once however many actions refer to it.
+async fn query_authored_data_size(
+    conductor: &Conductor,
+    cell_id: &CellId,
+) -> ConductorResult<usize> {
+    let author = cell_id.agent_pubkey().clone();
+    Ok(conductor
+        .spaces
+        .authored_db(cell_id.dna_hash())?
+        .async_reader(move |txn| {
+            txn.query_row(
+                "
+                SELECT
+                (SELECT COALESCE(SUM(LENGTH(blob)), 0) FROM Action WHERE author = :author)
+                +
+                (SELECT COALESCE(SUM(LENGTH(blob)), 0) FROM Entry
+                WHERE hash IN (SELECT entry_hash FROM Action WHERE author = :author))
+                ",
+                named_params! {
+                    ":author": author,
+                },
+                |row| row.get::<_, usize>(0),
+            )
+            .map_err(DatabaseError::from)
+        })
+        .await?)
+}
+
+/// The bytes of actions and entries authored by the agents of these cells.
+/// The counts kept for cells with an authored data quota are used if there.
+async fn authored_data_size(conductor: &Conductor, cells: &[CellId]) -> ConductorResult<usize> {
+    let mut size = 0;
+    for cell_id in cells {
+        let counted = conductor
+            .app_quotas
+            .authored
+            .share_ref(|authored| authored.get(cell_id).copied());
+        size += match counted {
+            Some(counted) => counted,
+            None => query_authored_data_size(conductor, cell_id).await?,
+        };
+    }
+    Ok(size)
+}
+
+/// The bytes authored by the agent of a cell with an authored data quota,
+/// which are counted from the database the first time.
+async fn counted_authored_data_size(
+    conductor: &Conductor,
+    cell_id: &CellId,
+) -> ConductorResult<usize> {
+    let counted = conductor
+        .app_quotas
+        .authored
+        .share_ref(|authored| authored.get(cell_id).copied());
+    match counted {
+        Some(counted) => Ok(counted),
+        None => {
+            let size = query_authored_data_size(conductor, cell_id).await?;
+            Ok(conductor
+                .app_quotas
+                .authored
+                .share_mut(|authored| *authored.entry(cell_id.clone()).or_insert(size)))
+        }
+    }
+}
+
+/// The bytes of the actions and entries in a scratch which aren't in the
+/// authored database of the cell yet.
+async fn scratch_data_size(
+    conductor: &Conductor,
+    cell_id: &CellId,
+    scratch: &SyncScratch,
+) -> ConductorResult<usize> {
+    let (actions_size, entries) = scratch
+        .apply(|scratch| {
+            let actions_size = scratch
+                .actions()
+                .map(|shh| Ok(to_blob(&(shh.action(), shh.signature()))?.len()))
+                .sum::<ConductorResult<usize>>()?;
+            let entries = scratch
+                .entries()
+                .map(|(hash, entry)| Ok((hash.clone(), to_blob(entry)?.len())))
+                .collect::<ConductorResult<Vec<_>>>()?;
+            ConductorResult::Ok((actions_size, entries))
+        })
+        .map_err(SourceChainError::from)??;
+    let new_entries_size = conductor
+        .spaces
+        .authored_db(cell_id.dna_hash())?
+        .async_reader(move |txn| {
+            let mut stmt = txn.prepare("SELECT EXISTS(SELECT 1 FROM Entry WHERE hash = :hash)")?;
+            let mut size = 0;
+            for (hash, entry_size) in entries {
+                let exists: bool =
+                    stmt.query_row(named_params! {":hash": hash}, |row| row.get(0))?;
+                if !exists {
+                    size += entry_size;
+                }
+            }
+            DatabaseResult::Ok(size)
+        })
+        .await?;
+    Ok(actions_size + new_entries_size)
+}
+
+/// The size of the caches of the DNAs of these cells, each divided between
+/// the apps which share it.
+async fn cache_data_size(conductor: &Conductor, cells: &[CellId]) -> ConductorResult<usize> {
+    let mut size = 0;
+    for (dna_hash, sharers) in conductor.app_quotas.cache_shares(cells) {
+        size += conductor
+            .spaces
+            .cache(&dna_hash)?
+            .async_reader(get_allocated_size)
+            .await?
+            / sharers;
+    }
+    Ok(size)
+}
+
+/// Evict ops from the caches of the DNAs of the apps of this cell which
+/// are over their cache quota.
+pub(crate) async fn enforce_cache_quota(
+    conductor: &Conductor,
+    cell_id: &CellId,
+) -> ConductorResult<()> {
+    for (_, quotas, cells) in conductor.app_quotas.apps_of_cell(cell_id) {
+        if let Some(max) = quotas.max_cache_bytes {
+            let dnas = conductor.app_quotas.cache_shares(&cells);
+            dht_cache::evict_over_app_quota(conductor, dnas, max).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Evict ops from the caches of the DNAs of all apps over their cache quota.
+pub(crate) async fn enforce_cache_quotas(conductor: &Conductor) -> ConductorResult<()> {
+    let apps: Vec<_> = conductor.app_quotas.apps.share_ref(|apps| {
+        apps.values()
+            .filter_map(|(quotas, cells)| {
+                quotas
+                    .max_cache_bytes
+                    .map(|max| (max, cells.iter().cloned().collect::<Vec<_>>()))
+            })
+            .collect()
+    });
+    for (max, cells) in apps {
+        let dnas = conductor.app_quotas.cache_shares(&cells);
+        dht_cache::evict_over_app_quota(conductor, dnas, max).await?;
+    }
+    Ok(())
+}
+
+/// Refuse to write the data authored by a zome call into this cell if it
+/// would take one of the apps of the cell over its authored data quota.
+/// Returns the data to count once it is written, or `None` if no app of
+/// the cell has an authored data quota.
+pub(crate) async fn check_authored_quota(
+    conductor: &Conductor,
+    cell_id: &CellId,
+    scratch: &SyncScratch,
+) -> ConductorResult<Option<AuthoredWrite>> {
+    let limited: Vec<_> = conductor
+        .app_quotas
+        .apps_of_cell(cell_id)
+        .into_iter()
+        .filter_map(|(app_id, quotas, cells)| {
+            quotas.max_authored_bytes.map(|max| (app_id, max, cells))
+        })
+        .collect();
+    if limited.is_empty() {
+        return Ok(None);
+    }
+    let locks = conductor
+        .app_quotas
+        .lock_authored(
+            limited
+                .iter()
+                .map(|(app_id, _, _)| app_id.clone())
+                .collect(),
+        )
+        .await;
+    let size = scratch_data_size(conductor, cell_id, scratch).await?;
+    for (app_id, max, cells) in limited {
+        let mut authored = size;
+        for cell_id in &cells {
+            authored += counted_authored_data_size(conductor, cell_id).await?;
+        }
+        if authored as u64 > max {
+            return Err(ConductorError::AppQuotaExceeded(
+                app_id,
+                format!("{} authored bytes", max),
+            ));
+        }
+    }
+    Ok(Some(AuthoredWrite {
+        authored: conductor.app_quotas.authored.clone(),
+        cell_id: cell_id.clone(),
+        size,
+        _locks: locks,
+    }))
+}
+
+pub(crate) async fn set_app_quotas(
+    conductor: &Conductor,
+    installed_app_id: InstalledAppId,
+    quotas: AppQuotas,
+) -> ConductorResult<()> {
+    conductor
+        .update_state(move |mut state| {
+            state.get_app_mut(&installed_app_id)?.set_quotas(quotas);
+            Ok(state)
+        })
+        .await?;
+    Ok(())
+}
+
+/// The resources used by each installed app, as counted against its quotas.
+pub(crate) async fn app_storage_info(
+    conductor: &Conductor,
+    state: &ConductorState,
+) -> ConductorResult<Vec<AppStorageInfo>> {
+    let mut apps = Vec::new();
+    for (installed_app_id, app) in state.installed_apps().iter() {
+        let cells: Vec<CellId> = app.all_cells().cloned().collect();
+        apps.push(AppStorageInfo {
+            installed_app_id: installed_app_id.clone(),
+            authored_data_size: authored_data_size(conductor, &cells).await?,
+            cache_data_size: cache_data_size(conductor, &cells).await?,
+            clone_cells: app.clone_cells().count() + app.disabled_clone_cells().count(),
+            concurrent_zome_calls: conductor.app_quotas.zome_calls(installed_app_id),
+            quotas: app.quotas().clone(),
+        });
+    }
+    Ok(apps)
+}
@@ -22,6 +22,8 @@ pub struct ConductorBuilder {
     pub state: Option<ConductorState>,
     /// Skip printing setup info to stdout
     pub no_print_setup: bool,
+    /// The file the config was loaded from, to read again on reload
+    pub config_path: Option<std::path::PathBuf>,
 }
 
 impl ConductorBuilder {
@@ -38,6 +40,13 @@ impl ConductorBuilder {
         self
     }
 
+    /// Set the file the ConductorConfig was loaded from, so that it can be
+    /// reloaded while the Conductor is running
+    pub fn config_path(mut self, config_path: std::path::PathBuf) -> Self {
+        self.config_path = Some(config_path);
+        self
+    }
+
     /// Set the passphrase for use in keystore initialization
     pub fn passphrase(mut self, passphrase: Option<sodoken::BufRead>) -> Self {
         self.passphrase = passphrase;
@@ -98,6 +107,7 @@ impl ConductorBuilder {
         let Self {
             ribosome_store,
             config,
+            config_path,
             ..
         } = self;
 
@@ -149,6 +159,7 @@ impl ConductorBuilder {
 
         let conductor = Conductor::new(
             config.clone(),
+            config_path,
             ribosome_store,
             keystore,
             holochain_p2p,
@@ -244,6 +255,7 @@ impl ConductorBuilder {
         tm.add_conductor_task_unrecoverable("post_commit_receiver", move |stop| {
             Self::spawn_post_commit(conductor2, post_commit_receiver, stop).map(Ok)
         });
+        conductor.clone().start_dht_cache_eviction();
 
         let configs = conductor_config.admin_interfaces.unwrap_or_default();
         let cell_startup_errors = conductor
@@ -337,6 +349,7 @@ impl ConductorBuilder {
 
         let conductor = Conductor::new(
             self.config.clone(),
+            self.config_path,
             ribosome_store,
             keystore,
             holochain_p2p,
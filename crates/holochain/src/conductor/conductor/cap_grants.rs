@@ -0,0 +1,159 @@
+use holochain_conductor_api::CapGrantInfo;
+use holochain_state::cap_grant_use::cap_grant_uses;
+use rusqlite::named_params;
+use rusqlite::OptionalExtension;
+
+use super::*;
+
+async fn cell_source_chain(
+    conductor: &Conductor,
+    cell_id: &CellId,
+) -> ConductorApiResult<SourceChain> {
+    Ok(SourceChain::new(
+        conductor.get_or_create_authored_db(cell_id.dna_hash())?,
+        conductor.get_or_create_dht_db(cell_id.dna_hash())?,
+        conductor
+            .get_or_create_space(cell_id.dna_hash())?
+            .dht_query_cache,
+        conductor.keystore.clone(),
+        cell_id.agent_pubkey().clone(),
+    )
+    .await?)
+}
+
+pub(crate) async fn list_cap_grants(
+    conductor: &Conductor,
+    cell_id: CellId,
+    include_revoked: bool,
+) -> ConductorApiResult<Vec<CapGrantInfo>> {
+    let source_chain = cell_source_chain(conductor, &cell_id).await?;
+    let grants = source_chain
+        .query(
+            QueryFilter::new()
+                .entry_type(EntryType::CapGrant)
+                .include_entries(true),
+        )
+        .await?;
+    // A grant is revoked by deleting or updating the action which created it.
+    let revocations: HashMap<ActionHash, Timestamp> = source_chain
+        .query(
+            QueryFilter::new()
+                .action_type(ActionType::Delete)
+                .action_type(ActionType::Update),
+        )
+        .await?
+        .into_iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some((delete.deletes_address.clone(), delete.timestamp)),
+            Action::Update(update) => {
+                Some((update.original_action_address.clone(), update.timestamp))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut infos = Vec::new();
+    for record in grants {
+        let action_hash = record.action_address().clone();
+        let created_at = record.action().timestamp();
+        let revoked_at = revocations.get(&action_hash).copied();
+        if revoked_at.is_some() && !include_revoked {
+            continue;
+        }
+        let cap_grant = match record.entry().as_option() {
+            Some(Entry::CapGrant(cap_grant)) => cap_grant.clone(),
+            _ => continue,
+        };
+        let uses = match cap_grant.max_uses {
+            Some(_) => {
+                let grant_hash = EntryHash::with_data_sync(&Entry::CapGrant(cap_grant.clone()));
+                Some(
+                    cap_grant_uses(&conductor.spaces.conductor_db, cell_id.clone(), grant_hash)
+                        .await?,
+                )
+            }
+            None => None,
+        };
+        infos.push(CapGrantInfo {
+            cap_grant,
+            action_hash,
+            created_at,
+            revoked_at,
+            uses,
+        });
+    }
+    Ok(infos)
+}
+
+pub(crate) async fn revoke_cap_grant(
+    conductor: &Conductor,
+    action_hash: ActionHash,
+) -> ConductorApiResult<()> {
+    // Find the running cell whose chain has the grant.
+    for cell_id in conductor.running_cell_ids(None) {
+        let grant_hash = conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?
+            .async_reader({
+                let author = cell_id.agent_pubkey().clone();
+                let action_hash = action_hash.clone();
+                move |txn| unrevoked_cap_grant(&txn, &author, &action_hash)
+            })
+            .await?;
+        if let Some(grant_hash) = grant_hash {
+            let source_chain = cell_source_chain(conductor, &cell_id).await?;
+            let action_builder = holochain_zome_types::builder::Delete {
+                deletes_address: action_hash,
+                deletes_entry_address: grant_hash,
+            };
+            source_chain
+                .put_weightless(action_builder, None, ChainTopOrdering::default())
+                .await?;
+            let cell = conductor.cell_by_id(&cell_id, false).await?;
+            source_chain.flush(cell.holochain_p2p_dna()).await?;
+            conductor.recount_authored(&cell_id);
+            return Ok(());
+        }
+    }
+    Err(ConductorError::CapGrantMissing(action_hash).into())
+}
+
+/// The entry hash of the cap grant created by an author's action,
+/// unless the action has been deleted or updated.
+fn unrevoked_cap_grant(
+    txn: &Transaction,
+    author: &AgentPubKey,
+    action_hash: &ActionHash,
+) -> StateQueryResult<Option<EntryHash>> {
+    let blob: Option<Vec<u8>> = txn
+        .query_row(
+            "
+            SELECT Action.blob
+            FROM Action
+            WHERE Action.hash = :hash
+            AND Action.author = :author
+            AND NOT EXISTS (
+                SELECT 1
+                FROM Action AS Revocation
+                WHERE Revocation.author = :author
+                AND (
+                    Revocation.deletes_action_hash = :hash
+                    OR Revocation.original_action_hash = :hash
+                )
+            )
+            ",
+            named_params! {
+                ":hash": action_hash,
+                ":author": author,
+            },
+            |row| row.get("blob"),
+        )
+        .optional()?;
+    let action = match blob {
+        Some(blob) => from_blob::<SignedAction>(blob)?.0,
+        None => return Ok(None),
+    };
+    Ok(match (action.entry_type(), action.entry_hash()) {
+        (Some(EntryType::CapGrant), Some(entry_hash)) => Some(entry_hash.clone()),
+        _ => None,
+    })
+}
@@ -0,0 +1,67 @@
+use holochain_conductor_api::CountersigningSessionInfo;
+use holochain_state::chain_lock::get_countersigning_session;
+use holochain_state::prelude::{abandon_countersigning_session, current_countersigning_session};
+
+use super::*;
+
+pub(crate) async fn list_countersigning_sessions(
+    conductor: &Conductor,
+) -> ConductorApiResult<Vec<CountersigningSessionInfo>> {
+    let mut sessions = Vec::new();
+    for cell_id in conductor.running_cell_ids(None) {
+        let author = cell_id.agent_pubkey().clone();
+        let session = conductor
+            .get_or_create_authored_db(cell_id.dna_hash())?
+            .async_reader(move |txn| {
+                let preflight_request = get_countersigning_session(&txn, &author)?;
+                let entry_hash =
+                    current_countersigning_session(&txn, Arc::new(author))?.map(|(h, _)| h);
+                SourceChainResult::Ok(preflight_request.map(|p| (p, entry_hash)))
+            })
+            .await?;
+        if let Some((preflight_request, entry_hash)) = session {
+            sessions.push(CountersigningSessionInfo {
+                cell_id,
+                preflight_request,
+                entry_hash,
+            });
+        }
+    }
+    Ok(sessions)
+}
+
+pub(crate) async fn abandon_session(
+    conductor: &Conductor,
+    cell_id: CellId,
+) -> ConductorApiResult<Option<EntryHash>> {
+    let installed = conductor
+        .get_state()
+        .await?
+        .installed_apps()
+        .values()
+        .flat_map(|app| app.all_cells())
+        .any(|id| *id == cell_id);
+    if !installed {
+        return Err(ConductorError::CellMissing(cell_id).into());
+    }
+    let space = conductor.get_or_create_space(cell_id.dna_hash())?;
+    let author = cell_id.agent_pubkey().clone();
+    let abandoned = space
+        .authored_db
+        .async_commit(move |txn| abandon_countersigning_session(txn, Arc::new(author)))
+        .await?;
+
+    if let Some(entry_hash) = &abandoned {
+        space
+            .countersigning_workspace
+            .abandon(entry_hash.clone())
+            .await?;
+        conductor
+            .signal_broadcaster()
+            .send(Signal::System(SystemSignal::AbandonedCountersigning(
+                entry_hash.clone(),
+            )))
+            .ok();
+    }
+    Ok(abandoned)
+}
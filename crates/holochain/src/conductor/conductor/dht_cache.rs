@@ -0,0 +1,262 @@
+use super::*;
+use holochain_conductor_api::conductor::DhtCacheConfig;
+use holochain_p2p::dht_arc::DhtArcRange;
+use holochain_sqlite::stats::get_allocated_size;
+
+/// How often the caches are checked against the limits in the config.
+pub(crate) const DHT_CACHE_EVICTION_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(60);
+
+/// The number of ops evicted from a cache at a time while it's over a size limit.
+const EVICTION_BATCH_SIZE: usize = 100;
+
+/// A DNA's cache, the condition matching its evictable ops, its size, and
+/// the number of users its size is divided between.
+type ChargedCache = (DbWrite<DbKindCache>, String, usize, usize);
+
+/// An SQL condition matching the ops of a DNA which no local agent is an
+/// authority for, i.e. which are outside the storage arcs of all of them.
+async fn evictable_condition(conductor: &Conductor, dna_hash: &DnaHash) -> ConductorResult<String> {
+    let db = conductor.spaces.p2p_agents_db(dna_hash)?;
+    let mut arcs = Vec::new();
+    for cell_id in conductor.live_cell_ids() {
+        if cell_id.dna_hash() != dna_hash {
+            continue;
+        }
+        let info = get_single_agent_info(
+            db.clone().into(),
+            dna_hash.clone(),
+            cell_id.agent_pubkey().clone(),
+        )
+        .await?;
+        if let Some(info) = info {
+            arcs.push(info.storage_arc.inner());
+        }
+    }
+    let authority: Vec<String> = arcs
+        .into_iter()
+        .filter_map(|arc| match arc {
+            DhtArcRange::Empty => None,
+            DhtArcRange::Full => Some("1".to_string()),
+            DhtArcRange::Bounded(start, end) => {
+                if start <= end {
+                    Some(format!(
+                        "(storage_center_loc >= {} AND storage_center_loc <= {})",
+                        start, end
+                    ))
+                } else {
+                    Some(format!(
+                        "(storage_center_loc >= {} OR storage_center_loc <= {})",
+                        start, end
+                    ))
+                }
+            }
+        })
+        .collect();
+    if authority.is_empty() {
+        Ok("1".to_string())
+    } else {
+        Ok(format!("NOT ({})", authority.join(" OR ")))
+    }
+}
+
+/// Delete the ops matching the condition from a cache, least recently
+/// fetched first, along with the actions and entries no op refers to anymore.
+fn evict_ops(
+    txn: &mut Transaction,
+    condition: &str,
+    limit: Option<usize>,
+) -> DatabaseResult<usize> {
+    let limit = limit
+        .map(|limit| format!("ORDER BY when_integrated ASC LIMIT {}", limit))
+        .unwrap_or_default();
+    let evicted = txn.execute(
+        &format!(
+            "DELETE FROM DhtOp WHERE hash IN (SELECT hash FROM DhtOp WHERE {} {})",
+            condition, limit
+        ),
+        [],
+    )?;
+    if evicted > 0 {
+        txn.execute(
+            "DELETE FROM Action WHERE hash NOT IN (SELECT action_hash FROM DhtOp)",
+            [],
+        )?;
+        txn.execute(
+            "DELETE FROM Entry WHERE hash NOT IN
+            (SELECT entry_hash FROM Action WHERE entry_hash IS NOT NULL)",
+            [],
+        )?;
+    }
+    Ok(evicted)
+}
+
+/// The time the least recently fetched op matching the condition was fetched.
+async fn least_recently_fetched(
+    cache: &DbWrite<DbKindCache>,
+    condition: String,
+) -> ConductorResult<Option<Timestamp>> {
+    Ok(cache
+        .async_reader(move |txn| {
+            txn.query_row(
+                &format!("SELECT MIN(when_integrated) FROM DhtOp WHERE {}", condition),
+                [],
+                |row| row.get(0),
+            )
+            .map_err(DatabaseError::from)
+        })
+        .await?)
+}
+
+/// Evict the ops of a DNA's cache which are older than the age limit,
+/// and then the least recently fetched ops while the cache is over the
+/// size limit. Returns the size of the cache afterwards.
+async fn evict_from_cache(
+    cache: &DbWrite<DbKindCache>,
+    evictable: &str,
+    config: &DhtCacheConfig,
+) -> ConductorResult<usize> {
+    if let Some(max_age_s) = config.max_age_s {
+        let cutoff = Timestamp::now().saturating_sub(&std::time::Duration::from_secs(max_age_s));
+        let condition = format!("{} AND when_integrated < {}", evictable, cutoff.as_micros());
+        cache
+            .async_commit(move |txn| evict_ops(txn, &condition, None))
+            .await?;
+    }
+    let mut size = cache.async_reader(get_allocated_size).await?;
+    if let Some(max_bytes) = config.max_bytes_per_dna {
+        while size as u64 > max_bytes {
+            let condition = evictable.to_string();
+            let evicted = cache
+                .async_commit(move |txn| evict_ops(txn, &condition, Some(EVICTION_BATCH_SIZE)))
+                .await?;
+            if evicted == 0 {
+                break;
+            }
+            size = cache.async_reader(get_allocated_size).await?;
+        }
+    }
+    Ok(size)
+}
+
+/// Bring the caches of all DNAs within the limits of the conductor config.
+///
+/// Each cache is first brought within the age and per DNA limits. Then, while
+/// the caches together are over the conductor-wide limit, the least recently
+/// fetched ops of all caches are evicted.
+pub(crate) async fn evict_dht_caches(conductor: &Conductor) -> ConductorResult<()> {
+    let config = match conductor.running_config.share_ref(|c| c.dht_cache.clone()) {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+    let caches = conductor
+        .spaces
+        .get_from_spaces(|space| ((*space.dna_hash).clone(), space.cache_db.clone()));
+
+    let mut sizes = Vec::with_capacity(caches.len());
+    for (dna_hash, cache) in caches {
+        let evictable = evictable_condition(conductor, &dna_hash).await?;
+        let size = evict_from_cache(&cache, &evictable, &config).await?;
+        sizes.push((cache, evictable, size));
+    }
+
+    if let Some(max_bytes) = config.max_bytes {
+        let mut caches: Vec<_> = sizes
+            .into_iter()
+            .map(|(cache, evictable, size)| (cache, evictable, size, 1))
+            .collect();
+        evict_over_limit(&mut caches, max_bytes).await?;
+    }
+    Ok(())
+}
+
+/// Evict the least recently fetched ops of the caches of these DNAs while
+/// they are over an app's cache quota. Each cache is charged for its size
+/// divided by the number of apps it is shared by, given with each DNA.
+///
+/// This runs after zome calls, so the evictable ops are only looked for
+/// once the sizes, which are cheap to read, are over the quota.
+pub(crate) async fn evict_over_app_quota(
+    conductor: &Conductor,
+    dnas: Vec<(DnaHash, usize)>,
+    max_bytes: u64,
+) -> ConductorResult<()> {
+    let mut sizes = Vec::with_capacity(dnas.len());
+    for (dna_hash, sharers) in dnas {
+        let cache = conductor.spaces.cache(&dna_hash)?;
+        let size = cache.async_reader(get_allocated_size).await?;
+        sizes.push((dna_hash, cache, size, sharers));
+    }
+    let charged: u64 = sizes
+        .iter()
+        .map(|(_, _, size, sharers)| charged_size(*size, *sharers))
+        .sum();
+    if charged <= max_bytes {
+        return Ok(());
+    }
+    let mut caches = Vec::with_capacity(sizes.len());
+    for (dna_hash, cache, size, sharers) in sizes {
+        let evictable = evictable_condition(conductor, &dna_hash).await?;
+        caches.push((cache, evictable, size, sharers));
+    }
+    evict_over_limit(&mut caches, max_bytes).await
+}
+
+/// The size a cache is charged for, when it is shared by this many users.
+fn charged_size(size: usize, sharers: usize) -> u64 {
+    (size / sharers.max(1)) as u64
+}
+
+/// While the charged sizes of the caches, each its size divided by its
+/// sharers, are together over the limit, evict the least recently fetched
+/// ops of all of them.
+async fn evict_over_limit(caches: &mut [ChargedCache], max_bytes: u64) -> ConductorResult<()> {
+    let charged = |caches: &[ChargedCache]| {
+        caches
+            .iter()
+            .map(|(_, _, size, sharers)| charged_size(*size, *sharers))
+            .sum::<u64>()
+    };
+    if charged(caches) <= max_bytes {
+        return Ok(());
+    }
+    // Only the cache evicted from changes, so only its oldest op is
+    // looked for again.
+    let mut oldest = Vec::with_capacity(caches.len());
+    for (cache, evictable, _, _) in caches.iter() {
+        oldest.push(least_recently_fetched(cache, evictable.clone()).await?);
+    }
+    while charged(caches) > max_bytes {
+        let i = match oldest
+            .iter()
+            .enumerate()
+            .filter_map(|(i, fetched)| fetched.map(|fetched| (i, fetched)))
+            .min_by_key(|(_, fetched)| *fetched)
+        {
+            Some((i, _)) => i,
+            None => break,
+        };
+        let (cache, evictable, size, _) = &mut caches[i];
+        let condition = evictable.clone();
+        cache
+            .async_commit(move |txn| evict_ops(txn, &condition, Some(EVICTION_BATCH_SIZE)))
+            .await?;
+        *size = cache.async_reader(get_allocated_size).await?;
+        oldest[i] = least_recently_fetched(cache, evictable.clone()).await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn clear_dht_cache(
+    conductor: &Conductor,
+    dna_hash: &DnaHash,
+) -> ConductorResult<usize> {
+    if conductor.get_dna_def(dna_hash).is_none() {
+        return Err(DnaError::DnaMissing(dna_hash.clone()).into());
+    }
+    Ok(conductor
+        .spaces
+        .cache(dna_hash)?
+        .async_commit(|txn| evict_ops(txn, "1", None))
+        .await?)
+}
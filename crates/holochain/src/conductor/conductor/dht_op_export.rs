@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use crate::conductor::api::error::ConductorApiError;
+use crate::core::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
+
+use super::*;
+
+pub(crate) async fn export_dht_ops(
+    conductor: &Conductor,
+    dna_hash: DnaHash,
+    signing_agent: AgentPubKey,
+    path: PathBuf,
+    filter: DhtOpExportFilter,
+) -> ConductorApiResult<usize> {
+    let ops = conductor
+        .spaces
+        .handle_fetch_op_data_by_regions(&dna_hash, vec![filter.region_bounds()])
+        .await?;
+
+    let export = DhtOpExport {
+        dna_hash,
+        exported_at: Timestamp::now(),
+        ops: ops.into_iter().map(|(_, op)| op).collect(),
+    };
+    let op_count = export.ops.len();
+    let export_bytes = export.to_bytes()?;
+    let signature = signing_agent
+        .sign_raw(conductor.keystore(), export_bytes.clone().into())
+        .await?;
+    let signed = SignedDhtOpExport {
+        signer: signing_agent,
+        signature,
+        export_bytes,
+    };
+
+    // Block because gzipping could take some time
+    let content = tokio::task::spawn_blocking(move || signed.to_file_content())
+        .await
+        .expect("blocking thread panicked - panicking here too")?;
+    tokio::fs::write(path, content).await?;
+
+    Ok(op_count)
+}
+
+pub(crate) async fn import_dht_ops(
+    conductor: &Conductor,
+    path: PathBuf,
+) -> ConductorApiResult<(DnaHash, AgentPubKey, usize)> {
+    let content = tokio::fs::read(path).await?;
+    let signed =
+        tokio::task::spawn_blocking(move || SignedDhtOpExport::from_file_content(&content))
+            .await
+            .expect("blocking thread panicked - panicking here too")?;
+
+    if !signed
+        .signer
+        .verify_signature_raw(&signed.signature, signed.export_bytes.clone().into())
+        .await
+    {
+        return Err(ConductorApiError::other(format!(
+            "The DHT op export file is not validly signed by {}",
+            signed.signer
+        )));
+    }
+    let DhtOpExport { dna_hash, ops, .. } = signed.export()?;
+
+    // The ops can only be validated by a running cell of the DNA.
+    let space = conductor.spaces.get_or_create_space(&dna_hash)?;
+    let trigger = conductor
+        .spaces
+        .queue_consumer_map
+        .sys_validation_trigger(Arc::new(dna_hash.clone()))
+        .ok_or_else(|| ConductorApiError::DnaMissing(dna_hash.clone()))?;
+
+    let op_count = ops.len();
+    let ops = ops
+        .into_iter()
+        .map(|op| (DhtOpHash::with_data_sync(&op), op))
+        .collect();
+    incoming_dht_ops_workflow(space, trigger, ops, false).await?;
+
+    Ok((dna_hash, signed.signer, op_count))
+}
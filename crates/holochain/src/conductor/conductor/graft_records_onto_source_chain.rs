@@ -97,6 +97,7 @@ pub(crate) async fn graft_records_onto_source_chain(
             }
         })
         .await?;
+    handle.recount_authored(&cell_id);
 
     // Check which ops need to be integrated.
     // Only integrated if a cell is installed.
@@ -0,0 +1,92 @@
+use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsHostAccess;
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
+use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
+use crate::core::ribosome::RibosomeT;
+use holochain_conductor_api::{CellIntrospection, ZomeIntrospection};
+use holochain_state::schedule::scheduled_fns;
+
+use super::*;
+
+pub(crate) async fn introspect_cell(
+    conductor: &Conductor,
+    cell_id: CellId,
+) -> ConductorApiResult<CellIntrospection> {
+    let ribosome = conductor.get_ribosome(cell_id.dna_hash())?;
+    let author = cell_id.agent_pubkey().clone();
+    let scheduled = conductor
+        .get_or_create_authored_db(cell_id.dna_hash())?
+        .async_reader(move |txn| scheduled_fns(&txn, &author))
+        .await?;
+    let zomes = tokio::task::spawn_blocking(move || introspect_zomes(&ribosome, scheduled))
+        .await
+        .expect("blocking thread panicked - panicking here too")?;
+    Ok(CellIntrospection { cell_id, zomes })
+}
+
+pub(crate) async fn introspect_app_cell(
+    conductor: &Conductor,
+    installed_app_id: &InstalledAppId,
+    cell_id: CellId,
+) -> ConductorApiResult<CellIntrospection> {
+    let state = conductor.get_state().await?;
+    if !state
+        .get_app(installed_app_id)?
+        .all_cells()
+        .any(|app_cell_id| *app_cell_id == cell_id)
+    {
+        return Err(ConductorError::CellMissing(cell_id).into());
+    }
+    introspect_cell(conductor, cell_id).await
+}
+
+fn introspect_zomes(
+    ribosome: &RealRibosome,
+    scheduled: Vec<ScheduledFn>,
+) -> RibosomeResult<Vec<ZomeIntrospection>> {
+    let mut entry_defs = match ribosome.run_entry_defs(EntryDefsHostAccess, EntryDefsInvocation)? {
+        EntryDefsResult::Defs(defs) => defs,
+        EntryDefsResult::Err(zome, error_string) => {
+            return Err(RibosomeError::EntryDefs(zome, error_string))
+        }
+    };
+    let num_integrity_zomes = ribosome.dna_def().integrity_zomes.len();
+    ribosome
+        .dna_def()
+        .all_zomes()
+        .enumerate()
+        .map(|(index, (zome_name, zome_def))| {
+            let zome: Zome = (zome_name.clone(), zome_def.clone()).into();
+            let integrity = index < num_integrity_zomes;
+            let link_types = if integrity {
+                ribosome
+                    .zome_types()
+                    .in_scope_subset(&[ZomeIndex(index as u8)])
+                    .links
+                    .0
+                    .into_iter()
+                    .flat_map(|(_, link_types)| link_types)
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            Ok(ZomeIntrospection {
+                zome_fns: ribosome.list_zome_fns(&zome)?,
+                callbacks: ribosome.list_callbacks(&zome)?,
+                scheduled_fns: scheduled
+                    .iter()
+                    .filter(|s| s.zome_name() == zome_name)
+                    .map(|s| s.fn_name().clone())
+                    .collect(),
+                entry_defs: entry_defs
+                    .remove(zome_name)
+                    .map(|defs| defs.0)
+                    .unwrap_or_default(),
+                link_types,
+                zome_name: zome_name.clone(),
+                integrity,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+use super::*;
+use holochain_conductor_api::conductor::ConductorConfigReload;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams;
+use holochain_p2p::HolochainP2pSender;
+
+type TuningParam = fn(&mut KitsuneP2pTuningParams) -> &mut f64;
+
+/// The tuning params which set the gossip bandwidth limits, the only
+/// network settings which can be changed while the network is running.
+const GOSSIP_BANDWIDTH_PARAMS: [(&str, TuningParam); 5] = [
+    ("gossip_inbound_target_mbps", |p| {
+        &mut p.gossip_inbound_target_mbps
+    }),
+    ("gossip_outbound_target_mbps", |p| {
+        &mut p.gossip_outbound_target_mbps
+    }),
+    ("gossip_historic_inbound_target_mbps", |p| {
+        &mut p.gossip_historic_inbound_target_mbps
+    }),
+    ("gossip_historic_outbound_target_mbps", |p| {
+        &mut p.gossip_historic_outbound_target_mbps
+    }),
+    ("gossip_burst_ratio", |p| &mut p.gossip_burst_ratio),
+];
+
+/// Whether a gossip bandwidth param can be applied on its own: limits must
+/// be finite and not negative, and the burst ratio finite and positive.
+fn gossip_bandwidth_param_is_valid(name: &str, value: f64) -> bool {
+    if name == "gossip_burst_ratio" {
+        value.is_finite() && value > 0.0
+    } else {
+        value.is_finite() && value >= 0.0
+    }
+}
+
+/// Whether every non-zero limit allows a burst of at least one bit
+/// with the burst ratio, which the bandwidth throttle requires.
+fn gossip_bandwidth_is_valid(tuning_params: &KitsuneP2pTuningParams) -> bool {
+    let mut tuning_params = tuning_params.clone();
+    let burst_ratio = tuning_params.gossip_burst_ratio;
+    GOSSIP_BANDWIDTH_PARAMS[..4].iter().all(|(_, param)| {
+        let bps = *param(&mut tuning_params) * 1000.0 * 1000.0;
+        bps == 0.0 || bps * burst_ratio >= 1.0
+    })
+}
+
+/// Apply the differences between the running config and the given one which
+/// can be applied while running, and report the others as requiring a restart.
+pub(crate) async fn reload_config(
+    conductor: ConductorHandle,
+    config: ConductorConfig,
+) -> ConductorResult<ConductorConfigReload> {
+    let running = conductor.running_config.share_ref(|c| c.clone());
+    let mut reloaded = running.clone();
+    let mut reload = ConductorConfigReload::default();
+
+    if config.tracing_override != running.tracing_override {
+        match holochain_trace::reload_filter(config.tracing_override.as_deref()) {
+            Ok(()) => {
+                reloaded.tracing_override = config.tracing_override.clone();
+                reload.applied.push("tracing_override".to_string());
+            }
+            Err(e) => {
+                tracing::warn!(?e, "Could not reload the tracing filter");
+                reload.requires_restart.push("tracing_override".to_string());
+            }
+        }
+    }
+
+    // The cache limits are read from the running config each time the
+    // caches are checked.
+    if config.dht_cache != running.dht_cache {
+        reloaded.dht_cache = config.dht_cache.clone();
+        reload.applied.push("dht_cache".to_string());
+    }
+
+    let running_admin_interfaces = running.admin_interfaces.clone().unwrap_or_default();
+    let admin_interfaces = config.admin_interfaces.clone().unwrap_or_default();
+    let added: Vec<_> = admin_interfaces
+        .iter()
+        .filter(|i| !running_admin_interfaces.contains(i))
+        .cloned()
+        .collect();
+    if !added.is_empty() {
+        conductor
+            .clone()
+            .add_admin_interfaces(added.clone())
+            .await?;
+        reloaded.admin_interfaces = Some(
+            running_admin_interfaces
+                .iter()
+                .cloned()
+                .chain(added)
+                .collect(),
+        );
+        reload.applied.push("admin_interfaces".to_string());
+    }
+    // Running admin interfaces can't be stopped.
+    if running_admin_interfaces
+        .iter()
+        .any(|i| !admin_interfaces.contains(i))
+    {
+        reload.requires_restart.push("admin_interfaces".to_string());
+    }
+
+    // Split the new tuning params into the bandwidth limits, which are
+    // applied, and the rest, which is compared with the running network
+    // config to find out whether a restart is needed.
+    let running_network = running.network.clone().unwrap_or_default();
+    let mut network = config.network.clone().unwrap_or_default();
+    let mut applied_tuning_params = (*running_network.tuning_params).clone();
+    let mut unapplied_tuning_params = (*network.tuning_params).clone();
+    let mut changed_params = Vec::new();
+    for (name, param) in GOSSIP_BANDWIDTH_PARAMS {
+        let value = *param(&mut unapplied_tuning_params);
+        let running_value = *param(&mut applied_tuning_params);
+        *param(&mut unapplied_tuning_params) = running_value;
+        if value == running_value {
+            continue;
+        }
+        let valid = gossip_bandwidth_param_is_valid(name, value);
+        let name = format!("network.tuning_params.{}", name);
+        if valid {
+            *param(&mut applied_tuning_params) = value;
+            changed_params.push(name);
+        } else {
+            reload.invalid.push(name);
+        }
+    }
+    if gossip_bandwidth_is_valid(&applied_tuning_params) {
+        reload.applied.extend(changed_params);
+    } else {
+        // The limits are only invalid in combination with the burst ratio,
+        // so none of the changes are applied.
+        applied_tuning_params = (*running_network.tuning_params).clone();
+        reload.invalid.extend(changed_params);
+    }
+    if applied_tuning_params != *running_network.tuning_params {
+        let tuning_params = Arc::new(applied_tuning_params);
+        conductor
+            .holochain_p2p
+            .set_gossip_bandwidth(tuning_params.clone())
+            .await?;
+        let mut running_network = running_network.clone();
+        running_network.tuning_params = tuning_params;
+        reloaded.network = Some(running_network);
+    }
+    network.tuning_params = Arc::new(unapplied_tuning_params);
+    if network != running_network {
+        reload.requires_restart.push("network".to_string());
+    }
+
+    for (name, changed) in [
+        (
+            "environment_path",
+            config.environment_path != running.environment_path,
+        ),
+        ("keystore", config.keystore != running.keystore),
+        ("dpki", config.dpki != running.dpki),
+        (
+            "chc_namespace",
+            config.chc_namespace != running.chc_namespace,
+        ),
+        (
+            "db_sync_strategy",
+            config.db_sync_strategy != running.db_sync_strategy,
+        ),
+    ] {
+        if changed {
+            reload.requires_restart.push(name.to_string());
+        }
+    }
+
+    conductor.running_config.share_mut(|c| *c = reloaded);
+    if !reload.invalid.is_empty() {
+        tracing::warn!(invalid = ?reload.invalid, "Invalid settings were not reloaded");
+    }
+    tracing::info!(?reload, "Reloaded the conductor config");
+    Ok(reload)
+}
@@ -0,0 +1,298 @@
+use super::*;
+use holochain_conductor_api::RemoteSignalDelivery;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::nonce::fresh_nonce;
+use holochain_state::remote_signal;
+use holochain_state::remote_signal::QueuedRemoteSignal;
+
+/// The number of reliable remote signals kept for each sending cell. Once
+/// this many are kept, the oldest delivered signal is dropped for each new
+/// one, or the oldest undelivered signal if all of them are undelivered.
+const MAX_QUEUED_REMOTE_SIGNALS_PER_CELL: usize = 1000;
+
+/// How long a reliable remote signal is kept, delivered or not.
+const REMOTE_SIGNAL_TTL: std::time::Duration = std::time::Duration::from_secs(60 * 60 * 24);
+
+/// How long to wait before sending again to a recipient which couldn't be
+/// reached, doubled after each failed attempt up to the maximum.
+const REMOTE_SIGNAL_MIN_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+const REMOTE_SIGNAL_MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// The zome function which receives remote signals.
+const RECV_REMOTE_SIGNAL: &str = "recv_remote_signal";
+
+/// The remote signals sent in reliable mode by the cells of this conductor.
+///
+/// Signals are kept in the conductor database, so undelivered signals are
+/// still retried after a restart. A signal is sent as soon as it is queued.
+/// Signals which could not be delivered are sent again whenever the
+/// recipient's agent info is put by the network, backing off from
+/// recipients which still can't be reached. A signal counts as delivered
+/// once the recipient's conductor acknowledges it by responding to the
+/// call, and is dropped if the recipient's conductor refuses it.
+#[derive(Clone)]
+pub(crate) struct RemoteSignalOutbox {
+    db: DbWrite<DbKindConductor>,
+    keystore: MetaLairClient,
+    holochain_p2p: holochain_p2p::HolochainP2pRef,
+    /// The signals with a delivery attempt in flight, so that the same
+    /// signal isn't sent twice when the recipient's agent info is seen
+    /// repeatedly.
+    delivering: RwShare<HashSet<i64>>,
+    /// The recipients which couldn't be reached, by DNA, with when to send
+    /// to them next and how long to wait after that if they still can't be.
+    backoff: RwShare<HashMap<(DnaHash, AgentPubKey), (tokio::time::Instant, std::time::Duration)>>,
+}
+
+/// The outcome of an attempt at delivering a signal.
+enum Delivery {
+    /// The recipient's conductor acknowledged the signal.
+    Delivered,
+    /// The recipient couldn't be reached, so the signal is sent again later.
+    Unreachable,
+    /// The recipient's conductor answered without accepting the signal,
+    /// which sending it again won't change.
+    Refused(String),
+}
+
+impl RemoteSignalOutbox {
+    pub(crate) fn new(
+        db: DbWrite<DbKindConductor>,
+        keystore: MetaLairClient,
+        holochain_p2p: holochain_p2p::HolochainP2pRef,
+    ) -> Self {
+        Self {
+            db,
+            keystore,
+            holochain_p2p,
+            delivering: RwShare::new(HashSet::new()),
+            backoff: RwShare::new(HashMap::new()),
+        }
+    }
+
+    /// Queue a signal from the given cell and make a first attempt at
+    /// delivering it.
+    pub(crate) fn send(
+        &self,
+        from: CellId,
+        to_agent: AgentPubKey,
+        zome_name: ZomeName,
+        signal: ExternIO,
+    ) {
+        let outbox = self.clone();
+        tokio::task::spawn(
+            async move {
+                match remote_signal::queue_remote_signal(
+                    &outbox.db,
+                    from,
+                    to_agent,
+                    zome_name,
+                    signal,
+                    MAX_QUEUED_REMOTE_SIGNALS_PER_CELL,
+                    REMOTE_SIGNAL_TTL,
+                )
+                .await
+                {
+                    Ok(id) => outbox.deliver_in_background(id),
+                    Err(e) => tracing::warn!(?e, "Failed to queue remote signal"),
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// Try again to deliver the undelivered signals for any of these agents
+    /// in the given DNA, unless they are being backed off from.
+    pub(crate) fn retry(&self, dna_hash: &DnaHash, agents: &[AgentPubKey]) {
+        let now = tokio::time::Instant::now();
+        let agents: Vec<_> = self.backoff.share_ref(|backoff| {
+            agents
+                .iter()
+                .filter(|agent| {
+                    !backoff
+                        .get(&(dna_hash.clone(), (*agent).clone()))
+                        .is_some_and(|(next, _)| *next > now)
+                })
+                .cloned()
+                .collect()
+        });
+        if agents.is_empty() {
+            return;
+        }
+        let outbox = self.clone();
+        let dna_hash = dna_hash.clone();
+        tokio::task::spawn(
+            async move {
+                match remote_signal::undelivered_remote_signals(
+                    &outbox.db,
+                    dna_hash,
+                    agents,
+                    REMOTE_SIGNAL_TTL,
+                )
+                .await
+                {
+                    Ok(ids) => {
+                        for id in ids {
+                            outbox.deliver_in_background(id);
+                        }
+                    }
+                    Err(e) => tracing::warn!(?e, "Failed to find remote signals to retry"),
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    /// The signals held for this cell, oldest first.
+    pub(crate) async fn deliveries(
+        &self,
+        cell_id: CellId,
+    ) -> ConductorResult<Vec<RemoteSignalDelivery>> {
+        Ok(
+            remote_signal::remote_signals(&self.db, cell_id, REMOTE_SIGNAL_TTL)
+                .await?
+                .into_iter()
+                .map(|s| RemoteSignalDelivery {
+                    to_agent: s.to_agent,
+                    zome_name: s.zome_name,
+                    signal: s.signal,
+                    queued_at: s.queued_at,
+                    attempts: s.attempts,
+                    delivered_at: s.delivered_at,
+                })
+                .collect(),
+        )
+    }
+
+    fn deliver_in_background(&self, id: i64) {
+        if !self.delivering.share_mut(|d| d.insert(id)) {
+            return;
+        }
+        let outbox = self.clone();
+        tokio::task::spawn(
+            async move {
+                if let Err(e) = outbox.attempt(id).await {
+                    tracing::info!("Failed to deliver remote signal because of {:?}", e);
+                }
+                outbox.delivering.share_mut(|d| d.remove(&id));
+            }
+            .in_current_span(),
+        );
+    }
+
+    async fn attempt(&self, id: i64) -> ConductorResult<()> {
+        let queued = match remote_signal::attempt_remote_signal(&self.db, id).await? {
+            Some(queued) => queued,
+            // Delivered or dropped since.
+            None => return Ok(()),
+        };
+        let network = self
+            .holochain_p2p
+            .to_dna(queued.from.dna_hash().clone(), None);
+        let recipient = (queued.from.dna_hash().clone(), queued.to_agent.clone());
+        match self.deliver(network, queued).await? {
+            Delivery::Delivered => {
+                self.backoff.share_mut(|backoff| backoff.remove(&recipient));
+                remote_signal::remote_signal_delivered(&self.db, id, Timestamp::now()).await?;
+            }
+            Delivery::Unreachable => self.back_off(recipient),
+            Delivery::Refused(reason) => {
+                self.backoff.share_mut(|backoff| backoff.remove(&recipient));
+                tracing::info!(
+                    "Dropping remote signal refused by its recipient: {}",
+                    reason
+                );
+                remote_signal::drop_remote_signal(&self.db, id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait longer before sending to a recipient again.
+    fn back_off(&self, recipient: (DnaHash, AgentPubKey)) {
+        self.backoff.share_mut(|backoff| {
+            let delay = backoff
+                .get(&recipient)
+                .map_or(REMOTE_SIGNAL_MIN_BACKOFF, |(_, delay)| {
+                    (*delay * 2).min(REMOTE_SIGNAL_MAX_BACKOFF)
+                });
+            backoff.insert(recipient, (tokio::time::Instant::now() + delay, delay));
+        });
+    }
+
+    /// Send the signal as a remote call, which the recipient's conductor
+    /// answers once the signal has been handed to the recipient's zome.
+    async fn deliver(
+        &self,
+        network: HolochainP2pDna,
+        queued: QueuedRemoteSignal,
+    ) -> ConductorResult<Delivery> {
+        let QueuedRemoteSignal {
+            from,
+            to_agent,
+            zome_name,
+            signal,
+            ..
+        } = queued;
+        let (nonce, expires_at) = fresh_nonce(Timestamp::now()).map_err(ConductorError::other)?;
+        let zome_call_unsigned = ZomeCallUnsigned {
+            provenance: from.agent_pubkey().clone(),
+            cell_id: CellId::new(from.dna_hash().clone(), to_agent.clone()),
+            zome_name,
+            fn_name: RECV_REMOTE_SIGNAL.into(),
+            cap_secret: None,
+            payload: signal,
+            nonce,
+            expires_at,
+        };
+        let signature = zome_call_unsigned
+            .provenance
+            .sign_raw(
+                &self.keystore,
+                zome_call_unsigned
+                    .data_to_sign()
+                    .map_err(ConductorError::other)?,
+            )
+            .await
+            .map_err(ConductorError::other)?;
+        let response = match network
+            .call_remote(
+                zome_call_unsigned.provenance,
+                signature,
+                to_agent,
+                zome_call_unsigned.zome_name,
+                zome_call_unsigned.fn_name,
+                zome_call_unsigned.cap_secret,
+                zome_call_unsigned.payload,
+                zome_call_unsigned.nonce,
+                zome_call_unsigned.expires_at,
+            )
+            .await
+        {
+            Ok(response) => response,
+            // The recipient can't be reached, try again when it is back.
+            Err(_) => return Ok(Delivery::Unreachable),
+        };
+        Ok(match ZomeCallResponse::try_from(response) {
+            Ok(ZomeCallResponse::Ok(_)) => Delivery::Delivered,
+            Ok(response) => Delivery::Refused(format!("{:?}", response)),
+            Err(e) => Delivery::Refused(e.to_string()),
+        })
+    }
+}
+
+pub(crate) async fn remote_signal_deliveries(
+    conductor: &Conductor,
+    installed_app_id: &InstalledAppId,
+    cell_id: CellId,
+) -> ConductorApiResult<Vec<RemoteSignalDelivery>> {
+    let state = conductor.get_state().await?;
+    if !state
+        .get_app(installed_app_id)?
+        .all_cells()
+        .any(|app_cell_id| *app_cell_id == cell_id)
+    {
+        return Err(ConductorError::CellMissing(cell_id).into());
+    }
+    Ok(conductor.remote_signal_outbox.deliveries(cell_id).await?)
+}
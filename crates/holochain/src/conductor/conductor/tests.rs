@@ -40,6 +40,7 @@ async fn can_update_state() {
     .unwrap();
     let conductor = Conductor::new(
         Default::default(),
+        None,
         ribosome_store,
         keystore,
         holochain_p2p,
@@ -91,6 +92,7 @@ async fn app_ids_are_unique() {
     .unwrap();
     let conductor = Conductor::new(
         Default::default(),
+        None,
         ribosome_store,
         test_keystore(),
         holochain_p2p,
@@ -1171,3 +1173,108 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reload_config_applies_live_changes_and_reports_the_rest() {
+    holochain_trace::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let handle = conductor.raw_handle();
+    let mut config = handle.running_config.share_ref(|c| c.clone());
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    config.admin_interfaces = Some(vec![AdminInterfaceConfig {
+        driver: InterfaceDriver::Websocket { port },
+    }]);
+    let mut network = config.network.clone().unwrap_or_default();
+    let mut tuning_params = (*network.tuning_params).clone();
+    tuning_params.gossip_burst_ratio /= 2.0;
+    network.tuning_params = Arc::new(tuning_params);
+    config.network = Some(network);
+    config.environment_path = test_db_dir().path().to_path_buf().into();
+
+    let reload = reload_config::reload_config(handle.clone(), config.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        reload.applied,
+        vec![
+            "admin_interfaces".to_string(),
+            "network.tuning_params.gossip_burst_ratio".to_string()
+        ]
+    );
+    // The admin interface which is no longer in the config keeps running.
+    assert_eq!(
+        reload.requires_restart,
+        vec![
+            "admin_interfaces".to_string(),
+            "environment_path".to_string()
+        ]
+    );
+    assert!(handle
+        .admin_websocket_ports
+        .share_ref(|ports| ports.len() == 2 && ports.contains(&port)));
+
+    // Nothing is applied twice.
+    let reload = reload_config::reload_config(handle.clone(), config.clone())
+        .await
+        .unwrap();
+    assert!(reload.applied.is_empty());
+    assert_eq!(
+        reload.requires_restart,
+        vec![
+            "admin_interfaces".to_string(),
+            "environment_path".to_string()
+        ]
+    );
+
+    // Invalid bandwidth limits are reported and not applied.
+    let mut network = config.network.clone().unwrap_or_default();
+    let mut tuning_params = (*network.tuning_params).clone();
+    tuning_params.gossip_burst_ratio = 0.0;
+    tuning_params.gossip_inbound_target_mbps = f64::NAN;
+    network.tuning_params = Arc::new(tuning_params);
+    config.network = Some(network);
+    let reload = reload_config::reload_config(handle.clone(), config)
+        .await
+        .unwrap();
+    assert!(reload.applied.is_empty());
+    assert_eq!(
+        reload.invalid,
+        vec![
+            "network.tuning_params.gossip_inbound_target_mbps".to_string(),
+            "network.tuning_params.gossip_burst_ratio".to_string()
+        ]
+    );
+    assert!(handle.running_config.share_ref(|c| {
+        let tuning_params = &c.network.as_ref().unwrap().tuning_params;
+        tuning_params.gossip_burst_ratio > 0.0
+            && tuning_params.gossip_inbound_target_mbps.is_finite()
+    }));
+
+    // There is no config file to reload from.
+    assert!(handle.reload_config().await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dnas_with_unreadable_spam_policy_or_metering_are_refused() {
+    use crate::core::ribosome::error::RibosomeError;
+
+    let conductor = SweetConductor::from_standard_config().await;
+    for key in ["spam_policy", "metering"] {
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+        let properties = serde_yaml::from_str(&format!("{}: not a map", key)).unwrap();
+        let dna = dna
+            .with_properties(YamlProperties::new(properties).try_into().unwrap())
+            .await;
+        assert_matches!(
+            conductor.register_dna(dna).await,
+            Err(ConductorError::RibosomeError(
+                RibosomeError::InvalidDnaProperty(..)
+            ))
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+/// The number of recordings kept for each cell. Once this many have been
+/// kept, the oldest recording is dropped for each new one.
+const MAX_RECORDINGS_PER_CELL: usize = 100;
+
+pub(crate) fn set_zome_call_recording(conductor: &Conductor, cell_id: CellId, enabled: bool) {
+    conductor.zome_call_recordings.share_mut(|r| {
+        if enabled {
+            r.entry(cell_id).or_default();
+        } else {
+            r.remove(&cell_id);
+        }
+    })
+}
+
+pub(crate) fn zome_call_recordings(
+    conductor: &Conductor,
+    cell_id: &CellId,
+) -> Vec<ZomeCallRecording> {
+    conductor.zome_call_recordings.share_ref(|r| {
+        r.get(cell_id)
+            .map(|recordings| recordings.iter().cloned().collect())
+            .unwrap_or_default()
+    })
+}
+
+pub(crate) fn add_zome_call_recording(conductor: &Conductor, recording: ZomeCallRecording) {
+    conductor.zome_call_recordings.share_mut(|r| {
+        // Recording may have been switched off while the call was running.
+        if let Some(recordings) = r.get_mut(&recording.call.cell_id) {
+            if recordings.len() >= MAX_RECORDINGS_PER_CELL {
+                recordings.pop_front();
+            }
+            recordings.push_back(recording);
+        }
+    })
+}
+
+pub(crate) async fn replay_zome_call(
+    conductor: &Conductor,
+    recording: ZomeCallRecording,
+) -> ConductorApiResult<ZomeCallResult> {
+    let cell = conductor.cell_by_id(&recording.call.cell_id, false).await?;
+    Ok(cell.replay_zome_call(recording).await?)
+}
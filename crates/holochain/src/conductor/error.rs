@@ -23,6 +23,9 @@ pub enum ConductorError {
     #[error(transparent)]
     AppBundleError(#[from] AppBundleError),
 
+    #[error("App {0} has reached its quota of {1}")]
+    AppQuotaExceeded(InstalledAppId, String),
+
     #[error(transparent)]
     DatabaseError(#[from] DatabaseError),
 
@@ -87,6 +90,9 @@ pub enum ConductorError {
     #[error("Wasm code was not found in the wasm store")]
     WasmMissing,
 
+    #[error("No live capability grant was created by the action: {0}")]
+    CapGrantMissing(ActionHash),
+
     #[error("Tried to access an app that was not installed: {0}")]
     AppNotInstalled(InstalledAppId),
 
@@ -499,8 +499,12 @@ pub mod test {
                             total_network_peers: 1,
                             bytes_since_last_time_queried: 1844,
                             completed_rounds_since_last_time_queried: 0,
+                            ..info[0].clone()
                         }]
-                    )
+                    );
+                    // a lone agent holds the whole DHT and has no one to gossip with
+                    assert_eq!(info[0].target_arc_size, 1.0);
+                    assert!(info[0].peer_gossip_info.is_empty());
                 }
                 other => panic!("unexpected response {:?}", other),
             }
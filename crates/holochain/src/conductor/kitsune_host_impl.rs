@@ -21,8 +21,12 @@ use holochain_types::{
 };
 use holochain_zome_types::Timestamp;
 use kitsune_p2p::{
-    agent_store::AgentInfoSigned, dependencies::kitsune_p2p_fetch::OpHashSized,
-    event::GetAgentInfoSignedEvt, KitsuneHost, KitsuneHostResult,
+    agent_store::AgentInfoSigned,
+    dependencies::kitsune_p2p_fetch::{
+        FetchContext, FetchPoolConfig, FetchPoolRecord, FetchPriority, OpHashSized,
+    },
+    event::GetAgentInfoSignedEvt,
+    KitsuneHost, KitsuneHostResult,
 };
 use kitsune_p2p_types::{
     config::KitsuneP2pTuningParams, dependencies::lair_keystore_api, KOpData, KOpHash,
@@ -60,6 +64,35 @@ impl KitsuneHostImpl {
     }
 }
 
+impl FetchPoolConfig for KitsuneHostImpl {
+    fn space_quota(&self) -> Option<usize> {
+        match self.tuning_params.fetch_pool_space_quota {
+            0 => None,
+            quota => Some(quota as usize),
+        }
+    }
+
+    /// Ops which we are being asked to hold as an authority, and ops which are
+    /// part of a countersigning session, are fetched before anything else.
+    fn fetch_priority(&self, context: Option<FetchContext>) -> FetchPriority {
+        use holochain_p2p::FetchContextExt;
+
+        match context {
+            Some(context)
+                if context.has_request_validation_receipt()
+                    || context.has_countersigning_session() =>
+            {
+                FetchPriority::High
+            }
+            _ => FetchPriority::Normal,
+        }
+    }
+
+    fn merge_fetch_contexts(&self, a: u32, b: u32) -> u32 {
+        a | b
+    }
+}
+
 impl KitsuneHost for KitsuneHostImpl {
     fn block(&self, input: kitsune_p2p_block::Block) -> KitsuneHostResult<()> {
         async move {
@@ -286,6 +319,64 @@ impl KitsuneHost for KitsuneHostImpl {
         .into()
     }
 
+    fn persist_fetch_pool(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        records: Vec<FetchPoolRecord>,
+    ) -> KitsuneHostResult<()> {
+        use holochain_state::query::{to_blob, StateQueryResult};
+
+        async move {
+            let db = self.spaces.dht_db(&DnaHash::from_kitsune(&space))?;
+            db.async_commit(move |txn| {
+                txn.execute("DELETE FROM FetchPoolItem", [])?;
+                for record in records {
+                    txn.execute(
+                        "INSERT INTO FetchPoolItem (key, blob) VALUES (?, ?)",
+                        [to_blob(&record.key)?, to_blob(&record)?],
+                    )?;
+                }
+                StateQueryResult::Ok(())
+            })
+            .await?;
+            Ok(())
+        }
+        .boxed()
+        .into()
+    }
+
+    fn load_fetch_pool(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<FetchPoolRecord>> {
+        use holochain_state::query::{from_blob, StateQueryResult};
+
+        async move {
+            let db = self.spaces.dht_db(&DnaHash::from_kitsune(&space))?;
+            let records = db
+                .async_reader(move |txn| {
+                    let mut stmt = txn.prepare("SELECT blob FROM FetchPoolItem")?;
+                    let blobs = stmt
+                        .query_map([], |row| row.get::<_, Vec<u8>>(0))?
+                        .collect::<Result<Vec<_>, _>>()?;
+                    blobs
+                        .into_iter()
+                        .map(from_blob)
+                        .collect::<StateQueryResult<Vec<FetchPoolRecord>>>()
+                })
+                .await?;
+            Ok(records)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn verify_agent_info_signed(&self, agent_info: AgentInfoSigned) -> KitsuneHostResult<bool> {
+        async move { Ok(verify_agent_info_signature(&agent_info).await) }
+            .boxed()
+            .into()
+    }
+
     fn lair_tag(&self) -> Option<Arc<str>> {
         self.lair_tag.clone()
     }
@@ -294,3 +385,83 @@ impl KitsuneHost for KitsuneHostImpl {
         self.lair_client.clone()
     }
 }
+
+/// Check that an agent info was signed by the agent it describes.
+async fn verify_agent_info_signature(agent_info: &AgentInfoSigned) -> bool {
+    use holochain_keystore::AgentPubKeyExt as _;
+    use holochain_p2p::AgentPubKeyExt as _;
+
+    let signature: [u8; 64] = match agent_info.signature.0.as_slice().try_into() {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let agent = holo_hash::AgentPubKey::from_kitsune(&agent_info.agent);
+    agent
+        .verify_signature_raw(
+            &holochain_zome_types::Signature(signature),
+            agent_info.encoded_bytes.to_vec().into(),
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holo_hash::AgentPubKey;
+    use holochain_keystore::{AgentPubKeyExt, MetaLairClient};
+    use holochain_p2p::AgentPubKeyExt as _;
+    use kitsune_p2p_types::bin_types::KitsuneSignature;
+    use kitsune_p2p_types::codec::{rmp_decode, rmp_encode};
+    use kitsune_p2p_types::KitsuneError;
+
+    /// Sign an agent info for `agent` with the key of `signer`, and send it
+    /// through the encoding used for mdns broadcasts.
+    async fn mdns_announcement(
+        keystore: &MetaLairClient,
+        agent: &AgentPubKey,
+        signer: &AgentPubKey,
+    ) -> AgentInfoSigned {
+        let signed_at_ms = Timestamp::now().as_millis() as u64;
+        let info = AgentInfoSigned::sign(
+            DnaHash::from_raw_32(vec![0; 32]).to_kitsune(),
+            agent.to_kitsune(),
+            u32::MAX,
+            vec![],
+            signed_at_ms,
+            signed_at_ms + 60_000,
+            |bytes| {
+                let bytes = bytes.to_vec();
+                let keystore = keystore.clone();
+                let signer = signer.clone();
+                async move {
+                    let signature = signer
+                        .sign_raw(&keystore, bytes.into())
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    Ok(Arc::new(KitsuneSignature(signature.0.to_vec())))
+                }
+            },
+        )
+        .await
+        .unwrap();
+        let mut buffer = Vec::new();
+        rmp_encode(&mut buffer, &info).unwrap();
+        rmp_decode(&mut buffer.as_slice()).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn mdns_agent_infos_are_only_accepted_if_signed_by_their_agent() {
+        let keystore = holochain_keystore::test_keystore::spawn_test_keystore()
+            .await
+            .unwrap();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        let mallory = keystore.new_sign_keypair_random().await.unwrap();
+
+        let signed = mdns_announcement(&keystore, &alice, &alice).await;
+        assert!(verify_agent_info_signature(&signed).await);
+
+        // Mallory announces an agent info claiming to be Alice.
+        let forged = mdns_announcement(&keystore, &alice, &mallory).await;
+        assert!(!verify_agent_info_signature(&forged).await);
+    }
+}
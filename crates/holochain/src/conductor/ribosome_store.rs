@@ -1,14 +1,18 @@
 use holochain_types::{prelude::*, share::RwShare};
 use holochain_zome_types::entry_def::EntryDef;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use tracing::*;
 
-use crate::core::ribosome::{error::RibosomeResult, real_ribosome::RealRibosome, RibosomeT};
+use crate::core::ribosome::{
+    error::RibosomeResult, module_cache::ModuleDiskCache, real_ribosome::RealRibosome, RibosomeT,
+};
 
 #[derive(Default, Debug)]
 pub struct RibosomeStore {
     ribosomes: HashMap<DnaHash, RealRibosome>,
     entry_defs: HashMap<EntryDefBufferKey, EntryDef>,
+    module_disk_cache: Option<ModuleDiskCache>,
 }
 
 impl RibosomeStore {
@@ -16,26 +20,55 @@ impl RibosomeStore {
         RwShare::new(RibosomeStore {
             ribosomes: HashMap::new(),
             entry_defs: HashMap::new(),
+            module_disk_cache: None,
         })
     }
 
+    /// Cache the modules compiled by every ribosome in this store on disk.
+    pub fn set_module_disk_cache(&mut self, cache: ModuleDiskCache) {
+        for ribosome in self.ribosomes.values_mut() {
+            ribosome.module_disk_cache = Some(cache.clone());
+        }
+        self.module_disk_cache = Some(cache);
+    }
+
+    /// The disk cache of the modules compiled by this store's ribosomes, if any.
+    pub fn module_disk_cache(&self) -> Option<&ModuleDiskCache> {
+        self.module_disk_cache.as_ref()
+    }
+
     #[instrument]
     pub fn add_dna(&mut self, dna: DnaFile) -> RibosomeResult<()> {
-        let hash = dna.dna_hash().clone();
         let ribosome = RealRibosome::new(dna)?;
-        self.ribosomes.insert(hash, ribosome);
+        self.add_ribosome(ribosome);
         Ok(())
     }
 
-    pub fn add_ribosome(&mut self, ribosome: RealRibosome) {
-        self.ribosomes.insert(ribosome.dna_hash().clone(), ribosome);
+    /// Add a ribosome to the store, returning it as stored
+    /// with this store's disk cache of compiled modules.
+    pub fn add_ribosome(&mut self, mut ribosome: RealRibosome) -> RealRibosome {
+        ribosome.module_disk_cache = self.module_disk_cache.clone();
+        self.ribosomes
+            .insert(ribosome.dna_hash().clone(), ribosome.clone());
+        ribosome
     }
 
     pub fn add_ribosomes<T: IntoIterator<Item = (DnaHash, RealRibosome)> + 'static>(
         &mut self,
         ribosomes: T,
     ) {
-        self.ribosomes.extend(ribosomes);
+        for (hash, mut ribosome) in ribosomes {
+            ribosome.module_disk_cache = self.module_disk_cache.clone();
+            self.ribosomes.insert(hash, ribosome);
+        }
+    }
+
+    /// The hashes of every wasm used by any DNA.
+    pub fn wasm_hashes(&self) -> HashSet<WasmHash> {
+        self.ribosomes
+            .values()
+            .flat_map(|r| r.dna_file().code().keys().cloned())
+            .collect()
     }
 
     #[instrument]
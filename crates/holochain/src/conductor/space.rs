@@ -719,7 +719,7 @@ impl Spaces {
                 // If the workflow has not been spawned yet we can't handle incoming messages.
                 None => return Ok(()),
             };
-            incoming_countersigning(ops, &workspace, trigger)?;
+            incoming_countersigning(ops, &workspace, trigger).await?;
         } else {
             let space = self.get_or_create_space(dna_hash)?;
             let trigger = match self
@@ -790,7 +790,7 @@ impl Space {
         ));
         let p2p_batch_sender = tx;
 
-        let countersigning_workspace = CountersigningWorkspace::new();
+        let countersigning_workspace = CountersigningWorkspace::persisted(authored_db.clone());
         let incoming_op_hashes = IncomingOpHashes::default();
         let incoming_ops_batch = IncomingOpsBatch::default();
         let dht_query_cache = DhtDbQueryCache::new(dht_db.clone().into());
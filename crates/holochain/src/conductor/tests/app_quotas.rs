@@ -0,0 +1,308 @@
+use super::dht_cache::{cache_op, cached_op_count};
+use crate::{
+    conductor::{api::error::ConductorApiError, error::ConductorError},
+    sweettest::*,
+};
+use holo_hash::ActionHash;
+use holochain_types::app::{
+    AppQuotas, CloneCellId, CreateCloneCellPayload, DisableCloneCellPayload,
+};
+use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::{
+    CapAccess, DnaModifiersOpt, GrantZomeCallCapabilityPayload, GrantedFunctions, Record,
+    Timestamp, ZomeCallCapGrant,
+};
+use matches::matches;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn zome_calls_are_refused_over_the_concurrent_zome_call_quota() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    conductor
+        .set_app_quotas(
+            app.installed_app_id().clone(),
+            AppQuotas {
+                max_concurrent_zome_calls: Some(0),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let result: Result<Option<Record>, _> = conductor.call_fallible(&zome, "get_entry", ()).await;
+    assert!(matches!(
+        result,
+        Err(ConductorApiError::ConductorError(
+            ConductorError::AppQuotaExceeded(..)
+        ))
+    ));
+
+    conductor
+        .set_app_quotas(app.installed_app_id().clone(), AppQuotas::default())
+        .await
+        .unwrap();
+    let _: Option<Record> = conductor.call(&zome, "get_entry", ()).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn writes_are_refused_over_the_authored_data_quota() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    // The genesis records alone exceed a quota of one byte.
+    conductor
+        .set_app_quotas(
+            app.installed_app_id().clone(),
+            AppQuotas {
+                max_authored_bytes: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let result: Result<ActionHash, _> = conductor.call_fallible(&zome, "create_entry", ()).await;
+    assert!(result.is_err());
+
+    // Calls which don't write are still allowed.
+    let _: Option<Record> = conductor.call(&zome, "get_entry", ()).await;
+
+    // Within the quota, what is written is counted against it.
+    conductor
+        .set_app_quotas(
+            app.installed_app_id().clone(),
+            AppQuotas {
+                max_authored_bytes: Some(1_000_000),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let authored_data_size = || async {
+        conductor
+            .storage_info()
+            .await
+            .unwrap()
+            .apps
+            .into_iter()
+            .find(|a| &a.installed_app_id == app.installed_app_id())
+            .unwrap()
+            .authored_data_size
+    };
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+    let before = authored_data_size().await;
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+    assert!(authored_data_size().await > before);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_writes_cannot_together_exceed_the_authored_data_quota() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    let authored_data_size = || async {
+        conductor
+            .storage_info()
+            .await
+            .unwrap()
+            .apps
+            .into_iter()
+            .find(|a| &a.installed_app_id == app.installed_app_id())
+            .unwrap()
+            .authored_data_size
+    };
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+    let before = authored_data_size().await;
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+    let after = authored_data_size().await;
+
+    // Room for one more write but not two.
+    let write_size = after - before;
+    conductor
+        .set_app_quotas(
+            app.installed_app_id().clone(),
+            AppQuotas {
+                max_authored_bytes: Some((after + write_size + write_size / 2) as u64),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let (a, b): (Result<ActionHash, _>, Result<ActionHash, _>) = futures::join!(
+        conductor.call_fallible(&zome, "create_entry", ()),
+        conductor.call_fallible(&zome, "create_entry", ()),
+    );
+    assert_eq!(a.is_ok() as u8 + b.is_ok() as u8, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn writes_outside_zome_calls_are_counted_against_the_authored_data_quota() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id().clone();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    conductor
+        .set_app_quotas(
+            app.installed_app_id().clone(),
+            AppQuotas {
+                max_authored_bytes: Some(1_000_000),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let authored_data_size = || async {
+        conductor
+            .storage_info()
+            .await
+            .unwrap()
+            .apps
+            .into_iter()
+            .find(|a| &a.installed_app_id == app.installed_app_id())
+            .unwrap()
+            .authored_data_size
+    };
+    // The first write counts what the agent has authored so far.
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+    let before = authored_data_size().await;
+
+    conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id,
+            cap_grant: ZomeCallCapGrant {
+                tag: "anyone".into(),
+                functions: GrantedFunctions::All,
+                access: CapAccess::Unrestricted,
+                expires_at: None,
+                max_uses: None,
+                curry_payloads: Default::default(),
+            },
+        })
+        .await
+        .unwrap();
+    assert!(authored_data_size().await > before);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn zome_calls_are_not_refused_over_the_cache_quota() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    // The ops in the cache of a DNA which a running cell is an authority
+    // for can't be evicted, so fill the cache of a disabled clone's DNA.
+    let clone = conductor
+        .clone()
+        .create_clone_cell(CreateCloneCellPayload {
+            app_id: app.installed_app_id().clone(),
+            role_name: dna.dna_hash().to_string(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("clone".to_string()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    conductor
+        .disable_clone_cell(&DisableCloneCellPayload {
+            app_id: app.installed_app_id().clone(),
+            clone_cell_id: CloneCellId::CloneId(clone.clone_id),
+        })
+        .await
+        .unwrap();
+    let clone_dna_hash = clone.cell_id.dna_hash();
+    for _ in 0..10 {
+        cache_op(&conductor, clone_dna_hash, Timestamp::now()).await;
+    }
+    assert_eq!(cached_op_count(&conductor, clone_dna_hash).await, 10);
+
+    // An empty cache is already bigger than one byte, so the quota is
+    // enforced by evicting from the cache instead.
+    conductor
+        .set_app_quotas(
+            app.installed_app_id().clone(),
+            AppQuotas {
+                max_cache_bytes: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let _: Option<Record> = conductor.call(&zome, "get_entry", ()).await;
+    assert!(cached_op_count(&conductor, clone_dna_hash).await < 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn apps_sharing_a_dna_are_each_charged_for_part_of_its_cache() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let other_app = conductor.setup_app("other_app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome(TestWasm::Create);
+
+    let _: ActionHash = conductor.call(&zome, "create_entry", ()).await;
+
+    // Both apps have cells of the DNA, so each is charged for half its cache.
+    let storage_info = conductor.storage_info().await.unwrap();
+    let cache_data_size = |app: &SweetApp| {
+        storage_info
+            .apps
+            .iter()
+            .find(|a| &a.installed_app_id == app.installed_app_id())
+            .unwrap()
+            .cache_data_size
+    };
+    assert!(cache_data_size(&app) > 0);
+    assert_eq!(cache_data_size(&app), cache_data_size(&other_app));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clone_cells_are_refused_over_the_clone_cell_quota() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let role_name = dna.dna_hash().to_string();
+    let quotas = AppQuotas {
+        max_clone_cells: Some(1),
+        ..Default::default()
+    };
+    conductor
+        .set_app_quotas(app.installed_app_id().clone(), quotas.clone())
+        .await
+        .unwrap();
+
+    let create_clone_cell = |network_seed: &str| CreateCloneCellPayload {
+        app_id: app.installed_app_id().clone(),
+        role_name: role_name.clone(),
+        modifiers: DnaModifiersOpt::none().with_network_seed(network_seed.to_string()),
+        membrane_proof: None,
+        name: None,
+    };
+    conductor
+        .clone()
+        .create_clone_cell(create_clone_cell("seed_1"))
+        .await
+        .unwrap();
+    let result = conductor
+        .clone()
+        .create_clone_cell(create_clone_cell("seed_2"))
+        .await;
+    assert!(matches!(result, Err(ConductorError::AppQuotaExceeded(..))));
+
+    let storage_info = conductor.storage_info().await.unwrap();
+    let app_info = storage_info
+        .apps
+        .iter()
+        .find(|a| &a.installed_app_id == app.installed_app_id())
+        .unwrap();
+    assert_eq!(app_info.clone_cells, 1);
+    assert_eq!(app_info.quotas, quotas);
+    assert!(app_info.authored_data_size > 0);
+}
@@ -0,0 +1,104 @@
+use crate::sweettest::*;
+use ::fixt::prelude::*;
+use holo_hash::{DnaHash, HasHash};
+use holochain_conductor_api::conductor::DhtCacheConfig;
+use holochain_serialized_bytes::UnsafeBytes;
+use holochain_sqlite::prelude::*;
+use holochain_state::prelude::{insert_op, set_when_integrated, StateMutationResult};
+use holochain_types::fixt::*;
+use holochain_types::prelude::{DhtOp, DhtOpHashed, NewEntryAction};
+use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::{AppEntryBytes, Entry, Timestamp};
+
+/// Insert an op into the cache of a DNA as if it was fetched at this time.
+pub(super) async fn cache_op(
+    conductor: &SweetConductor,
+    dna_hash: &DnaHash,
+    fetched_at: Timestamp,
+) {
+    let entry = Box::new(Entry::App(AppEntryBytes(
+        UnsafeBytes::from(vec![1; 1000]).try_into().unwrap(),
+    )));
+    let action = NewEntryAction::Create(fixt!(Create));
+    let op = DhtOpHashed::from_content_sync(DhtOp::StoreEntry(fixt!(Signature), action, entry));
+    conductor
+        .spaces
+        .cache(dna_hash)
+        .unwrap()
+        .async_commit(move |txn| {
+            insert_op(txn, &op)?;
+            set_when_integrated(txn, op.as_hash(), fetched_at)?;
+            StateMutationResult::Ok(())
+        })
+        .await
+        .unwrap();
+}
+
+pub(super) async fn cached_op_count(conductor: &SweetConductor, dna_hash: &DnaHash) -> usize {
+    conductor
+        .spaces
+        .cache(dna_hash)
+        .unwrap()
+        .async_reader(|txn| {
+            txn.query_row("SELECT COUNT(*) FROM DhtOp", [], |row| row.get(0))
+                .map_err(DatabaseError::from)
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ops_older_than_the_max_age_are_evicted() {
+    let mut config = SweetConductorConfig::standard();
+    config.dht_cache = Some(DhtCacheConfig {
+        max_age_s: Some(60 * 60),
+        ..Default::default()
+    });
+    let conductor = SweetConductor::from_config(config).await;
+    // Without any cells of the DNA, this conductor is no authority for any of its ops.
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    conductor.register_dna(dna.clone()).await.unwrap();
+
+    let two_hours_ago =
+        Timestamp::now().saturating_sub(&std::time::Duration::from_secs(2 * 60 * 60));
+    cache_op(&conductor, dna.dna_hash(), two_hours_ago).await;
+    cache_op(&conductor, dna.dna_hash(), Timestamp::now()).await;
+    assert_eq!(cached_op_count(&conductor, dna.dna_hash()).await, 2);
+
+    conductor.evict_dht_caches().await.unwrap();
+    assert_eq!(cached_op_count(&conductor, dna.dna_hash()).await, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ops_are_evicted_until_the_cache_is_within_its_size_limit() {
+    let mut config = SweetConductorConfig::standard();
+    config.dht_cache = Some(DhtCacheConfig {
+        max_bytes_per_dna: Some(1),
+        ..Default::default()
+    });
+    let conductor = SweetConductor::from_config(config).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    conductor.register_dna(dna.clone()).await.unwrap();
+
+    for _ in 0..10 {
+        cache_op(&conductor, dna.dna_hash(), Timestamp::now()).await;
+    }
+
+    // An empty database is still bigger than one byte, so every op is evicted.
+    conductor.evict_dht_caches().await.unwrap();
+    assert_eq!(cached_op_count(&conductor, dna.dna_hash()).await, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn clear_dht_cache_deletes_all_cached_ops() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    assert!(conductor.clear_dht_cache(dna.dna_hash()).await.is_err());
+    conductor.register_dna(dna.clone()).await.unwrap();
+
+    cache_op(&conductor, dna.dna_hash(), Timestamp::now()).await;
+    cache_op(&conductor, dna.dna_hash(), Timestamp::now()).await;
+
+    assert_eq!(conductor.clear_dht_cache(dna.dna_hash()).await.unwrap(), 2);
+    assert_eq!(cached_op_count(&conductor, dna.dna_hash()).await, 0);
+}
@@ -52,6 +52,7 @@ async fn clone_only_provisioning_creates_no_cell_and_allows_cloning() {
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_1".into()),
             network_seed: None,
+            quotas: Default::default(),
             membrane_proofs: HashMap::new(),
         }
     }
@@ -162,6 +163,7 @@ async fn reject_duplicate_app_for_same_agent() {
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_1".into()),
             network_seed: None,
+            quotas: Default::default(),
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -182,6 +184,7 @@ async fn reject_duplicate_app_for_same_agent() {
             installed_app_id: Some("app_2".into()),
             membrane_proofs: HashMap::new(),
             network_seed: None,
+            quotas: Default::default(),
         })
         .await;
     assert_matches!(
@@ -207,6 +210,7 @@ async fn reject_duplicate_app_for_same_agent() {
             installed_app_id: Some("app_2".into()),
             membrane_proofs: HashMap::new(),
             network_seed: None,
+            quotas: Default::default(),
         })
         .await;
     assert_matches!(
@@ -226,6 +230,7 @@ async fn reject_duplicate_app_for_same_agent() {
             installed_app_id: Some("app_2".into()),
             membrane_proofs: HashMap::new(),
             network_seed: Some("network".into()),
+            quotas: Default::default(),
         })
         .await;
     assert!(valid_install_of_second_app.is_ok());
@@ -277,6 +282,7 @@ async fn can_install_app_a_second_time_using_nothing_but_the_manifest_from_app_i
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_1".into()),
             network_seed: Some("final seed".into()),
+            quotas: Default::default(),
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -319,6 +325,7 @@ async fn can_install_app_a_second_time_using_nothing_but_the_manifest_from_app_i
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_2".into()),
             network_seed: None,
+            quotas: Default::default(),
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -383,6 +390,7 @@ async fn network_seed_regression() {
             source: AppBundleSource::Bundle(bundle1),
             installed_app_id: Some("no-seed".into()),
             network_seed: None,
+            quotas: Default::default(),
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -395,6 +403,7 @@ async fn network_seed_regression() {
             source: AppBundleSource::Bundle(bundle2),
             installed_app_id: Some("yes-seed".into()),
             network_seed: Some("seed".into()),
+            quotas: Default::default(),
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -648,6 +657,7 @@ impl TestCase {
                 source,
                 installed_app_id: Some(case_str.clone()),
                 network_seed,
+                quotas: Default::default(),
                 membrane_proofs: HashMap::new(),
             })
             .await
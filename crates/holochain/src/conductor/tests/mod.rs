@@ -1,6 +1,9 @@
 mod app_info;
+mod app_quotas;
 mod cell_cloning;
+mod dht_cache;
 mod install_app_bundle;
 mod network_info;
+mod remote_signals;
 mod request_dna_def;
 mod signed_zome_call;
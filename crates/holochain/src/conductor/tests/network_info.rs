@@ -33,6 +33,9 @@ async fn network_info() {
     assert_eq!(network_info[0].total_network_peers, 3);
     assert_eq!(network_info[0].completed_rounds_since_last_time_queried, 0);
     assert!(network_info[0].bytes_since_last_time_queried > 0);
+    // with full arcs, every peer holds every point of the DHT
+    assert_eq!(network_info[0].target_arc_size, 1.0);
+    assert!(network_info[0].extrapolated_coverage.is_some());
 
     // query since previous query should return 0 received bytes
     let last_time_queried = Timestamp::now();
@@ -0,0 +1,85 @@
+use crate::sweettest::*;
+use ::fixt::prelude::*;
+use holochain_types::prelude::*;
+use holochain_wasm_test_utils::TestWasm;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reliable_remote_signal_is_delivered_when_the_recipient_returns() {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::EmitSignal]).await;
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let apps = conductors.setup_app("app", &[dna]).await.unwrap();
+    let ((alice,), (bob,)) = apps.into_tuples();
+    conductors.exchange_peer_info().await;
+
+    // Bob goes offline before alice sends the signal.
+    conductors[1].shutdown().await;
+
+    let signal = fixt!(ExternIo);
+    let _: () = conductors[0]
+        .call(
+            &alice.zome(TestWasm::EmitSignal),
+            "signal_others_reliably",
+            RemoteSignal {
+                signal: signal.clone(),
+                agents: vec![bob.agent_pubkey().clone()],
+                reliable: true,
+            },
+        )
+        .await;
+
+    // The signal is queued but can't be delivered while bob is offline.
+    let alice_conductor = conductors[0].raw_handle();
+    let deliveries = || async {
+        alice_conductor
+            .remote_signal_deliveries(&"app".to_string(), alice.cell_id().clone())
+            .await
+            .unwrap()
+    };
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while deliveries().await.first().map_or(0, |d| d.attempts) == 0 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .unwrap();
+    let queued = deliveries().await;
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].to_agent, *bob.agent_pubkey());
+    assert_eq!(queued[0].delivered_at, None);
+
+    // Bob comes back online, and alice sees his agent info again.
+    conductors[1].startup().await;
+    let mut rxs = conductors[1].signal_broadcaster().subscribe_separately();
+    let mut rx = rxs.pop().unwrap();
+    tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            let bob_infos = conductors[1]
+                .get_agent_infos(Some(bob.cell_id().clone()))
+                .await
+                .unwrap();
+            alice_conductor.add_agent_infos(bob_infos).await.unwrap();
+            if deliveries().await[0].delivered_at.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+    })
+    .await
+    .expect("the signal was never delivered");
+
+    // Bob received the retried signal.
+    let received = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        received,
+        Signal::App {
+            cell_id: bob.cell_id().clone(),
+            zome_name: TestWasm::EmitSignal.coordinator_zome_name(),
+            signal: AppSignal::new(signal),
+        }
+    );
+    assert!(deliveries().await[0].attempts >= 2);
+}
@@ -59,6 +59,9 @@ async fn signed_zome_call() {
             secret: cap_access_secret,
             assignees,
         },
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     };
 
     // request authorization of signing key for agent's own cell should succeed
@@ -200,6 +203,9 @@ async fn signed_zome_call_wildcard() {
             secret: cap_access_secret,
             assignees,
         },
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     };
 
     // request authorization of signing key for agent's own cell should succeed
@@ -268,3 +274,283 @@ async fn signed_zome_call_wildcard() {
         .unwrap();
     assert_matches!(response, holochain_zome_types::ZomeCallResponse::Ok(_));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "test_utils")]
+async fn signed_zome_call_with_limited_uses() {
+    use holochain_conductor_api::ZomeCall;
+    use holochain_state::nonce::fresh_nonce;
+    use holochain_zome_types::{
+        CapAccess, ExternIO, Timestamp, ZomeCallCapGrant, ZomeCallResponse, ZomeCallUnsigned,
+    };
+    use matches::assert_matches;
+
+    let zome = TestWasm::Create;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![zome]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id();
+
+    let cap_access_public_key = fixt!(AgentPubKey, fixt::Predictable, 1);
+    let mut buf = arbitrary::Unstructured::new(&[]);
+    let cap_access_secret = CapSecret::arbitrary(&mut buf).unwrap();
+    let cap_grant = ZomeCallCapGrant::new(
+        "once".into(),
+        CapAccess::from((cap_access_secret, cap_access_public_key.clone())),
+        GrantedFunctions::All,
+    )
+    .with_max_uses(1);
+    conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id: cell_id.clone(),
+            cap_grant,
+        })
+        .await
+        .unwrap();
+
+    let call = || async {
+        let (nonce, expires_at) = fresh_nonce(Timestamp::now()).unwrap();
+        conductor
+            .call_zome(
+                ZomeCall::try_from_unsigned_zome_call(
+                    &conductor.keystore(),
+                    ZomeCallUnsigned {
+                        provenance: cap_access_public_key.clone(),
+                        cell_id: cell_id.clone(),
+                        zome_name: zome.coordinator_zome_name(),
+                        fn_name: "get_entry".into(),
+                        cap_secret: Some(cap_access_secret),
+                        payload: ExternIO::encode(()).unwrap(),
+                        nonce,
+                        expires_at,
+                    },
+                )
+                .await
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+    };
+
+    // the grant can be used once
+    assert_matches!(call().await, ZomeCallResponse::Ok(_));
+    // and is used up after that
+    assert_matches!(call().await, ZomeCallResponse::Unauthorized(..));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "test_utils")]
+async fn signed_zome_call_with_curried_payload() {
+    use holochain_conductor_api::ZomeCall;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_state::nonce::fresh_nonce;
+    use holochain_zome_types::{
+        CapAccess, ExternIO, Timestamp, ZomeCallCapGrant, ZomeCallResponse, ZomeCallUnsigned,
+    };
+    use matches::assert_matches;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Transfer {
+        from: String,
+        to: String,
+        amount: u32,
+    }
+    #[derive(Debug, Serialize, Deserialize)]
+    struct FromAccount {
+        from: String,
+    }
+    #[derive(Debug, Serialize, Deserialize)]
+    struct PartialTransfer {
+        to: String,
+        amount: u32,
+    }
+
+    let zome = TestWasm::Capability;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![zome]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id();
+
+    // grant transfers from alice's account only
+    let cap_access_public_key = fixt!(AgentPubKey, fixt::Predictable, 1);
+    let mut buf = arbitrary::Unstructured::new(&[]);
+    let cap_access_secret = CapSecret::arbitrary(&mut buf).unwrap();
+    let granted_function: GrantedFunction = (zome.coordinator_zome_name(), "echo_transfer".into());
+    let from_alice = SerializedBytes::try_from(UnsafeBytes::from(
+        holochain_serialized_bytes::encode(&FromAccount {
+            from: "alice".into(),
+        })
+        .unwrap(),
+    ))
+    .unwrap();
+    let cap_grant = ZomeCallCapGrant::new(
+        "alice's account".into(),
+        CapAccess::from((cap_access_secret, cap_access_public_key.clone())),
+        GrantedFunctions::Listed([granted_function.clone()].into()),
+    )
+    .with_curry_payload(granted_function, from_alice);
+    conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id: cell_id.clone(),
+            cap_grant,
+        })
+        .await
+        .unwrap();
+
+    let call = |payload: ExternIO| async {
+        let (nonce, expires_at) = fresh_nonce(Timestamp::now()).unwrap();
+        conductor
+            .call_zome(
+                ZomeCall::try_from_unsigned_zome_call(
+                    &conductor.keystore(),
+                    ZomeCallUnsigned {
+                        provenance: cap_access_public_key.clone(),
+                        cell_id: cell_id.clone(),
+                        zome_name: zome.coordinator_zome_name(),
+                        fn_name: "echo_transfer".into(),
+                        cap_secret: Some(cap_access_secret),
+                        payload,
+                        nonce,
+                        expires_at,
+                    },
+                )
+                .await
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+    };
+
+    // the zome function receives the account fixed by the grant
+    let response = call(
+        ExternIO::encode(PartialTransfer {
+            to: "bob".into(),
+            amount: 5,
+        })
+        .unwrap(),
+    )
+    .await;
+    let transfer: Transfer = match response {
+        ZomeCallResponse::Ok(output) => output.decode().unwrap(),
+        other => panic!("unexpected response {:?}", other),
+    };
+    assert_eq!(
+        transfer,
+        Transfer {
+            from: "alice".into(),
+            to: "bob".into(),
+            amount: 5,
+        }
+    );
+
+    // a transfer from another account isn't covered by the grant
+    let response = call(
+        ExternIO::encode(Transfer {
+            from: "mallory".into(),
+            to: "bob".into(),
+            amount: 5,
+        })
+        .unwrap(),
+    )
+    .await;
+    assert_matches!(response, ZomeCallResponse::Unauthorized(..));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "test_utils")]
+async fn list_and_revoke_zome_call_capability() {
+    use holochain_conductor_api::ZomeCall;
+    use holochain_state::nonce::fresh_nonce;
+    use holochain_zome_types::{
+        CapAccess, ExternIO, Timestamp, ZomeCallCapGrant, ZomeCallResponse, ZomeCallUnsigned,
+    };
+    use matches::assert_matches;
+
+    let zome = TestWasm::Create;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![zome]).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let cell_id = app.cells()[0].cell_id();
+
+    let cap_access_public_key = fixt!(AgentPubKey, fixt::Predictable, 1);
+    let mut buf = arbitrary::Unstructured::new(&[]);
+    let cap_access_secret = CapSecret::arbitrary(&mut buf).unwrap();
+    let cap_grant = ZomeCallCapGrant::new(
+        "browser".into(),
+        CapAccess::from((cap_access_secret, cap_access_public_key.clone())),
+        GrantedFunctions::All,
+    );
+    conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id: cell_id.clone(),
+            cap_grant: cap_grant.clone(),
+        })
+        .await
+        .unwrap();
+
+    let grants = conductor
+        .list_capability_grants(cell_id.clone(), false)
+        .await
+        .unwrap();
+    let grant = grants
+        .iter()
+        .find(|info| info.cap_grant == cap_grant)
+        .unwrap()
+        .clone();
+    assert_eq!(grant.revoked_at, None);
+    assert_eq!(grant.uses, None);
+
+    conductor
+        .revoke_zome_call_capability(grant.action_hash.clone())
+        .await
+        .unwrap();
+
+    // the grant is only listed along with revoked grants
+    assert!(!conductor
+        .list_capability_grants(cell_id.clone(), false)
+        .await
+        .unwrap()
+        .iter()
+        .any(|info| info.action_hash == grant.action_hash));
+    let revoked = conductor
+        .list_capability_grants(cell_id.clone(), true)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|info| info.action_hash == grant.action_hash)
+        .unwrap();
+    assert!(revoked.revoked_at.is_some());
+
+    // and can't be revoked again
+    assert!(conductor
+        .revoke_zome_call_capability(grant.action_hash.clone())
+        .await
+        .is_err());
+
+    // zome calls can no longer be made with it
+    let (nonce, expires_at) = fresh_nonce(Timestamp::now()).unwrap();
+    let response = conductor
+        .call_zome(
+            ZomeCall::try_from_unsigned_zome_call(
+                &conductor.keystore(),
+                ZomeCallUnsigned {
+                    provenance: cap_access_public_key.clone(),
+                    cell_id: cell_id.clone(),
+                    zome_name: zome.coordinator_zome_name(),
+                    fn_name: "get_entry".into(),
+                    cap_secret: Some(cap_access_secret),
+                    payload: ExternIO::encode(()).unwrap(),
+                    nonce,
+                    expires_at,
+                },
+            )
+            .await
+            .unwrap(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(response, ZomeCallResponse::Unauthorized(..));
+}
@@ -23,5 +23,9 @@ pub(crate) fn spawn_countersigning_consumer(
         move || countersigning_workflow(space.clone(), dna_network.clone(), trigger_sys.clone()),
     );
 
+    // Run once on startup to resume any sessions which were persisted
+    // before the conductor was stopped.
+    tx.trigger(&"resume_countersigning");
+
     tx
 }
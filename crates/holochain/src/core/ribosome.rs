@@ -7,10 +7,14 @@
 
 // This allow is here because #[automock] automaticaly creates a struct without
 // documentation, and there seems to be no way to add docs to it after the fact
+pub mod curry;
 #[allow(missing_docs)]
 pub mod error;
 pub mod guest_callback;
+pub mod host_call_tape;
 pub mod host_fn;
+pub mod metering;
+pub mod module_cache;
 pub mod real_ribosome;
 
 use crate::conductor::api::CellConductorHandle;
@@ -26,7 +30,6 @@ use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::CallIterator;
-use derive_more::Constructor;
 use error::RibosomeResult;
 use guest_callback::entry_defs::EntryDefsHostAccess;
 use guest_callback::init::InitHostAccess;
@@ -43,6 +46,8 @@ use holochain_state::nonce::*;
 use holochain_types::prelude::*;
 use holochain_types::zome_types::GlobalZomeTypes;
 use holochain_zome_types::block::BlockTargetId;
+use host_call_tape::HostCallTape;
+use metering::ZomeCallMeter;
 use mockall::automock;
 use std::iter::Iterator;
 use std::sync::Arc;
@@ -186,6 +191,24 @@ impl HostContext {
         }
     }
 
+    /// Get the tape which host function calls are recorded to or replayed
+    /// from, if there is one.
+    pub fn host_call_tape(&self) -> Option<&HostCallTape> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { host_call_tape, .. }) => host_call_tape.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Get the meter which the points used by a zome call are added to,
+    /// if there is one.
+    pub fn zome_call_meter(&self) -> Option<&ZomeCallMeter> {
+        match self {
+            Self::ZomeCall(ZomeCallHostAccess { meter, .. }) => meter.as_ref(),
+            _ => None,
+        }
+    }
+
     /// Get the call zome handle, panics if none was provided
     pub fn call_zome_handle(&self) -> &CellConductorReadHandle {
         match self {
@@ -193,6 +216,7 @@ impl HostContext {
                 call_zome_handle, ..
             })
             | Self::Init(InitHostAccess { call_zome_handle, .. })
+            | Self::PostCommit(PostCommitHostAccess { call_zome_handle, .. })
             => call_zome_handle,
             _ => panic!(
                 "Gave access to a host function that uses the call zome handle without providing a call zome handle"
@@ -329,8 +353,12 @@ impl ZomeCallInvocation {
     /// - we need to find a live (committed and not deleted) cap grant that matches the secret
     /// - if the live cap grant is for the current author the call is ALWAYS authorized ELSE
     /// - the live cap grant needs to include the invocation's provenance AND zome/function name
+    /// - the live cap grant must not have expired
+    /// - if the live cap grant has limited uses, one of them is used up by this call
+    /// - if the live cap grant curries payload fields into calls to the function, they are
+    ///   merged into the payload, which must not already have them with other values
     pub async fn verify_grant(
-        &self,
+        &mut self,
         host_access: &ZomeCallHostAccess,
     ) -> RibosomeResult<ZomeCallAuthorization> {
         let check_function = (self.zome.zome_name().clone(), self.fn_name.clone());
@@ -341,13 +369,35 @@ impl ZomeCallInvocation {
             .source_chain()
             .as_ref()
             .expect("Must have source chain to make zome calls")
-            .valid_cap_grant(check_function, check_agent, check_secret)
+            .valid_cap_grant(check_function.clone(), check_agent, check_secret)
             .await?;
-        Ok(if maybe_grant.is_some() {
-            ZomeCallAuthorization::Authorized
-        } else {
-            ZomeCallAuthorization::BadCapGrant
-        })
+        let grant = match maybe_grant {
+            Some(CapGrant::RemoteAgent(grant)) => grant,
+            Some(CapGrant::ChainAuthor(_)) => return Ok(ZomeCallAuthorization::Authorized),
+            None => return Ok(ZomeCallAuthorization::BadCapGrant),
+        };
+        let curried_payload = match grant.curry_payloads.0.get(&check_function) {
+            Some(curry) => match curry::curry_payload(&self.payload, curry) {
+                Some(payload) => Some(payload),
+                None => return Ok(ZomeCallAuthorization::BadCapGrant),
+            },
+            None => None,
+        };
+        if let Some(max_uses) = grant.max_uses {
+            let grant_hash = EntryHash::with_data_sync(&Entry::CapGrant(grant.clone()));
+            if !host_access
+                .call_zome_handle
+                .use_cap_grant(grant_hash, max_uses, grant.expires_at)
+                .await
+                .map_err(Box::new)?
+            {
+                return Ok(ZomeCallAuthorization::BadCapGrant);
+            }
+        }
+        if let Some(payload) = curried_payload {
+            self.payload = payload;
+        }
+        Ok(ZomeCallAuthorization::Authorized)
     }
 
     pub async fn verify_nonce(
@@ -395,23 +445,25 @@ impl ZomeCallInvocation {
     /// to verify if the zome call is authorized:
     /// - the signature must be valid
     /// - the nonce must not have already been seen
-    /// - the grant must be valid
     /// - the provenance must not have any active blocks against them right now
+    /// - the grant must be valid
     /// the checks MUST be done in this order as witnessing the nonce is a write
     /// and so we MUST NOT write nonces until after we verify the signature.
+    /// Likewise a use of a grant with limited uses is only counted once every
+    /// other check has passed.
     #[allow(clippy::extra_unused_lifetimes)]
     pub async fn is_authorized<'a>(
-        &self,
+        &mut self,
         host_access: &ZomeCallHostAccess,
     ) -> RibosomeResult<ZomeCallAuthorization> {
         Ok(match self.verify_signature().await? {
             ZomeCallAuthorization::Authorized => match self.verify_nonce(host_access).await? {
-                ZomeCallAuthorization::Authorized => match self.verify_grant(host_access).await? {
-                    ZomeCallAuthorization::Authorized => {
-                        self.verify_blocked_provenance(host_access).await?
+                ZomeCallAuthorization::Authorized => {
+                    match self.verify_blocked_provenance(host_access).await? {
+                        ZomeCallAuthorization::Authorized => self.verify_grant(host_access).await?,
+                        unauthorized => unauthorized,
                     }
-                    unauthorized => unauthorized,
-                },
+                }
                 unauthorized => unauthorized,
             },
             unauthorized => unauthorized,
@@ -537,13 +589,49 @@ impl From<ZomeCallInvocation> for ZomeCall {
     }
 }
 
-#[derive(Clone, Constructor)]
+#[derive(Clone)]
 pub struct ZomeCallHostAccess {
     pub workspace: HostFnWorkspace,
     pub keystore: MetaLairClient,
     pub network: HolochainP2pDna,
     pub signal_tx: SignalBroadcaster,
     pub call_zome_handle: CellConductorReadHandle,
+    /// If set, host function calls are recorded to or replayed from this tape.
+    pub host_call_tape: Option<HostCallTape>,
+    /// If set, the metering points used by the call are added to this meter.
+    pub meter: Option<ZomeCallMeter>,
+}
+
+impl ZomeCallHostAccess {
+    pub fn new(
+        workspace: HostFnWorkspace,
+        keystore: MetaLairClient,
+        network: HolochainP2pDna,
+        signal_tx: SignalBroadcaster,
+        call_zome_handle: CellConductorReadHandle,
+    ) -> Self {
+        Self {
+            workspace,
+            keystore,
+            network,
+            signal_tx,
+            call_zome_handle,
+            host_call_tape: None,
+            meter: None,
+        }
+    }
+
+    /// Record host function calls to, or replay them from, the given tape.
+    pub fn with_host_call_tape(mut self, tape: HostCallTape) -> Self {
+        self.host_call_tape = Some(tape);
+        self
+    }
+
+    /// Add the metering points used by the call to the given meter.
+    pub fn with_meter(mut self, meter: ZomeCallMeter) -> Self {
+        self.meter = Some(meter);
+        self
+    }
 }
 
 impl std::fmt::Debug for ZomeCallHostAccess {
@@ -632,18 +720,25 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     /// values without needing to make holochain a dependency.
     fn get_const_fn(&self, zome: &Zome, name: &str) -> Result<Option<i32>, RibosomeError>;
 
-    /// @todo list out all the available callbacks and maybe cache them somewhere
-    fn list_callbacks(&self) {
-        unimplemented!()
-        // pseudocode
-        // self.instance().exports().filter(|e| e.is_callback())
+    /// List every function a zome exports, callbacks included.
+    fn list_extern_fns(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>>;
+
+    /// List the callbacks a zome exports.
+    fn list_callbacks(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>> {
+        Ok(self
+            .list_extern_fns(zome)?
+            .into_iter()
+            .filter(is_callback)
+            .collect())
     }
 
-    /// @todo list out all the available zome functions and maybe cache them somewhere
-    fn list_zome_fns(&self) {
-        unimplemented!()
-        // pseudocode
-        // self.instance().exports().filter(|e| !e.is_callback())
+    /// List the functions a zome exports which aren't callbacks.
+    fn list_zome_fns(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>> {
+        Ok(self
+            .list_extern_fns(zome)?
+            .into_iter()
+            .filter(|f| !is_callback(f))
+            .collect())
     }
 
     fn run_genesis_self_check(
@@ -695,6 +790,24 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     fn zome_types(&self) -> &Arc<GlobalZomeTypes>;
 }
 
+/// The names of the callbacks the host calls on zomes, as opposed to the
+/// zome functions which are called on behalf of an agent.
+pub const CALLBACK_FN_NAMES: &[&str] = &[
+    "entry_defs",
+    "genesis_self_check",
+    "init",
+    "migrate_agent",
+    "migrate_agent_close",
+    "migrate_agent_open",
+    "post_commit",
+    "validate",
+];
+
+/// Is this the name of a callback the host calls on zomes?
+pub fn is_callback(fn_name: &FunctionName) -> bool {
+    CALLBACK_FN_NAMES.contains(&fn_name.0.as_str())
+}
+
 /// Placeholder for weighing. Currently produces zero weight.
 pub fn weigh_placeholder() -> EntryRateWeight {
     EntryRateWeight::default()
@@ -0,0 +1,106 @@
+//! Currying the payload fields fixed by a capability grant into zome calls.
+//!
+//! A grant can fix fields of the payload of calls to a function, so that
+//! e.g. a grant for `transfer` only allows transfers `from` one account.
+//! The curried fields are added to the payload of the call, which must be
+//! a map (or unit, for an empty map). A call whose payload already has one
+//! of the fields with a different value is not covered by the grant.
+
+use holochain_types::prelude::*;
+use rmpv::Value;
+
+/// Curry the fields of a grant's payload into a call's payload.
+/// Returns `None` if the call's payload contradicts the grant's,
+/// or either isn't a map.
+pub fn curry_payload(payload: &ExternIO, curry: &SerializedBytes) -> Option<ExternIO> {
+    let curry = match holochain_serialized_bytes::decode::<_, Value>(curry.bytes()).ok()? {
+        Value::Map(fields) => fields,
+        _ => return None,
+    };
+    let mut fields =
+        match holochain_serialized_bytes::decode::<_, Value>(payload.as_bytes()).ok()? {
+            Value::Map(fields) => fields,
+            Value::Nil => Vec::new(),
+            _ => return None,
+        };
+    for (key, value) in curry {
+        match fields.iter().find(|(k, _)| *k == key) {
+            Some((_, given)) if *given != value => return None,
+            Some(_) => (),
+            None => fields.push((key, value)),
+        }
+    }
+    ExternIO::encode(Value::Map(fields)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Transfer {
+        from: String,
+        to: String,
+        amount: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct FromAccount {
+        from: String,
+    }
+
+    fn curry(from: &str) -> SerializedBytes {
+        SerializedBytes::try_from(UnsafeBytes::from(
+            holochain_serialized_bytes::encode(&FromAccount { from: from.into() }).unwrap(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn curried_fields_are_added_to_the_payload() {
+        #[derive(Debug, Serialize)]
+        struct Partial {
+            to: String,
+            amount: u32,
+        }
+        let payload = ExternIO::encode(Partial {
+            to: "bob".into(),
+            amount: 10,
+        })
+        .unwrap();
+        let curried = curry_payload(&payload, &curry("alice")).unwrap();
+        assert_eq!(
+            curried.decode::<Transfer>().unwrap(),
+            Transfer {
+                from: "alice".into(),
+                to: "bob".into(),
+                amount: 10,
+            }
+        );
+
+        let curried = curry_payload(&ExternIO::encode(()).unwrap(), &curry("alice")).unwrap();
+        assert_eq!(curried.decode::<FromAccount>().unwrap().from, "alice");
+    }
+
+    #[test]
+    fn payloads_must_match_the_curried_fields() {
+        let transfer = |from: &str| {
+            ExternIO::encode(Transfer {
+                from: from.into(),
+                to: "bob".into(),
+                amount: 10,
+            })
+            .unwrap()
+        };
+        assert_eq!(
+            curry_payload(&transfer("alice"), &curry("alice"))
+                .unwrap()
+                .decode::<Transfer>()
+                .unwrap()
+                .from,
+            "alice"
+        );
+        assert!(curry_payload(&transfer("carol"), &curry("alice")).is_none());
+        assert!(curry_payload(&ExternIO::encode(10).unwrap(), &curry("alice")).is_none());
+    }
+}
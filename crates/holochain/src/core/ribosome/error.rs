@@ -101,6 +101,10 @@ pub enum RibosomeError {
 
     #[error(transparent)]
     ModuleDeserializeError(#[from] holochain_wasmer_host::prelude::DeserializeError),
+
+    /// A DNA property which configures the conductor couldn't be read.
+    #[error("The `{0}` DNA property could not be read: {1}")]
+    InvalidDnaProperty(String, SerializedBytesError),
 }
 
 /// Type alias
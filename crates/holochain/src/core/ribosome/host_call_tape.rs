@@ -0,0 +1,99 @@
+//! Recording and replaying of the host function calls made during a zome call.
+
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// The host function calls of a single zome call, either being recorded as
+/// they are made, or being played back in place of the real host functions.
+#[derive(Clone, Debug)]
+pub enum HostCallTape {
+    /// Host functions run as normal, and each call is appended to the tape.
+    Record(Arc<parking_lot::Mutex<Vec<RecordedHostCall>>>),
+    /// Host functions don't run. Instead each call takes the next recorded
+    /// result from the tape, which must be for the same host function.
+    Replay(Arc<parking_lot::Mutex<VecDeque<RecordedHostCall>>>),
+}
+
+impl HostCallTape {
+    /// An empty tape to record onto.
+    pub fn record() -> Self {
+        Self::Record(Default::default())
+    }
+
+    /// A tape which plays back previously recorded host calls.
+    pub fn replay(host_calls: Vec<RecordedHostCall>) -> Self {
+        Self::Replay(Arc::new(parking_lot::Mutex::new(host_calls.into())))
+    }
+
+    /// The host calls recorded so far, or left to replay.
+    pub fn host_calls(&self) -> Vec<RecordedHostCall> {
+        match self {
+            Self::Record(calls) => calls.lock().clone(),
+            Self::Replay(calls) => calls.lock().iter().cloned().collect(),
+        }
+    }
+
+    /// Call a host function through the tape.
+    pub fn call<I, O>(
+        &self,
+        host_fn: &str,
+        input: I,
+        f: impl FnOnce(I) -> Result<O, RuntimeError>,
+    ) -> Result<O, RuntimeError>
+    where
+        I: serde::Serialize + std::fmt::Debug,
+        O: serde::Serialize + serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let input_io =
+            ExternIO::encode(&input).map_err(|e| -> RuntimeError { wasm_error!(e).into() })?;
+        match self {
+            Self::Record(calls) => {
+                let (result, output) = match f(input) {
+                    Ok(o) => {
+                        let output = ExternIO::encode(&o)
+                            .map_err(|e| -> RuntimeError { wasm_error!(e).into() })?;
+                        (Ok(o), Ok(output))
+                    }
+                    Err(runtime_error) => match runtime_error.downcast::<WasmError>() {
+                        Ok(wasm_error) => (Err(wasm_error.clone().into()), Err(wasm_error)),
+                        Err(runtime_error) => {
+                            let wasm_error =
+                                wasm_error!(WasmErrorInner::Host(runtime_error.to_string()));
+                            (Err(runtime_error), Err(wasm_error))
+                        }
+                    },
+                };
+                calls.lock().push(RecordedHostCall {
+                    host_fn: host_fn.to_string(),
+                    input: input_io,
+                    output,
+                });
+                result
+            }
+            Self::Replay(calls) => {
+                let recorded = calls.lock().pop_front().ok_or_else(|| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(format!(
+                        "Replay diverged: {} was called after all recorded host calls were replayed",
+                        host_fn
+                    )))
+                    .into()
+                })?;
+                if recorded.host_fn != host_fn || recorded.input != input_io {
+                    return Err(wasm_error!(WasmErrorInner::Host(format!(
+                        "Replay diverged: {} was called where {} was recorded",
+                        host_fn, recorded.host_fn
+                    )))
+                    .into());
+                }
+                match recorded.output {
+                    Ok(output) => output
+                        .decode()
+                        .map_err(|e| -> RuntimeError { wasm_error!(e).into() }),
+                    Err(wasm_error) => Err(wasm_error.into()),
+                }
+            }
+        }
+    }
+}
@@ -42,11 +42,16 @@ macro_rules! host_fn_api_impls {
         impl<Ribosome: RibosomeT> HostFnApiT for HostFnApi<Ribosome> {
             $(
                 fn $f(&self, input: $input) -> Result<$output, HostFnApiError> {
-                    $f::$f(
+                    let call = |input: $input| $f::$f(
                         self.ribosome.clone(),
                         self.call_context.clone(),
                         input.into()
-                    ).map_err(|e| HostFnApiError::RibosomeError(Box::new(e)))
+                    );
+                    match self.call_context.host_context.host_call_tape() {
+                        Some(tape) => tape.call(stringify!($f), input, call),
+                        None => call(input),
+                    }
+                    .map_err(|e| HostFnApiError::RibosomeError(Box::new(e)))
                 }
             )*
         }
@@ -146,6 +151,7 @@ host_fn_api_impls! {
     fn get_agent_activity (zt::agent_activity::GetAgentActivityInput) -> zt::query::AgentActivity;
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
+    fn get_meta (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::DhtMetadata>>;
 
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
@@ -169,6 +175,9 @@ host_fn_api_impls! {
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<Record>;
 
+    // Count the records a query of the source chain would return.
+    fn query_count (zt::query::ChainQueryFilter) -> usize;
+
     // the length of random bytes to create
     fn random_bytes (u32) -> zt::bytes::Bytes;
 
@@ -34,11 +34,18 @@ pub fn accept_countersigning_preflight_request<'a>(
                     return Ok(PreflightRequestAcceptance::UnacceptableFutureStart);
                 }
 
+                // Optional signers are indexed by their position in the
+                // optional signing agents.
                 let agent_index = match input
                     .signing_agents
                     .iter()
                     .position(|(agent, _)| agent == &author)
-                {
+                    .or_else(|| {
+                        input
+                            .optional_signing_agents
+                            .iter()
+                            .position(|(agent, _)| agent == &author)
+                    }) {
                     Some(agent_index) => agent_index as u8,
                     None => return Ok(PreflightRequestAcceptance::UnacceptableAgentNotFound),
                 };
@@ -0,0 +1,58 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use futures::future::join_all;
+use holochain_cascade::Cascade;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_meta<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    inputs: Vec<GetInput>,
+) -> Result<Vec<Option<DhtMetadata>>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let results: Vec<Result<Option<DhtMetadata>, _>> =
+                tokio_helper::block_forever_on(async move {
+                    join_all(inputs.into_iter().map(|input| async {
+                        let GetInput {
+                            any_dht_hash,
+                            get_options,
+                        } = input;
+                        Cascade::from_workspace_and_network(
+                            &call_context.host_context.workspace(),
+                            call_context.host_context.network().to_owned(),
+                        )
+                        .get_meta(any_dht_hash, get_options)
+                        .await
+                    }))
+                    .await
+                });
+            let results: Result<Vec<_>, _> = results
+                .into_iter()
+                .map(|result| {
+                    result.map_err(|cascade_error| {
+                        wasm_error!(WasmErrorInner::Host(cascade_error.to_string()))
+                    })
+                })
+                .collect();
+            Ok(results?)
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_meta".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -80,6 +80,24 @@ pub fn must_get_agent_activity(
                         missing_action, author, chain_filter
                     )))
                     .into()),
+                    (Forked(fork), HostContext::Validate(_)) => {
+                        Err(wasm_error!(WasmErrorInner::HostShortCircuit(
+                            holochain_serialized_bytes::encode(
+                                &ExternIO::encode(ValidateCallbackResult::Invalid(format!(
+                                    "must_get_agent_activity found a fork at action_seq {} between actions {} and {} for author {}",
+                                    fork.fork_seq, fork.first_action, fork.second_action, author
+                                )))
+                                .map_err(|e| -> RuntimeError { wasm_error!(e).into() })?,
+                            )
+                            .map_err(|e| -> RuntimeError { wasm_error!(e).into() })?
+                        ))
+                        .into())
+                    }
+                    (Forked(fork), _) => Err(wasm_error!(WasmErrorInner::Host(format!(
+                        "must_get_agent_activity found a fork at action_seq {} between actions {} and {} for author {} and filter {:?}",
+                        fork.fork_seq, fork.first_action, fork.second_action, author, chain_filter
+                    )))
+                    .into()),
                     (EmptyRange, _) => Err(wasm_error!(WasmErrorInner::Host(format!(
                         "must_get_agent_activity chain has produced an invalid range because the range is empty for author {} and filter {:?}",
                         author, chain_filter
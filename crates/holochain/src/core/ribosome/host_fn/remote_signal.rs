@@ -32,11 +32,29 @@ pub fn remote_signal(
             const FN_NAME: &str = "recv_remote_signal";
             let from_agent = super::agent_info::agent_info(_ribosome, call_context.clone(), ())?
                 .agent_latest_pubkey;
-            // Timeouts and errors are ignored,
-            // this is a send and forget operation.
             let network = call_context.host_context().network().clone();
-            let RemoteSignal { agents, signal } = input;
+            let RemoteSignal {
+                agents,
+                signal,
+                reliable,
+            } = input;
             let zome_name = call_context.zome().zome_name().clone();
+            if reliable {
+                // The conductor queues the signals and retries them
+                // until they are acknowledged or expire.
+                let host_context = call_context.host_context();
+                let call_zome_handle = host_context.call_zome_handle();
+                for agent in agents {
+                    call_zome_handle.send_reliable_remote_signal(
+                        agent,
+                        zome_name.clone(),
+                        signal.clone(),
+                    );
+                }
+                return Ok(());
+            }
+            // Timeouts and errors are ignored,
+            // this is a send and forget operation.
             let fn_name: FunctionName = FN_NAME.into();
 
             tokio::task::spawn(
@@ -113,7 +131,11 @@ mod tests {
     use hdk::prelude::*;
     use tokio_stream::StreamExt;
 
-    fn test_zome(agents: Vec<AgentPubKey>, num_signals: Arc<AtomicUsize>) -> InlineIntegrityZome {
+    fn test_zome(
+        agents: Vec<AgentPubKey>,
+        num_signals: Arc<AtomicUsize>,
+        reliable: bool,
+    ) -> InlineIntegrityZome {
         let entry_def = EntryDef::from_id("entrydef");
 
         InlineIntegrityZome::new_unique(vec![entry_def.clone()], 0)
@@ -122,6 +144,7 @@ mod tests {
                 let signal = RemoteSignal {
                     agents: agents.clone(),
                     signal,
+                    reliable,
                 };
                 tracing::debug!("sending signal to {:?}", agents);
                 api.remote_signal(signal)?;
@@ -141,6 +164,9 @@ mod tests {
                     // empty access converts to unrestricted
                     access: ().into(),
                     functions,
+                    expires_at: None,
+                    max_uses: None,
+                    curry_payloads: Default::default(),
                 };
                 api.create(CreateInput::new(
                     EntryDefLocation::CapGrant,
@@ -167,7 +193,7 @@ mod tests {
         let agents =
             future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
 
-        let zome = test_zome(agents.clone(), num_signals.clone());
+        let zome = test_zome(agents.clone(), num_signals.clone(), false);
         let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(("zome", zome)).await;
 
         let apps = conductors
@@ -196,4 +222,73 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg(feature = "test_utils")]
+    async fn reliable_remote_signal_test() -> anyhow::Result<()> {
+        holochain_trace::test_run().ok();
+        const NUM_CONDUCTORS: usize = 2;
+
+        let num_signals = Arc::new(AtomicUsize::new(0));
+
+        let mut conductors = SweetConductorBatch::from_standard_config(NUM_CONDUCTORS).await;
+
+        let agents =
+            future::join_all(conductors.iter().map(|c| SweetAgents::one(c.keystore()))).await;
+        // An agent which never comes online, so its signal can't be delivered.
+        let offline_agent = SweetAgents::one(conductors[0].keystore()).await;
+
+        let mut recipients = agents.clone();
+        recipients.push(offline_agent.clone());
+        let zome = test_zome(recipients, num_signals.clone(), true);
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(("zome", zome)).await;
+
+        let apps = conductors
+            .setup_app_for_zipped_agents("app", &agents, &[dna_file.clone().into()])
+            .await
+            .unwrap();
+
+        conductors.exchange_peer_info().await;
+
+        let cells: Vec<_> = apps.cells_flattened();
+
+        let _: () = conductors[0]
+            .call(&cells[0].zome("zome"), "signal_others", ())
+            .await;
+
+        crate::assert_eq_retry_10s!(num_signals.load(Ordering::SeqCst), NUM_CONDUCTORS);
+
+        // The recipients' conductors acknowledge the signals they received.
+        let app_id: holochain_types::prelude::InstalledAppId = "app".into();
+        crate::assert_eq_retry_10s!(
+            conductors[0]
+                .remote_signal_deliveries(&app_id, cells[0].cell_id().clone())
+                .await
+                .unwrap()
+                .iter()
+                .filter(|d| d.delivered_at.is_some())
+                .count(),
+            NUM_CONDUCTORS
+        );
+
+        let deliveries = conductors[0]
+            .remote_signal_deliveries(&app_id, cells[0].cell_id().clone())
+            .await
+            .unwrap();
+        assert_eq!(deliveries.len(), NUM_CONDUCTORS + 1);
+        let undelivered = deliveries
+            .iter()
+            .find(|d| d.to_agent == offline_agent)
+            .unwrap();
+        assert!(undelivered.delivered_at.is_none());
+        assert!(undelivered.attempts >= 1);
+
+        // Only the cells of the app can be queried.
+        assert!(conductors[0]
+            .remote_signal_deliveries(&"other app".into(), cells[0].cell_id().clone())
+            .await
+            .is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,359 @@
+//! Wasm metering budgets and usage.
+//!
+//! Every wasm call may use at most [`WASM_METERING_LIMIT`] points, but a DNA
+//! can give each kind of call a smaller budget, for all of its zomes or for
+//! individual zomes, under the `metering` key of its properties:
+//!
+//! ```yaml
+//! properties:
+//!   metering:
+//!     default:
+//!       validate: 10000000
+//!     zomes:
+//!       posts:
+//!         zome_call: 1000000000
+//! ```
+//!
+//! Budgets above the global limit are clamped to it. The points used by
+//! each call are recorded so expensive functions can be found before they
+//! hit their budget.
+
+use holochain_conductor_api::MeteredCallKind;
+use holochain_conductor_api::WasmMeteringStats;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+use holochain_types::wasmer_types::WASM_METERING_LIMIT;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use super::HostContext;
+
+/// Calls using more than this fraction of their budget are logged.
+const WARN_BUDGET_FRACTION: f64 = 0.8;
+
+/// Budgets for each kind of call. Unset budgets fall back to the next
+/// less specific budget, and finally to the global limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeteringBudget {
+    /// The budget of zome functions.
+    pub zome_call: Option<u64>,
+    /// The budget of the `validate` callback.
+    pub validate: Option<u64>,
+    /// The budget of the `init` callback.
+    pub init: Option<u64>,
+    /// The budget of every other callback.
+    pub other: Option<u64>,
+}
+
+impl MeteringBudget {
+    fn get(&self, kind: MeteredCallKind) -> Option<u64> {
+        match kind {
+            MeteredCallKind::ZomeCall => self.zome_call,
+            MeteredCallKind::Validate => self.validate,
+            MeteredCallKind::Init => self.init,
+            MeteredCallKind::Other => self.other,
+        }
+    }
+}
+
+/// The metering budgets of a DNA.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeteringBudgets {
+    /// Budgets for every zome of the DNA.
+    pub default: MeteringBudget,
+    /// Budgets for individual zomes, overriding the defaults.
+    pub zomes: HashMap<ZomeName, MeteringBudget>,
+}
+
+impl MeteringBudgets {
+    /// Read the budgets for a DNA from its properties,
+    /// falling back to the global limit if none are set.
+    /// Like the spam policy, `metering` which can't be read is an error, and
+    /// DNAs with it are refused when they are registered.
+    pub fn from_modifiers(modifiers: &DnaModifiers) -> Result<Self, SerializedBytesError> {
+        // Properties which aren't a map have no budgets.
+        let properties: serde_yaml::Value =
+            holochain_serialized_bytes::decode(modifiers.properties.bytes()).unwrap_or_default();
+        match properties.get("metering") {
+            Some(budgets) => serde_yaml::from_value(budgets.clone())
+                .map_err(|e| SerializedBytesError::Deserialize(e.to_string())),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The budget of a kind of call into a zome.
+    pub fn budget(&self, zome_name: &ZomeName, kind: MeteredCallKind) -> u64 {
+        self.zomes
+            .get(zome_name)
+            .and_then(|b| b.get(kind))
+            .or_else(|| self.default.get(kind))
+            .unwrap_or(WASM_METERING_LIMIT)
+            .min(WASM_METERING_LIMIT)
+    }
+}
+
+impl From<&HostContext> for MeteredCallKind {
+    fn from(host_context: &HostContext) -> Self {
+        match host_context {
+            HostContext::ZomeCall(_) => Self::ZomeCall,
+            HostContext::Validate(_) => Self::Validate,
+            HostContext::Init(_) => Self::Init,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Adds up the points used by a zome call, for callers which asked for them.
+#[derive(Clone, Debug, Default)]
+pub struct ZomeCallMeter(Arc<AtomicU64>);
+
+impl ZomeCallMeter {
+    /// Create a meter at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add points used by the call.
+    pub fn add(&self, points: u64) {
+        self.0.fetch_add(points, Ordering::Relaxed);
+    }
+
+    /// The points used so far.
+    pub fn points(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Usage {
+    budget: u64,
+    calls: u64,
+    total_points: u64,
+    max_points: u64,
+    exhausted: u64,
+}
+
+/// The points used by each kind of call into each zome of a DNA.
+/// Clones share the same stats.
+#[derive(Clone, Debug, Default)]
+pub struct MeteringStats(Arc<parking_lot::Mutex<HashMap<(ZomeName, MeteredCallKind), Usage>>>);
+
+impl MeteringStats {
+    /// Record the points used by a call.
+    pub fn record(
+        &self,
+        zome_name: &ZomeName,
+        kind: MeteredCallKind,
+        budget: u64,
+        points: u64,
+        exhausted: bool,
+    ) {
+        if exhausted {
+            tracing::warn!(%zome_name, ?kind, budget, "Wasm call used its whole metering budget");
+        } else if points as f64 > budget as f64 * WARN_BUDGET_FRACTION {
+            tracing::warn!(%zome_name, ?kind, budget, points, "Wasm call used most of its metering budget");
+        }
+        let mut stats = self.0.lock();
+        let usage = stats.entry((zome_name.clone(), kind)).or_default();
+        usage.budget = budget;
+        usage.calls += 1;
+        usage.total_points = usage.total_points.saturating_add(points);
+        usage.max_points = usage.max_points.max(points);
+        usage.exhausted += exhausted as u64;
+    }
+
+    /// The stats of every kind of call made so far.
+    pub fn stats(&self, dna_hash: &DnaHash) -> Vec<WasmMeteringStats> {
+        let mut stats: Vec<_> = self
+            .0
+            .lock()
+            .iter()
+            .map(|((zome_name, kind), usage)| WasmMeteringStats {
+                dna_hash: dna_hash.clone(),
+                zome_name: zome_name.clone(),
+                kind: *kind,
+                budget: usage.budget,
+                calls: usage.calls,
+                total_points: usage.total_points,
+                max_points: usage.max_points,
+                exhausted: usage.exhausted,
+            })
+            .collect();
+        stats.sort_by(|a, b| (&a.zome_name, a.kind).cmp(&(&b.zome_name, b.kind)));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweettest::SweetConductor;
+    use crate::sweettest::SweetDnaFile;
+    use crate::test_utils::new_zome_call;
+    use ::fixt::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    fn modifiers(properties: serde_yaml::Value) -> DnaModifiers {
+        DnaModifiersBuilder::default()
+            .network_seed("".into())
+            .properties(YamlProperties::new(properties).try_into().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn metering_budgets_from_properties() {
+        let props = serde_yaml::from_str(
+            "
+            metering:
+              default:
+                validate: 1000
+                init: 18446744073709551615
+              zomes:
+                posts:
+                  validate: 10
+                  zome_call: 20
+            ",
+        )
+        .unwrap();
+        let budgets = MeteringBudgets::from_modifiers(&modifiers(props)).unwrap();
+        let posts = ZomeName::from("posts");
+        let comments = ZomeName::from("comments");
+
+        assert_eq!(budgets.budget(&posts, MeteredCallKind::Validate), 10);
+        assert_eq!(budgets.budget(&posts, MeteredCallKind::ZomeCall), 20);
+        assert_eq!(budgets.budget(&comments, MeteredCallKind::Validate), 1000);
+        assert_eq!(
+            budgets.budget(&comments, MeteredCallKind::ZomeCall),
+            WASM_METERING_LIMIT
+        );
+        // Budgets are clamped to the global limit.
+        assert_eq!(
+            budgets.budget(&comments, MeteredCallKind::Init),
+            WASM_METERING_LIMIT
+        );
+
+        let budgets = MeteringBudgets::from_modifiers(&modifiers(serde_yaml::Value::Null)).unwrap();
+        assert_eq!(budgets, MeteringBudgets::default());
+    }
+
+    #[test]
+    fn malformed_metering_budgets_are_an_error() {
+        let props = serde_yaml::from_str(
+            "
+            metering:
+              default:
+                validate: lots
+            ",
+        )
+        .unwrap();
+        assert!(MeteringBudgets::from_modifiers(&modifiers(props)).is_err());
+    }
+
+    #[test]
+    fn metering_stats_add_up() {
+        let stats = MeteringStats::default();
+        let zome = ZomeName::from("posts");
+        stats.record(&zome, MeteredCallKind::ZomeCall, 100, 10, false);
+        stats.record(&zome, MeteredCallKind::ZomeCall, 100, 30, false);
+        stats.record(&zome, MeteredCallKind::ZomeCall, 100, 100, true);
+        stats.record(&zome, MeteredCallKind::Validate, 50, 5, false);
+
+        let dna_hash = fixt!(DnaHash);
+        let stats = stats.stats(&dna_hash);
+        assert_eq!(
+            stats[0],
+            WasmMeteringStats {
+                dna_hash: dna_hash.clone(),
+                zome_name: zome.clone(),
+                kind: MeteredCallKind::ZomeCall,
+                budget: 100,
+                calls: 3,
+                total_points: 140,
+                max_points: 100,
+                exhausted: 1,
+            }
+        );
+        assert_eq!(stats[1].kind, MeteredCallKind::Validate);
+        assert_eq!(stats[1].calls, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn zome_calls_are_metered_within_their_budget() {
+        holochain_trace::test_run().ok();
+        let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo]).await;
+        let props = serde_yaml::from_str("metering: { zomes: { foo: { zome_call: 1 } } }").unwrap();
+        let limited_dna_file = dna_file.update_modifiers(
+            DnaModifiersOpt::none().with_properties(YamlProperties::new(props).try_into().unwrap()),
+        );
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let (cell,) = conductor
+            .setup_app("app", [&dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let (limited_cell,) = conductor
+            .setup_app("limited", [&limited_dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let foo = ZomeName::from(TestWasm::Foo);
+
+        let call = new_zome_call(
+            &conductor.keystore(),
+            cell.cell_id(),
+            "foo",
+            (),
+            TestWasm::Foo,
+        )
+        .await
+        .unwrap();
+        let (result, points) = conductor
+            .raw_handle()
+            .call_zome_metered(call)
+            .await
+            .unwrap();
+        assert!(matches!(result, Ok(ZomeCallResponse::Ok(_))));
+        assert!(points > 0);
+
+        let stats = conductor.wasm_metering_stats(Some(dna_file.dna_hash()));
+        let zome_calls = stats
+            .iter()
+            .find(|s| s.zome_name == foo && s.kind == MeteredCallKind::ZomeCall)
+            .unwrap();
+        assert_eq!(zome_calls.budget, WASM_METERING_LIMIT);
+        assert_eq!(zome_calls.calls, 1);
+        assert_eq!(zome_calls.max_points, points);
+        assert_eq!(zome_calls.exhausted, 0);
+
+        // A call which runs out of budget fails.
+        let call = new_zome_call(
+            &conductor.keystore(),
+            limited_cell.cell_id(),
+            "foo",
+            (),
+            TestWasm::Foo,
+        )
+        .await
+        .unwrap();
+        let (result, points) = conductor
+            .raw_handle()
+            .call_zome_metered(call)
+            .await
+            .unwrap();
+        assert!(result.is_err());
+        assert_eq!(points, 1);
+
+        let stats = conductor.wasm_metering_stats(Some(limited_dna_file.dna_hash()));
+        let zome_calls = stats
+            .iter()
+            .find(|s| s.zome_name == foo && s.kind == MeteredCallKind::ZomeCall)
+            .unwrap();
+        assert_eq!(zome_calls.budget, 1);
+        assert_eq!(zome_calls.exhausted, 1);
+    }
+}
@@ -0,0 +1,182 @@
+//! A persistent cache of compiled wasm modules.
+//!
+//! Compiling zomes with cranelift is the slowest part of starting a conductor,
+//! so compiled modules are serialized to a directory under the conductor's
+//! database root and deserialized on later runs instead of being compiled
+//! again. The in-memory module cache is always checked first.
+//!
+//! Each conductor's [`RibosomeStore`](crate::conductor::ribosome_store::RibosomeStore)
+//! owns the cache under its own database root and gives it to the ribosomes
+//! it stores.
+//!
+//! Modules live in a subdirectory named for the wasmer version, the target
+//! and the metering limit they were compiled with, in files named for the
+//! hash of their wasm, so a module is never loaded by an engine that didn't
+//! compile it. Subdirectories for other engines are removed by
+//! [`ModuleDiskCache::gc`].
+
+use holo_hash::WasmHash;
+use holochain_types::wasmer_types::cranelift;
+use holochain_types::wasmer_types::WASM_METERING_LIMIT;
+use holochain_wasmer_host::module::CacheKey;
+use holochain_wasmer_host::prelude::Module;
+use holochain_wasmer_host::prelude::Store;
+use holochain_wasmer_host::prelude::Triple;
+use holochain_wasmer_host::prelude::Universal;
+use holochain_wasmer_host::prelude::VERSION as WASMER_VERSION;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// The name of the directory under the database root holding the cache.
+pub const MODULE_CACHE_DIRECTORY: &str = "wasm-module-cache";
+
+/// A directory of compiled wasm modules.
+#[derive(Debug, Clone)]
+pub struct ModuleDiskCache {
+    dir: Arc<Path>,
+}
+
+impl ModuleDiskCache {
+    /// Open the cache under this database root,
+    /// creating the cache directory if needed.
+    pub fn new(db_root: &Path) -> std::io::Result<Self> {
+        let dir = db_root
+            .join(MODULE_CACHE_DIRECTORY)
+            .join(Self::engine_directory());
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir: dir.into() })
+    }
+
+    /// The name of the subdirectory for modules compiled by this engine.
+    fn engine_directory() -> String {
+        format!(
+            "wasmer-{}-{}-{}",
+            WASMER_VERSION,
+            Triple::host(),
+            WASM_METERING_LIMIT
+        )
+    }
+
+    fn module_path(&self, key: &CacheKey) -> PathBuf {
+        let file_name: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        self.dir.join(file_name)
+    }
+
+    /// Load a module from the cache, if it has been compiled before.
+    /// A module which fails to deserialize is removed from the cache so
+    /// it will be compiled again.
+    pub fn load(&self, key: &CacheKey) -> Option<Module> {
+        let path = self.module_path(key);
+        if !path.exists() {
+            return None;
+        }
+        let store = Store::new(&Universal::new(cranelift()).engine());
+        // Only modules serialized by `store` for this engine are in this directory.
+        match unsafe { Module::deserialize_from_file(&store, &path) } {
+            Ok(module) => Some(module),
+            Err(e) => {
+                tracing::warn!(
+                    ?path,
+                    ?e,
+                    "Removing cached wasm module which failed to load"
+                );
+                std::fs::remove_file(&path).ok();
+                None
+            }
+        }
+    }
+
+    /// Write a compiled module to the cache.
+    /// Failing to write is logged but otherwise ignored,
+    /// as the module will simply be compiled again next time.
+    pub fn store(&self, key: &CacheKey, module: &Module) {
+        let path = self.module_path(key);
+        // Write to a temporary file first so a partially written module
+        // is never loaded.
+        let tmp = path.with_extension(format!("tmp-{}", nanoid::nanoid!()));
+        let result = module
+            .serialize_to_file(&tmp)
+            .map_err(|e| e.to_string())
+            .and_then(|_| std::fs::rename(&tmp, &path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!(?path, %e, "Failed to cache compiled wasm module");
+            std::fs::remove_file(&tmp).ok();
+        }
+    }
+
+    /// Remove every cached module that isn't compiled from one of the given
+    /// wasms, along with the modules of every other engine.
+    /// Modules which are still being written are left alone.
+    /// Returns the number of modules removed.
+    pub fn gc(&self, referenced: impl IntoIterator<Item = WasmHash>) -> std::io::Result<usize> {
+        let keep: HashSet<_> = referenced
+            .into_iter()
+            .filter_map(|hash| {
+                let key: CacheKey = hash.get_raw_32().try_into().ok()?;
+                Some(self.module_path(&key))
+            })
+            .collect();
+
+        let mut removed = 0;
+        if let Some(root) = self.dir.parent() {
+            for entry in std::fs::read_dir(root)? {
+                let path = entry?.path();
+                if *path != *self.dir && path.is_dir() {
+                    removed += std::fs::read_dir(&path)?.count();
+                    std::fs::remove_dir_all(&path)?;
+                }
+            }
+        }
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            // Modules being written have a temporary extension.
+            if path.extension().is_none() && !keep.contains(&path) {
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn modules_are_cached_on_disk_and_collected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = ModuleDiskCache::new(tmp.path()).unwrap();
+
+        let wasm = DnaWasm::from(TestWasm::Foo);
+        let hash = DnaWasmHashed::from_content(wasm.clone()).await.into_hash();
+        let key: CacheKey = hash.get_raw_32().try_into().unwrap();
+        assert!(cache.load(&key).is_none());
+
+        let store = Store::new(&Universal::new(cranelift()).engine());
+        let module = Module::from_binary(&store, &wasm.code()).unwrap();
+        cache.store(&key, &module);
+        assert!(cache.load(&key).is_some());
+
+        // Modules compiled by other engines are always removed.
+        let stale = tmp.path().join(MODULE_CACHE_DIRECTORY).join("wasmer-0.0.0");
+        std::fs::create_dir_all(&stale).unwrap();
+        std::fs::write(stale.join("module"), b"").unwrap();
+
+        assert_eq!(cache.gc([hash]).unwrap(), 1);
+        assert!(cache.load(&key).is_some());
+        assert!(!stale.exists());
+
+        // Modules which are still being written are left alone.
+        let writing = cache.module_path(&key).with_extension("tmp-writing");
+        std::fs::write(&writing, b"").unwrap();
+
+        assert_eq!(cache.gc([]).unwrap(), 1);
+        assert!(cache.load(&key).is_none());
+        assert!(writing.exists());
+    }
+}
@@ -41,12 +41,14 @@ use crate::core::ribosome::host_fn::get::get;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
+use crate::core::ribosome::host_fn::get_meta::get_meta;
 use crate::core::ribosome::host_fn::hash::hash;
 use crate::core::ribosome::host_fn::must_get_action::must_get_action;
 use crate::core::ribosome::host_fn::must_get_agent_activity::must_get_agent_activity;
 use crate::core::ribosome::host_fn::must_get_entry::must_get_entry;
 use crate::core::ribosome::host_fn::must_get_valid_record::must_get_valid_record;
 use crate::core::ribosome::host_fn::query::query;
+use crate::core::ribosome::host_fn::query_count::query_count;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
 use crate::core::ribosome::host_fn::remote_signal::remote_signal;
 use crate::core::ribosome::host_fn::schedule::schedule;
@@ -67,11 +69,16 @@ use crate::core::ribosome::host_fn::x_salsa20_poly1305_shared_secret_create_rand
 use crate::core::ribosome::host_fn::x_salsa20_poly1305_shared_secret_export::x_salsa20_poly1305_shared_secret_export;
 use crate::core::ribosome::host_fn::x_salsa20_poly1305_shared_secret_ingest::x_salsa20_poly1305_shared_secret_ingest;
 use crate::core::ribosome::host_fn::zome_info::zome_info;
+use crate::core::ribosome::metering::MeteringBudgets;
+use crate::core::ribosome::metering::MeteringStats;
+use crate::core::ribosome::module_cache::ModuleDiskCache;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::Invocation;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::sys_validate::SpamPolicy;
 use fallible_iterator::FallibleIterator;
+use holochain_conductor_api::MeteredCallKind;
 use holochain_types::prelude::*;
 use holochain_wasmer_host::module::SerializedModuleCache;
 // This is here because there were errors about different crate versions
@@ -87,6 +94,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use wasmer_middlewares::metering::MeteringPoints;
 
 /// The only RealRibosome is a Wasm ribosome.
 /// note that this is cloned on every invocation so keep clones cheap!
@@ -104,6 +112,16 @@ pub struct RealRibosome {
 
     /// Dependencies for every zome.
     pub zome_dependencies: Arc<HashMap<ZomeName, Vec<ZomeIndex>>>,
+
+    /// Metering budgets for every zome.
+    pub metering_budgets: Arc<MeteringBudgets>,
+
+    /// The metering points used by calls into every zome.
+    pub metering_stats: MeteringStats,
+
+    /// The disk cache of compiled modules of the conductor
+    /// running this ribosome, if it has one.
+    pub module_disk_cache: Option<ModuleDiskCache>,
 }
 
 struct HostFnBuilder {
@@ -124,11 +142,18 @@ impl HostFnBuilder {
         host_function: fn(Arc<RealRibosome>, Arc<CallContext>, I) -> Result<O, RuntimeError>,
     ) -> &Self
     where
-        I: serde::de::DeserializeOwned + std::fmt::Debug,
-        O: serde::Serialize + std::fmt::Debug,
+        I: serde::de::DeserializeOwned + serde::Serialize + std::fmt::Debug,
+        O: serde::Serialize + serde::de::DeserializeOwned + std::fmt::Debug,
     {
         let ribosome_arc = Arc::clone(&self.ribosome_arc);
         let context_key = self.context_key;
+        // Host calls are taped under the name of the host function itself,
+        // the same as when they're made by inline zomes.
+        let taped_name = host_function_name
+            .strip_prefix("__hc__")
+            .and_then(|name| name.strip_suffix("_1"))
+            .unwrap_or(host_function_name)
+            .to_string();
         ns.insert(
             host_function_name,
             Function::new_with_env(
@@ -169,7 +194,12 @@ impl HostFnBuilder {
                             .clone()
                     };
                     let result = match db.consume_bytes_from_guest(guest_ptr, len) {
-                        Ok(input) => host_function(Arc::clone(&ribosome_arc), context_arc, input),
+                        Ok(input) => match context_arc.host_context.host_call_tape().cloned() {
+                            Some(tape) => tape.call(&taped_name, input, |input| {
+                                host_function(Arc::clone(&ribosome_arc), context_arc, input)
+                            }),
+                            None => host_function(Arc::clone(&ribosome_arc), context_arc, input),
+                        },
                         Err(runtime_error) => Result::<_, RuntimeError>::Err(runtime_error),
                     };
                     Ok(vec![Value::I64(i64::from_le_bytes(
@@ -234,11 +264,22 @@ fn context_key_from_key(key: &[u8; 32]) -> u64 {
 impl RealRibosome {
     /// Create a new instance
     pub fn new(dna_file: DnaFile) -> RibosomeResult<Self> {
+        let modifiers = &dna_file.dna().modifiers;
+        // Refuse DNAs whose properties configure the conductor unreadably.
+        SpamPolicy::from_modifiers(modifiers)
+            .map_err(|e| RibosomeError::InvalidDnaProperty("spam_policy".into(), e))?;
+        let metering_budgets = Arc::new(
+            MeteringBudgets::from_modifiers(modifiers)
+                .map_err(|e| RibosomeError::InvalidDnaProperty("metering".into(), e))?,
+        );
         // Create an empty ribosome.
         let ribosome = Self {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            metering_budgets,
+            metering_stats: Default::default(),
+            module_disk_cache: None,
         };
 
         // Collect the number of entry and link types
@@ -324,6 +365,9 @@ impl RealRibosome {
             dna_file: ribosome.dna_file,
             zome_types,
             zome_dependencies: Arc::new(zome_dependencies),
+            metering_budgets: ribosome.metering_budgets,
+            metering_stats: ribosome.metering_stats,
+            module_disk_cache: ribosome.module_disk_cache,
         })
     }
 
@@ -333,6 +377,9 @@ impl RealRibosome {
             dna_file,
             zome_types: Default::default(),
             zome_dependencies: Default::default(),
+            metering_budgets: Default::default(),
+            metering_stats: Default::default(),
+            module_disk_cache: None,
         }
     }
 
@@ -361,10 +408,38 @@ impl RealRibosome {
                 .ok();
         }
 
-        Ok(holochain_wasmer_host::module::MODULE_CACHE.write().get(
-            self.wasm_cache_key(zome_name)?,
-            &self.dna_file.get_wasm_for_zome(zome_name)?.code(),
-        )?)
+        use holochain_wasmer_host::module::PlruCache;
+        let key = self.wasm_cache_key(zome_name)?;
+        let mut module_cache = holochain_wasmer_host::module::MODULE_CACHE.write();
+        if let Some(module) = module_cache.get_item(&key) {
+            return Ok(module);
+        }
+
+        // Try the modules compiled by previous runs before compiling.
+        let disk_cache = self.module_disk_cache.as_ref();
+        if let Some(module) = disk_cache.and_then(|c| c.load(&key)) {
+            return Ok(module_cache.put_item(key, Arc::new(module)));
+        }
+        let module = module_cache.get(key, &self.dna_file.get_wasm_for_zome(zome_name)?.code())?;
+        if let Some(disk_cache) = disk_cache {
+            disk_cache.store(&key, &module);
+        }
+        Ok(module)
+    }
+
+    /// Compile every wasm zome of this DNA which isn't already compiled,
+    /// so the first calls into them don't have to wait for compilation.
+    pub fn precompile_zomes(&self) -> RibosomeResult<()> {
+        for (zome_name, def) in self.dna_def().all_zomes() {
+            if let ZomeDef::Wasm(WasmZome {
+                preserialized_path: None,
+                ..
+            }) = def
+            {
+                self.runtime_compiled_module(zome_name)?;
+            }
+        }
+        Ok(())
     }
 
     pub fn wasm_cache_key(&self, zome_name: &ZomeName) -> Result<[u8; 32], DnaError> {
@@ -609,6 +684,7 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__hc__capability_info_1", capability_info)
             .with_host_function(&mut ns, "__hc__get_1", get)
             .with_host_function(&mut ns, "__hc__get_details_1", get_details)
+            .with_host_function(&mut ns, "__hc__get_meta_1", get_meta)
             .with_host_function(&mut ns, "__hc__get_links_1", get_links)
             .with_host_function(&mut ns, "__hc__get_link_details_1", get_link_details)
             .with_host_function(&mut ns, "__hc__get_agent_activity_1", get_agent_activity)
@@ -625,6 +701,7 @@ impl RealRibosome {
                 must_get_agent_activity,
             )
             .with_host_function(&mut ns, "__hc__query_1", query)
+            .with_host_function(&mut ns, "__hc__query_count_1", query_count)
             .with_host_function(&mut ns, "__hc__remote_signal_1", remote_signal)
             .with_host_function(&mut ns, "__hc__call_1", call)
             .with_host_function(&mut ns, "__hc__create_1", create)
@@ -660,7 +737,11 @@ impl RealRibosome {
             // there is a callback to_call and it is implemented in the wasm
             // it is important to fully instantiate this (e.g. don't try to use the module above)
             // because it builds guards against memory leaks and handles imports correctly
+            let kind = MeteredCallKind::from(&call_context.host_context);
+            let meter = call_context.host_context.zome_call_meter().cloned();
+            let budget = self.metering_budgets.budget(zome.zome_name(), kind);
             let (instance, context_key) = self.instance(call_context)?;
+            wasmer_middlewares::metering::set_remaining_points(&instance.lock(), budget);
 
             let result: Result<ExternIO, RuntimeError> = holochain_wasmer_host::guest::call(
                 instance.clone(),
@@ -671,6 +752,19 @@ impl RealRibosome {
                 invocation.to_owned().host_input()?,
             );
 
+            let (points, exhausted) =
+                match wasmer_middlewares::metering::get_remaining_points(&instance.lock()) {
+                    MeteringPoints::Remaining(remaining) => {
+                        (budget.saturating_sub(remaining), false)
+                    }
+                    MeteringPoints::Exhausted => (budget, true),
+                };
+            self.metering_stats
+                .record(zome.zome_name(), kind, budget, points, exhausted);
+            if let Some(meter) = meter {
+                meter.add(points);
+            }
+
             // a bit of typefu to avoid cloning the result.
             let (can_cache, result) = match result {
                 Err(runtime_error) => match runtime_error.downcast::<WasmError>() {
@@ -879,6 +973,36 @@ impl RibosomeT for RealRibosome {
         }
     }
 
+    fn list_extern_fns(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>> {
+        match zome.zome_def() {
+            ZomeDef::Wasm(wasm_zome) => {
+                let module = if let Some(path) = wasm_zome.preserialized_path.as_ref() {
+                    self.precompiled_module(path)?
+                } else {
+                    self.runtime_compiled_module(zome.zome_name())?
+                };
+                // Only functions, without the exports the hdk needs for
+                // memory management.
+                let mut extern_fns: Vec<_> = module
+                    .info()
+                    .exports
+                    .iter()
+                    .filter(|(name, index)| {
+                        matches!(index, ExportIndex::Function(_)) && !name.starts_with("__")
+                    })
+                    .map(|(name, _)| FunctionName::new(name))
+                    .collect();
+                extern_fns.sort();
+                Ok(extern_fns)
+            }
+            ZomeDef::Inline { inline_zome, .. } => {
+                let mut extern_fns = inline_zome.0.functions();
+                extern_fns.sort();
+                Ok(extern_fns)
+            }
+        }
+    }
+
     fn get_const_fn(&self, zome: &Zome, name: &str) -> Result<Option<i32>, RibosomeError> {
         // Create a blank context as this is not actually used.
         let call_context = CallContext {
@@ -1110,12 +1234,14 @@ pub mod wasm_test {
                 "__hc__get_details_1",
                 "__hc__get_link_details_1",
                 "__hc__get_links_1",
+                "__hc__get_meta_1",
                 "__hc__hash_1",
                 "__hc__must_get_action_1",
                 "__hc__must_get_agent_activity_1",
                 "__hc__must_get_entry_1",
                 "__hc__must_get_valid_record_1",
                 "__hc__query_1",
+                "__hc__query_count_1",
                 "__hc__random_bytes_1",
                 "__hc__remote_signal_1",
                 "__hc__schedule_1",
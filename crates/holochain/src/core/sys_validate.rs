@@ -25,9 +25,12 @@ pub use holochain_zome_types::Timestamp;
 
 #[allow(missing_docs)]
 mod error;
+mod spam_policy;
 #[cfg(test)]
 mod tests;
 
+pub use spam_policy::*;
+
 /// 16mb limit on Entries due to websocket limits.
 /// 4mb limit to constrain bandwidth usage on uploading.
 /// (Assuming a baseline 5mbps upload for now... update this
@@ -97,9 +100,32 @@ pub fn check_countersigning_session_data_contains_action(
 pub async fn check_countersigning_preflight_response_signature(
     preflight_response: &PreflightResponse,
 ) -> SysValidationResult<()> {
-    let signature_is_valid = preflight_response
-        .request()
-        .signing_agents
+    check_preflight_response_signature_of(
+        preflight_response,
+        &preflight_response.request().signing_agents,
+    )
+    .await
+}
+
+/// Verify that the signature on a preflight request from an optional
+/// signing agent is valid.
+pub async fn check_countersigning_optional_preflight_response_signature(
+    preflight_response: &PreflightResponse,
+) -> SysValidationResult<()> {
+    check_preflight_response_signature_of(
+        preflight_response,
+        &preflight_response.request().optional_signing_agents,
+    )
+    .await
+}
+
+/// Verify the signature on a preflight response against the agent at the
+/// response's agent index in the given agents.
+async fn check_preflight_response_signature_of(
+    preflight_response: &PreflightResponse,
+    signing_agents: &CounterSigningAgents,
+) -> SysValidationResult<()> {
+    let signature_is_valid = signing_agents
         .get(*preflight_response.agent_state().agent_index() as usize)
         .ok_or_else(|| {
             SysValidationError::ValidationOutcome(ValidationOutcome::PreflightResponseSignature(
@@ -139,18 +165,29 @@ pub async fn check_countersigning_session_data(
     session_data.check_integrity()?;
     check_countersigning_session_data_contains_action(entry_hash, session_data, action)?;
 
-    let tasks: Vec<_> = session_data
+    let tasks = session_data
         .responses()
         .iter()
-        .map(|(response, signature)| async move {
+        .map(|(response, signature)| (response, signature, false))
+        .chain(
+            session_data
+                .optional_responses()
+                .iter()
+                .map(|(response, signature)| (response, signature, true)),
+        )
+        .map(|(response, signature, optional)| async move {
             let preflight_response = PreflightResponse::try_new(
                 session_data.preflight_request().clone(),
                 response.clone(),
                 signature.clone(),
             )?;
-            check_countersigning_preflight_response_signature(&preflight_response).await
-        })
-        .collect();
+            if optional {
+                check_countersigning_optional_preflight_response_signature(&preflight_response)
+                    .await
+            } else {
+                check_countersigning_preflight_response_signature(&preflight_response).await
+            }
+        });
 
     let results: Vec<SysValidationResult<()>> = futures::future::join_all(tasks).await;
     let results: SysValidationResult<()> = results.into_iter().collect();
@@ -204,35 +241,79 @@ pub fn check_valid_if_dna(action: &Action, dna_def: &DnaDefHashed) -> SysValidat
     }
 }
 
-/// Check if there are other actions at this
-/// sequence number
+/// Check if there are other actions by the same author at this
+/// sequence number, which means the author has forked their chain.
+/// This happens when an agent rolls back to an old backup of their
+/// chain and keeps authoring from there.
 pub async fn check_chain_rollback(
     action: &Action,
     workspace: &SysValidationWorkspace,
-) -> SysValidationResult<()> {
-    let empty = workspace.action_seq_is_empty(action).await?;
-
-    // Ok or log warning
-    if empty {
-        Ok(())
-    } else {
-        // TODO: implement real rollback detection once we know what that looks like
-        tracing::error!(
-            "Chain rollback detected at position {} for agent {:?} from action {:?}",
-            action.action_seq(),
+) -> SysValidationResult<Option<ChainFork>> {
+    let other = workspace.other_action_at_seq(action).await?;
+
+    Ok(other.map(|other| {
+        let fork = ChainFork {
+            fork_seq: action.action_seq(),
+            first_action: other,
+            second_action: ActionHash::with_data_sync(action),
+        };
+        tracing::warn!(
+            "Chain fork detected at position {} for agent {:?} between actions {} and {}",
+            fork.fork_seq,
             action.author(),
-            action,
+            fork.first_action,
+            fork.second_action,
         );
-        Ok(())
+        fork
+    }))
+}
+
+/// Check an op against the DNA's [`SpamPolicy`].
+/// Entries and link tags must be within the policy's size limits, and
+/// the activity authority checks the author hasn't exceeded the
+/// policy's rate of actions in the window before this action.
+/// The rate is only checked once the author's chain in the window has
+/// been validated, until then the op awaits it.
+pub async fn check_spam(
+    op: &DhtOp,
+    policy: &SpamPolicy,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<()> {
+    match op {
+        DhtOp::StoreRecord(_, _, Some(entry))
+        | DhtOp::StoreEntry(_, _, entry)
+        | DhtOp::RegisterUpdatedContent(_, _, Some(entry))
+        | DhtOp::RegisterUpdatedRecord(_, _, Some(entry)) => {
+            check_entry_size_limit(entry, policy.max_entry_size)
+        }
+        DhtOp::RegisterAddLink(_, link_add) => {
+            check_tag_size_limit(&link_add.tag, policy.max_tag_size)
+        }
+        DhtOp::RegisterAgentActivity(_, action) => {
+            let max = match policy.max_actions_per_window {
+                Some(max) => max,
+                None => return Ok(()),
+            };
+            let start = action.timestamp().saturating_sub(&policy.window());
+            let count = workspace
+                .count_actions_since(action, start, max as usize)
+                .await?
+                + 1;
+            if count > max as usize {
+                Err(ValidationOutcome::TooManyActions(
+                    action.author().clone(),
+                    max,
+                    policy.window_secs,
+                )
+                .into())
+            } else {
+                Ok(())
+            }
+        }
+        _ => Ok(()),
     }
 }
 
-/// Placeholder for future spam check.
-/// Check action timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_action: &Action) -> SysValidationResult<()> {
-    Ok(())
-}
-
 /// Check that created agents are always paired with an AgentValidationPkg and vice versa
 pub fn check_agent_validation_pkg_predecessor(
     action: &Action,
@@ -403,13 +484,18 @@ pub fn check_new_entry_action(action: &Action) -> SysValidationResult<()> {
 
 /// Check the entry size is under the MAX_ENTRY_SIZE
 pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
+    check_entry_size_limit(entry, MAX_ENTRY_SIZE)
+}
+
+/// Check the entry size is under a limit
+pub fn check_entry_size_limit(entry: &Entry, max: usize) -> SysValidationResult<()> {
     match entry {
         Entry::App(bytes) => {
             let size = std::mem::size_of_val(&bytes.bytes()[..]);
-            if size < MAX_ENTRY_SIZE {
+            if size < max {
                 Ok(())
             } else {
-                Err(ValidationOutcome::EntryTooLarge(size, MAX_ENTRY_SIZE).into())
+                Err(ValidationOutcome::EntryTooLarge(size, max).into())
             }
         }
         // Other entry types are small
@@ -419,11 +505,16 @@ pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
 
 /// Check the link tag size is under the MAX_TAG_SIZE
 pub fn check_tag_size(tag: &LinkTag) -> SysValidationResult<()> {
+    check_tag_size_limit(tag, MAX_TAG_SIZE)
+}
+
+/// Check the link tag size is under a limit
+pub fn check_tag_size_limit(tag: &LinkTag, max: usize) -> SysValidationResult<()> {
     let size = std::mem::size_of_val(&tag.0[..]);
-    if size < MAX_TAG_SIZE {
+    if size < max {
         Ok(())
     } else {
-        Err(ValidationOutcome::TagTooLarge(size, MAX_TAG_SIZE).into())
+        Err(ValidationOutcome::TagTooLarge(size, max).into())
     }
 }
 
@@ -119,6 +119,8 @@ pub enum ValidationOutcome {
     EntryVisibility(AppEntryDef),
     #[error("The link tag size {0} was larger than the MAX_TAG_SIZE {1}")]
     TagTooLarge(usize, usize),
+    #[error("The author {0:?} authored more than {1} actions in {2} seconds")]
+    TooManyActions(AgentPubKey, u32, u64),
     #[error("The action {0:?} was expected to be a link add action")]
     NotCreateLink(ActionHash),
     #[error("The action was expected to be a new entry action but was a {0:?}")]
@@ -0,0 +1,126 @@
+use super::MAX_ENTRY_SIZE;
+use super::MAX_TAG_SIZE;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::DnaModifiers;
+
+/// Limits that authorities enforce on publishers during sys validation,
+/// so a single author can't flood the DHT.
+///
+/// The defaults can be overridden per DNA under the `spam_policy` key of the
+/// DNA properties, for example:
+///
+/// ```yaml
+/// properties:
+///   spam_policy:
+///     max_actions_per_window: 100
+///     window_secs: 60
+///     max_entry_size: 100000
+/// ```
+///
+/// Entry and tag sizes above the global limits are clamped to those limits.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpamPolicy {
+    /// The most actions an author may author in any window.
+    /// There is no limit by default.
+    pub max_actions_per_window: Option<u32>,
+    /// The length of the window in seconds.
+    pub window_secs: u64,
+    /// The largest app entry in bytes.
+    pub max_entry_size: usize,
+    /// The largest link tag in bytes.
+    pub max_tag_size: usize,
+}
+
+impl Default for SpamPolicy {
+    fn default() -> Self {
+        Self {
+            max_actions_per_window: None,
+            window_secs: 60,
+            max_entry_size: MAX_ENTRY_SIZE,
+            max_tag_size: MAX_TAG_SIZE,
+        }
+    }
+}
+
+impl SpamPolicy {
+    /// Read the policy for a DNA from its properties,
+    /// falling back to the defaults if none is set.
+    /// A `spam_policy` which can't be read is an error rather than
+    /// being ignored, so a typo can't silently disable the policy.
+    /// DNAs with such a policy are refused when they are registered.
+    pub fn from_modifiers(modifiers: &DnaModifiers) -> Result<Self, SerializedBytesError> {
+        // Properties which aren't a map have no policy.
+        let properties: serde_yaml::Value =
+            holochain_serialized_bytes::decode(modifiers.properties.bytes()).unwrap_or_default();
+        let policy: Self = match properties.get("spam_policy") {
+            Some(policy) => serde_yaml::from_value(policy.clone())
+                .map_err(|e| SerializedBytesError::Deserialize(e.to_string()))?,
+            None => Self::default(),
+        };
+        Ok(Self {
+            max_entry_size: policy.max_entry_size.min(MAX_ENTRY_SIZE),
+            max_tag_size: policy.max_tag_size.min(MAX_TAG_SIZE),
+            ..policy
+        })
+    }
+
+    /// The length of the window.
+    pub fn window(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.window_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::prelude::*;
+
+    fn modifiers(properties: serde_yaml::Value) -> DnaModifiers {
+        DnaModifiersBuilder::default()
+            .network_seed("".into())
+            .properties(YamlProperties::new(properties).try_into().unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn spam_policy_from_properties() {
+        let props = serde_yaml::from_str(
+            "
+            foo: bar
+            spam_policy:
+              max_actions_per_window: 10
+              max_tag_size: 100000
+            ",
+        )
+        .unwrap();
+        let policy = SpamPolicy::from_modifiers(&modifiers(props)).unwrap();
+        assert_eq!(
+            policy,
+            SpamPolicy {
+                max_actions_per_window: Some(10),
+                max_tag_size: MAX_TAG_SIZE,
+                ..Default::default()
+            }
+        );
+
+        let policy = SpamPolicy::from_modifiers(&modifiers(serde_yaml::Value::Null)).unwrap();
+        assert_eq!(policy, SpamPolicy::default());
+        let policy =
+            SpamPolicy::from_modifiers(&modifiers(serde_yaml::Value::from("not a map"))).unwrap();
+        assert_eq!(policy, SpamPolicy::default());
+    }
+
+    #[test]
+    fn malformed_spam_policy_is_an_error() {
+        let props = serde_yaml::from_str(
+            "
+            spam_policy:
+              max_actions_per_window: lots
+            ",
+        )
+        .unwrap();
+        assert!(SpamPolicy::from_modifiers(&modifiers(props)).is_err());
+    }
+}
@@ -200,6 +200,141 @@ async fn check_previous_seq() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn check_chain_rollback_test() {
+    let tmp = test_authored_db();
+    let tmp_dht = test_dht_db();
+    let tmp_cache = test_cache_db();
+    let cache: DhtDbQueryCache = tmp_dht.to_db().into();
+    let workspace = SysValidationWorkspace::new(
+        tmp.to_db().into(),
+        tmp_dht.to_db().into(),
+        cache,
+        tmp_cache.to_db(),
+        Arc::new(fixt!(DnaDef)),
+    );
+
+    let mut action = fixt!(CreateLink);
+    action.action_seq = 5;
+    let action: Action = action.into();
+    let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+        fixt!(Signature),
+        action.clone(),
+    ));
+    tmp_dht.to_db().test_commit(|txn| {
+        holochain_state::mutations::insert_op(txn, &op).unwrap();
+    });
+
+    // The action itself is not a fork.
+    assert_matches!(check_chain_rollback(&action, &workspace).await, Ok(None));
+
+    // Another action by the same author at a different seq is not a fork.
+    let mut next = fixt!(CreateLink);
+    next.author = action.author().clone();
+    next.action_seq = 6;
+    assert_matches!(
+        check_chain_rollback(&next.clone().into(), &workspace).await,
+        Ok(None)
+    );
+
+    // Another action by the same author at the same seq is a fork.
+    next.action_seq = 5;
+    let fork = check_chain_rollback(&next.clone().into(), &workspace)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fork.fork_seq, 5);
+    assert_eq!(fork.first_action, ActionHash::with_data_sync(&action));
+    assert_eq!(
+        fork.second_action,
+        ActionHash::with_data_sync(&Action::from(next))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_spam_rate_test() {
+    let tmp = test_authored_db();
+    let tmp_dht = test_dht_db();
+    let tmp_cache = test_cache_db();
+    let cache: DhtDbQueryCache = tmp_dht.to_db().into();
+    let workspace = SysValidationWorkspace::new(
+        tmp.to_db().into(),
+        tmp_dht.to_db().into(),
+        cache,
+        tmp_cache.to_db(),
+        Arc::new(fixt!(DnaDef)),
+    );
+
+    let author = fixt!(AgentPubKey);
+    let now = Timestamp::now();
+    let make_action = |seq: u32, prev_action: ActionHash| {
+        let mut action = fixt!(CreateLink);
+        action.author = author.clone();
+        action.action_seq = seq;
+        action.prev_action = prev_action;
+        action.timestamp = now;
+        Action::from(action)
+    };
+
+    // The author's chain starts before the window, and they have already
+    // authored three actions in it.
+    let mut dna = fixt!(Dna);
+    dna.author = author.clone();
+    dna.timestamp = now.saturating_sub(&std::time::Duration::from_secs(60 * 60));
+    let dna = Action::from(dna);
+    let mut prev_action = ActionHash::with_data_sync(&dna);
+    let mut chain = vec![DhtOpHashed::from_content_sync(
+        DhtOp::RegisterAgentActivity(fixt!(Signature), dna),
+    )];
+    for seq in 1..=3 {
+        let action = make_action(seq, prev_action.clone());
+        prev_action = ActionHash::with_data_sync(&action);
+        chain.push(DhtOpHashed::from_content_sync(
+            DhtOp::RegisterAgentActivity(fixt!(Signature), action),
+        ));
+    }
+    let op = DhtOp::RegisterAgentActivity(fixt!(Signature), make_action(4, prev_action));
+
+    // Until the author's chain is held and validated the rate can't be checked.
+    let policy = SpamPolicy {
+        max_actions_per_window: Some(4),
+        ..Default::default()
+    };
+    assert_matches!(
+        check_spam(&op, &policy, &workspace).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::NotHoldingDep(_)
+        ))
+    );
+
+    tmp_dht.to_db().test_commit(|txn| {
+        for op in &chain {
+            holochain_state::mutations::insert_op(txn, op).unwrap();
+            holochain_state::mutations::set_validation_status(
+                txn,
+                op.as_hash(),
+                ValidationStatus::Valid,
+            )
+            .unwrap();
+        }
+    });
+
+    // No limit by default.
+    let mut policy = SpamPolicy::default();
+    assert_matches!(check_spam(&op, &policy, &workspace).await, Ok(()));
+
+    policy.max_actions_per_window = Some(4);
+    assert_matches!(check_spam(&op, &policy, &workspace).await, Ok(()));
+
+    policy.max_actions_per_window = Some(3);
+    assert_matches!(
+        check_spam(&op, &policy, &workspace).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::TooManyActions(_, 3, 60)
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_entry_type_test() {
     let entry_fixt = EntryFixturator::new(Predictable);
@@ -3,6 +3,7 @@ use super::app_validation_workflow::AppValidationError;
 use super::app_validation_workflow::Outcome;
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::sys_validate_record;
+use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::CellConductorApi;
 use crate::conductor::api::CellConductorApiT;
 use crate::conductor::interface::SignalBroadcaster;
@@ -10,6 +11,8 @@ use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::post_commit::send_post_commit;
+use crate::core::ribosome::host_call_tape::HostCallTape;
+use crate::core::ribosome::metering::ZomeCallMeter;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
@@ -37,6 +40,8 @@ pub struct CallZomeWorkflowArgs<RibosomeT> {
     pub conductor_handle: ConductorHandle,
     pub is_root_zome_call: bool,
     pub cell_id: CellId,
+    /// If set, the metering points used by the call are added to this meter.
+    pub meter: Option<ZomeCallMeter>,
 }
 
 #[instrument(skip(
@@ -65,14 +70,33 @@ where
         .ok();
     let should_write = args.is_root_zome_call;
     let conductor_handle = args.conductor_handle.clone();
+    let cell_id = args.cell_id.clone();
     let result =
         call_zome_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args)
             .await?;
+    if should_write {
+        // The call may have fetched into the caches of its apps.
+        if let Err(e) = conductor_handle.enforce_cache_quota(&cell_id).await {
+            tracing::warn!(?e, "Could not enforce the app cache quotas");
+        }
+    }
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
     if should_write {
+        let scratch = workspace.source_chain().scratch();
+        let has_authored = scratch
+            .apply(|scratch| !scratch.is_empty())
+            .map_err(SourceChainError::from)?;
+        let authored_write = if has_authored {
+            conductor_handle
+                .check_authored_quota(&cell_id, &scratch)
+                .await
+                .map_err(|e| Box::new(ConductorApiError::from(e)))?
+        } else {
+            None
+        };
         let is_empty = workspace.source_chain().is_empty()?;
         let countersigning_op = workspace.source_chain().countersigning_op()?;
         match HostFnWorkspace::from(workspace.clone())
@@ -80,6 +104,9 @@ where
             .await
         {
             Ok(flushed_actions) => {
+                if let Some(write) = authored_write {
+                    write.written();
+                }
                 // Q: what is the purpose of checking for an empty chain? When would this ever happen? The chain should
                 //    be genesis'd by now, right?
                 if !is_empty {
@@ -143,24 +170,64 @@ where
         signal_tx,
         conductor_handle,
         cell_id,
+        meter,
         ..
     } = args;
 
+    let recording = conductor_handle.is_recording_zome_calls(&cell_id).then(|| {
+        (
+            HostCallTape::record(),
+            ZomeCallUnsigned {
+                provenance: invocation.provenance.clone(),
+                cell_id: invocation.cell_id.clone(),
+                zome_name: invocation.zome.zome_name().clone(),
+                fn_name: invocation.fn_name.clone(),
+                cap_secret: invocation.cap_secret,
+                payload: invocation.payload.clone(),
+                nonce: invocation.nonce,
+                expires_at: invocation.expires_at,
+            },
+            invocation.signature.clone(),
+            Timestamp::now(),
+        )
+    });
+
     let call_zome_handle =
         CellConductorApi::new(conductor_handle.clone(), cell_id).into_call_zome_handle();
 
     tracing::trace!("Before zome call");
-    let host_access = ZomeCallHostAccess::new(
+    let mut host_access = ZomeCallHostAccess::new(
         workspace.clone().into(),
         keystore,
         network.clone(),
         signal_tx,
         call_zome_handle,
     );
-    let (ribosome, result) =
-        call_zome_function_authorized(ribosome, host_access, invocation).await?;
+    if let Some((tape, ..)) = &recording {
+        host_access = host_access.with_host_call_tape(tape.clone());
+    }
+    if let Some(meter) = meter {
+        host_access = host_access.with_meter(meter);
+    }
+    let call_result = call_zome_function_authorized(ribosome, host_access, invocation).await;
     tracing::trace!("After zome call");
 
+    // Calls which fail before or outside of the zome are recorded too.
+    if let Some((tape, call, signature, recorded_at)) = recording {
+        conductor_handle.add_zome_call_recording(ZomeCallRecording {
+            call,
+            signature,
+            recorded_at,
+            host_calls: tape.host_calls(),
+            response: match &call_result {
+                Ok((_, Ok(response))) => Ok(response.clone()),
+                Ok((_, Err(error))) => Err(error.to_string()),
+                Err(error) => Err(error.to_string()),
+            },
+        });
+    }
+    let (ribosome, result) = call_result?;
+
     let validation_result =
         inline_validation(workspace.clone(), network, conductor_handle, ribosome).await;
     if matches!(
@@ -198,7 +265,7 @@ where
 pub async fn call_zome_function_authorized<R>(
     ribosome: R,
     host_access: ZomeCallHostAccess,
-    invocation: ZomeCallInvocation,
+    mut invocation: ZomeCallInvocation,
 ) -> WorkflowResult<(R, RibosomeResult<ZomeCallResponse>)>
 where
     R: RibosomeT + 'static,
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use holo_hash::{ActionHash, AgentPubKey, DhtOpHash, EntryHash, OpBasis};
 use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::{HolochainP2pDna, HolochainP2pDnaT};
+use holochain_sqlite::db::{DbKindAuthored, DbWrite};
 use holochain_state::integrate::authored_ops_to_dht_db_without_check;
 use holochain_state::mutations;
 use holochain_state::prelude::{
     current_countersigning_session, SourceChainResult, StateMutationResult, Store,
 };
+use holochain_types::countersigning::CountersigningSessionActions;
 use holochain_types::dht_op::DhtOp;
 use holochain_types::signal::{Signal, SystemSignal};
 use holochain_zome_types::Timestamp;
@@ -28,8 +31,12 @@ use super::{error::WorkflowResult, incoming_dht_ops_workflow::incoming_dht_ops_w
 #[derive(Clone)]
 /// A cheaply clonable, thread safe and in-memory store for
 /// active countersigning sessions.
+/// If the workspace has a database then every op put in the workspace is
+/// also persisted there, so that the sessions can be resumed after a restart.
 pub struct CountersigningWorkspace {
     inner: Share<CountersigningWorkspaceInner>,
+    db: Option<DbWrite<DbKindAuthored>>,
+    resumed: Arc<AtomicBool>,
 }
 
 #[derive(Default)]
@@ -41,9 +48,9 @@ pub struct CountersigningWorkspaceInner {
 #[derive(Default)]
 struct Session {
     /// Map of action hash for a each signers action to the
-    /// [`DhtOp`] and other required actions for this session to be
+    /// [`DhtOp`] and the actions for this session to be
     /// considered complete.
-    map: HashMap<ActionHash, (DhtOpHash, DhtOp, Vec<ActionHash>)>,
+    map: HashMap<ActionHash, (DhtOpHash, DhtOp, CountersigningSessionActions)>,
     /// When this session expires.
     /// If this is none the session is empty.
     expires: Option<Timestamp>,
@@ -53,7 +60,7 @@ struct Session {
 // TODO: PERF: This takes a lock on the workspace which could
 // block other incoming DhtOps if there are many active sessions.
 // We could create an incoming buffer if this actually becomes an issue.
-pub(crate) fn incoming_countersigning(
+pub(crate) async fn incoming_countersigning(
     ops: Vec<(DhtOpHash, DhtOp)>,
     workspace: &CountersigningWorkspace,
     trigger: TriggerSender,
@@ -61,37 +68,24 @@ pub(crate) fn incoming_countersigning(
     let mut should_trigger = false;
 
     // For each op check it's the right type and extract the
-    // entry hash, required actions and expires time.
+    // entry hash, session actions and expires time.
     for (hash, op) in ops {
-        // Must be a store entry op.
-        if let DhtOp::StoreEntry(_, _, entry) = &op {
-            // Must have a counter sign entry type.
-            if let Entry::CounterSign(session_data, _) = entry.as_ref() {
-                let entry_hash = EntryHash::with_data_sync(&**entry);
-                // Get the required actions for this session.
-                let weight = weigh_placeholder();
-                let action_set = session_data.build_action_set(entry_hash, weight)?;
-
-                // Get the expires time for this session.
-                let expires = *session_data.preflight_request().session_times.end();
-
-                // Get the entry hash from an action.
-                // If the actions have different entry hashes they will fail validation.
-                if let Some(entry_hash) = action_set.first().and_then(|h| h.entry_hash().cloned()) {
-                    // Hash the required actions.
-                    let required_actions: Vec<_> = action_set
-                        .into_iter()
-                        .map(|h| ActionHash::with_data_sync(&h))
-                        .collect();
-
-                    // Check if already timed out.
-                    if holochain_zome_types::Timestamp::now() < expires {
-                        // Put this op in the pending map.
-                        workspace.put(entry_hash, hash, op, required_actions, expires);
-                        // We have new ops so we should trigger the workflow.
-                        should_trigger = true;
-                    }
-                }
+        if let Some((entry_hash, session_actions, expires)) = countersigning_session_of(&op)? {
+            // Check if already timed out.
+            if holochain_zome_types::Timestamp::now() < expires {
+                workspace
+                    .persist(
+                        entry_hash.clone(),
+                        hash.clone(),
+                        op.clone(),
+                        session_actions.clone(),
+                        expires,
+                    )
+                    .await?;
+                // Put this op in the pending map.
+                workspace.put(entry_hash, hash, op, session_actions, expires);
+                // We have new ops so we should trigger the workflow.
+                should_trigger = true;
             }
         }
     }
@@ -103,6 +97,40 @@ pub(crate) fn incoming_countersigning(
     Ok(())
 }
 
+/// Get the entry hash, session actions and expires time of the
+/// countersigning session an op is for, if it is a store entry op
+/// for a countersigned entry.
+fn countersigning_session_of(
+    op: &DhtOp,
+) -> WorkflowResult<Option<(EntryHash, CountersigningSessionActions, Timestamp)>> {
+    // Must be a store entry op.
+    if let DhtOp::StoreEntry(_, _, entry) = op {
+        // Must have a counter sign entry type.
+        if let Entry::CounterSign(session_data, _) = entry.as_ref() {
+            let entry_hash = EntryHash::with_data_sync(&**entry);
+            // Get the actions for this session.
+            let weight = weigh_placeholder();
+            let action_set = session_data.build_action_set(entry_hash.clone(), weight.clone())?;
+
+            // Get the expires time for this session.
+            let expires = *session_data.preflight_request().session_times.end();
+
+            // Get the entry hash from an action.
+            // If the actions have different entry hashes they will fail validation.
+            if let Some(entry_hash) = action_set.first().and_then(|h| h.entry_hash().cloned()) {
+                // Hash the required and optional actions.
+                let session_actions = CountersigningSessionActions::from_session_data(
+                    session_data,
+                    entry_hash.clone(),
+                    weight,
+                )?;
+                return Ok(Some((entry_hash, session_actions, expires)));
+            }
+        }
+    }
+    Ok(None)
+}
+
 /// Countersigning workflow that checks for complete sessions and
 /// pushes the complete ops to validation then messages the signers.
 pub(crate) async fn countersigning_workflow(
@@ -110,8 +138,16 @@ pub(crate) async fn countersigning_workflow(
     network: impl HolochainP2pDnaT + Send + Sync,
     sys_validation_trigger: TriggerSender,
 ) -> WorkflowResult<WorkComplete> {
+    // Pick up any sessions which were in progress before a restart.
+    space.countersigning_workspace.resume().await?;
+
     // Get any complete sessions.
     let complete_sessions = space.countersigning_workspace.get_complete_sessions();
+    let complete_entry_hashes: Vec<_> = complete_sessions
+        .iter()
+        .flat_map(|(_, _, actions)| actions.first())
+        .filter_map(|SignedAction(action, _)| action.entry_hash().cloned())
+        .collect();
     let mut notify_agents = Vec::with_capacity(complete_sessions.len());
 
     // For each complete session send the ops to validation.
@@ -132,6 +168,13 @@ pub(crate) async fn countersigning_workflow(
         notify_agents.push((agents, actions));
     }
 
+    // The complete sessions are now in validation so they no longer
+    // need to be persisted.
+    space
+        .countersigning_workspace
+        .forget(complete_entry_hashes)
+        .await?;
+
     // For each complete session notify the agents of success.
     for (agents, actions) in notify_agents {
         if let Err(e) = network
@@ -253,13 +296,12 @@ pub(crate) async fn countersigning_success(
                 // Check we have the right session.
                 if cs_entry_hash == entry_hash {
                     let weight = weigh_placeholder();
-                    let stored_actions = cs.build_action_set(entry_hash, weight)?;
-                    if stored_actions.len() == incoming_actions.len() {
-                        // Check all stored action hashes match an incoming action hash.
-                        if stored_actions.iter().all(|h| {
-                            let h = ActionHash::with_data_sync(h);
-                            incoming_actions.iter().any(|i| *i == h)
-                        }) {
+                    let session_actions = CountersigningSessionActions::from_session_data(&cs, entry_hash, weight)?;
+                    // Check every incoming action is part of the session.
+                    if incoming_actions.iter().all(|i| session_actions.contains(i)) {
+                        // Check all required and enough optional action hashes
+                        // match an incoming action hash.
+                        if session_actions.is_complete(|h| incoming_actions.contains(h)) {
                             // All checks have passed so unlock the chain.
                             mutations::unlock_chain(txn, &author)?;
                             // Update ops to publish.
@@ -358,7 +400,91 @@ impl CountersigningWorkspace {
     pub fn new() -> CountersigningWorkspace {
         Self {
             inner: Share::new(Default::default()),
+            db: None,
+            resumed: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Create a new countersigning workspace which persists its sessions
+    /// to the authored database, and resumes any sessions found there.
+    pub fn persisted(db: DbWrite<DbKindAuthored>) -> CountersigningWorkspace {
+        Self {
+            inner: Share::new(Default::default()),
+            db: Some(db),
+            resumed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Put the sessions persisted in the database back in the workspace.
+    /// This only happens the first time it is called.
+    async fn resume(&self) -> WorkflowResult<()> {
+        let db = match &self.db {
+            Some(db) => db,
+            None => return Ok(()),
+        };
+        if self.resumed.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let ops = db
+            .async_reader(|txn| holochain_state::chain_lock::get_countersigning_ops(&txn))
+            .await?;
+        for (entry_hash, expires, op_hash, op, session_actions) in ops {
+            self.put(entry_hash, op_hash, op, session_actions, expires);
+        }
+        Ok(())
+    }
+
+    /// Persist a single signers store entry op.
+    async fn persist(
+        &self,
+        entry_hash: EntryHash,
+        op_hash: DhtOpHash,
+        op: DhtOp,
+        session_actions: CountersigningSessionActions,
+        expires: Timestamp,
+    ) -> WorkflowResult<()> {
+        if let Some(db) = &self.db {
+            db.async_commit(move |txn| {
+                mutations::insert_countersigning_op(
+                    txn,
+                    &entry_hash,
+                    &op_hash,
+                    &op,
+                    &session_actions,
+                    &expires,
+                )
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Drop the persisted ops of sessions which are complete, along with
+    /// any which have expired.
+    async fn forget(&self, complete: Vec<EntryHash>) -> WorkflowResult<()> {
+        if let Some(db) = &self.db {
+            db.async_commit(move |txn| {
+                for entry_hash in complete {
+                    mutations::delete_countersigning_ops(txn, &entry_hash)?;
+                }
+                mutations::delete_expired_countersigning_ops(txn, Timestamp::now())
+            })
+            .await?;
         }
+        Ok(())
+    }
+
+    /// Drop a session from the workspace and the database, whether or not
+    /// it is complete.
+    pub async fn abandon(&self, entry_hash: EntryHash) -> WorkflowResult<()> {
+        self.inner
+            .share_mut(|i, _| {
+                i.pending.remove(&entry_hash);
+                Ok(())
+            })
+            // We don't close this share so we can ignore this error.
+            .ok();
+        self.forget(vec![entry_hash]).await
     }
 
     /// Put a single signers store entry op in the workspace.
@@ -367,7 +493,7 @@ impl CountersigningWorkspace {
         entry_hash: EntryHash,
         op_hash: DhtOpHash,
         op: DhtOp,
-        required_actions: Vec<ActionHash>,
+        session_actions: CountersigningSessionActions,
         expires: Timestamp,
     ) {
         // hash the action of this ops.
@@ -380,7 +506,7 @@ impl CountersigningWorkspace {
                 // Insert the op into the session.
                 session
                     .map
-                    .insert(action_hash, (op_hash, op, required_actions));
+                    .insert(action_hash, (op_hash, op, session_actions));
 
                 // Set the expires time.
                 session.expires = Some(expires);
@@ -404,12 +530,10 @@ impl CountersigningWorkspace {
                     .pending
                     .iter()
                     .filter_map(|(entry_hash, session)| {
-                        // If all session required actions are contained in the map
-                        // then the session is complete.
-                        if session.map.values().all(|(_, _, required_hashes)| {
-                            required_hashes
-                                .iter()
-                                .all(|hash| session.map.contains_key(hash))
+                        // If all required actions and enough optional actions
+                        // are contained in the map then the session is complete.
+                        if session.map.values().all(|(_, _, session_actions)| {
+                            session_actions.is_complete(|hash| session.map.contains_key(hash))
                         }) {
                             Some(entry_hash.clone())
                         } else {
@@ -495,7 +619,7 @@ mod tests {
                 entry_hash.clone(),
                 op_h,
                 op,
-                required_actions.clone(),
+                required_actions.clone().into(),
                 expires,
             );
         }
@@ -530,7 +654,7 @@ mod tests {
         let expires = (Timestamp::now() - std::time::Duration::from_secs(60 * 60)).unwrap();
 
         // - Add it to the workspace.
-        workspace.put(entry_hash, op_hash, op, vec![action_hash], expires);
+        workspace.put(entry_hash, op_hash, op, vec![action_hash].into(), expires);
         let r = workspace.get_complete_sessions();
 
         // - Expect we have no complete sessions.
@@ -544,4 +668,80 @@ mod tests {
             })
             .unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    /// Test that sessions persisted by one workspace are resumed by
+    /// another workspace on the same database, until they are complete.
+    async fn resumes_persisted_sessions() {
+        let mut u = arbitrary::Unstructured::new(&holochain_zome_types::NOISE);
+        let db = holochain_state::prelude::test_authored_db();
+        let workspace = CountersigningWorkspace::persisted(db.to_db());
+
+        // - Create the ops for a session of 2 actions.
+        let entry_hash = EntryHash::arbitrary(&mut u).unwrap();
+        let ops: Vec<_> = (0..2)
+            .map(|_| {
+                let op_hash = DhtOpHash::arbitrary(&mut u).unwrap();
+                let op = DhtOp::arbitrary(&mut u).unwrap();
+                (op_hash, op)
+            })
+            .collect();
+        let required_actions: Vec<_> = ops
+            .iter()
+            .map(|(_, op)| ActionHash::with_data_sync(&op.action()))
+            .collect();
+        let expires = (Timestamp::now() + std::time::Duration::from_secs(60 * 60)).unwrap();
+
+        // - Persist only the first op, as if the conductor stopped
+        // before the second one arrived.
+        let (op_hash, op) = ops[0].clone();
+        workspace
+            .persist(
+                entry_hash.clone(),
+                op_hash,
+                op,
+                required_actions.clone().into(),
+                expires,
+            )
+            .await
+            .unwrap();
+
+        // - A new workspace on the same database resumes the session.
+        let workspace = CountersigningWorkspace::persisted(db.to_db());
+        workspace.resume().await.unwrap();
+        assert_eq!(workspace.get_complete_sessions().len(), 0);
+
+        // - The second op completes the session.
+        let (op_hash, op) = ops[1].clone();
+        workspace
+            .persist(
+                entry_hash.clone(),
+                op_hash.clone(),
+                op.clone(),
+                required_actions.clone().into(),
+                expires,
+            )
+            .await
+            .unwrap();
+        workspace.put(
+            entry_hash.clone(),
+            op_hash,
+            op,
+            required_actions.into(),
+            expires,
+        );
+        assert_eq!(workspace.get_complete_sessions().len(), 1);
+        workspace.forget(vec![entry_hash]).await.unwrap();
+
+        // - A complete session is not resumed again.
+        let workspace = CountersigningWorkspace::persisted(db.to_db());
+        workspace.resume().await.unwrap();
+        workspace
+            .inner
+            .share_mut(|i, _| {
+                assert_eq!(i.pending.len(), 0);
+                Ok(())
+            })
+            .unwrap();
+    }
 }
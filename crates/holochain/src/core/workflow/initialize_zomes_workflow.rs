@@ -47,6 +47,7 @@ where
     Ribosome: RibosomeT + Clone + 'static,
 {
     let conductor_handle = args.conductor_handle.clone();
+    let cell_id = args.cell_id.clone();
     let coordinators = args.ribosome.dna_def().get_all_coordinators();
     let result =
         initialize_zomes_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args)
@@ -59,6 +60,7 @@ where
         let flushed_actions = HostFnWorkspace::from(workspace.clone())
             .flush(&network)
             .await?;
+        conductor_handle.recount_authored(&cell_id);
 
         send_post_commit(
             conductor_handle,
@@ -297,6 +299,9 @@ pub mod tests {
                     functions: GrantedFunctions::Listed(
                         vec![("no-init".into(), "xxx".into())].into_iter().collect(),
                     ),
+                    expires_at: None,
+                    max_uses: None,
+                    curry_payloads: Default::default(),
                 }),
                 ChainTopOrdering::default(),
             ))?;
@@ -23,6 +23,7 @@ use holochain_types::db_cache::DhtDbQueryCache;
 use holochain_types::prelude::*;
 use holochain_zome_types::Entry;
 use holochain_zome_types::ValidationStatus;
+use rusqlite::OptionalExtension;
 use rusqlite::Transaction;
 use std::convert::TryInto;
 use std::sync::Arc;
@@ -109,15 +110,26 @@ async fn sys_validation_workflow_inner(
 
                 let dependency = get_dependency(op_type, &action);
 
-                let r = validate_op(
+                let outcome = validate_op(
                     &op,
                     &workspace,
                     cascade,
                     conductor_handle.as_ref(),
                     Some(incoming_dht_ops_sender),
                 )
-                .await;
-                r.map(|o| (op_hash, o, dependency))
+                .await?;
+                // A fork doesn't make either action invalid in itself, so both
+                // sides are held. The fork is recorded for agent activity to
+                // report it.
+                let fork = match (&outcome, &op) {
+                    (Outcome::Accepted, DhtOp::RegisterAgentActivity(_, action)) => {
+                        check_chain_rollback(action, &workspace)
+                            .await?
+                            .map(|fork| (action.author().clone(), fork))
+                    }
+                    _ => None,
+                };
+                WorkflowResult::Ok((op_hash, outcome, dependency, fork))
             }
         }
     });
@@ -162,7 +174,10 @@ async fn sys_validation_workflow_inner(
                 let mut missing = 0;
                 let mut rejected = 0;
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, outcome, dependency) = outcome?;
+                    let (op_hash, outcome, dependency, fork) = outcome?;
+                    if let Some((author, fork)) = fork {
+                        holochain_state::chain_fork::insert_chain_fork(txn, &author, &fork)?;
+                    }
                     match outcome {
                         Outcome::Accepted => {
                             total += 1;
@@ -242,15 +257,21 @@ async fn validate_op(
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> WorkflowResult<Outcome> {
     let dna_def = DnaDefHashed::from_content_sync((*workspace.dna_def()).clone());
-    match validate_op_inner(
-        op,
-        &cascade,
-        dna_def,
-        conductor_handle,
-        incoming_dht_ops_sender,
-    )
-    .await
-    {
+    let spam_policy = workspace.spam_policy();
+    let result = match check_spam(op, spam_policy, workspace).await {
+        Ok(()) => {
+            validate_op_inner(
+                op,
+                &cascade,
+                dna_def,
+                conductor_handle,
+                incoming_dht_ops_sender,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    match result {
         Ok(_) => Ok(Outcome::Accepted),
         // Handle the errors that result in pending or awaiting deps
         Err(SysValidationError::ValidationOutcome(e)) => {
@@ -288,6 +309,7 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         ValidationOutcome::EntryType => Rejected,
         ValidationOutcome::EntryVisibility(_) => Rejected,
         ValidationOutcome::TagTooLarge(_, _) => Rejected,
+        ValidationOutcome::TooManyActions(_, _, _) => Rejected,
         ValidationOutcome::NotCreateLink(_) => Rejected,
         ValidationOutcome::NotNewEntry(_) => Rejected,
         ValidationOutcome::NotHoldingDep(dep) => AwaitingOpDep(dep),
@@ -548,8 +570,6 @@ async fn register_agent_activity(
         )
         .await?;
     }
-    // not appropriate for sys validation
-    // check_chain_rollback(action, workspace).await?;
     Ok(())
 }
 
@@ -745,6 +765,7 @@ pub struct SysValidationWorkspace {
     dht_query_cache: Option<DhtDbQueryCache>,
     cache: DbWrite<DbKindCache>,
     pub(crate) dna_def: Arc<DnaDef>,
+    spam_policy: once_cell::sync::OnceCell<SpamPolicy>,
 }
 
 impl SysValidationWorkspace {
@@ -762,9 +783,25 @@ impl SysValidationWorkspace {
             cache,
             dna_def,
             scratch: None,
+            spam_policy: Default::default(),
         }
     }
 
+    /// The DNA's [`SpamPolicy`], read from its properties the first time
+    /// it's needed. DNAs with a policy which can't be read are refused when
+    /// registered, so falling back to the defaults should never happen.
+    pub fn spam_policy(&self) -> &SpamPolicy {
+        self.spam_policy.get_or_init(|| {
+            SpamPolicy::from_modifiers(&self.dna_def.modifiers).unwrap_or_else(|e| {
+                tracing::warn!(
+                    ?e,
+                    "The DNA's spam policy could not be read, using the defaults"
+                );
+                SpamPolicy::default()
+            })
+        })
+    }
+
     pub async fn is_chain_empty(&self, author: &AgentPubKey) -> SourceChainResult<bool> {
         // If we have a query cache then this is an authority node and
         // we can quickly check if the chain is empty from the cache.
@@ -813,52 +850,127 @@ impl SysValidationWorkspace {
         Ok(!chain_not_empty)
     }
 
-    pub async fn action_seq_is_empty(&self, action: &Action) -> SourceChainResult<bool> {
+    /// Find another action by the same author at the same sequence number
+    /// as this action, if this authority holds one.
+    pub async fn other_action_at_seq(
+        &self,
+        action: &Action,
+    ) -> SourceChainResult<Option<ActionHash>> {
         let author = action.author().clone();
         let seq = action.action_seq();
         let hash = ActionHash::with_data_sync(action);
-        let action_seq_is_not_empty = self
+        let other = self
             .dht_db
             .async_reader({
                 let hash = hash.clone();
                 move |txn| {
-                    DatabaseResult::Ok(txn.query_row(
-                        "
-                SELECT EXISTS(
-                    SELECT
-                    1
-                    FROM Action
-                    WHERE
-                    Action.author = :author
-                    AND
-                    Action.seq = :seq
-                    AND
-                    Action.hash != :hash
-                    LIMIT 1
-                )
+                    DatabaseResult::Ok(
+                        txn.query_row(
+                            "
+                SELECT
+                Action.hash
+                FROM Action
+                JOIN
+                DhtOp ON Action.hash = DhtOp.action_hash
+                WHERE
+                Action.author = :author
+                AND
+                Action.seq = :seq
+                AND
+                Action.hash != :hash
+                AND
+                DhtOp.type = :activity
+                LIMIT 1
                 ",
-                        named_params! {
-                            ":author": author,
-                            ":seq": seq,
-                            ":hash": hash,
-                        },
-                        |row| row.get(0),
-                    )?)
+                            named_params! {
+                                ":author": author,
+                                ":seq": seq,
+                                ":hash": hash,
+                                ":activity": DhtOpType::RegisterAgentActivity,
+                            },
+                            |row| row.get(0),
+                        )
+                        .optional()?,
+                    )
                 }
             })
             .await?;
-        let action_seq_is_not_empty = match &self.scratch {
-            Some(scratch) => {
-                scratch.apply(|scratch| {
-                    scratch.actions().any(|shh| {
-                        shh.action().action_seq() == seq && *shh.action_address() != hash
-                    })
-                })? || action_seq_is_not_empty
-            }
-            None => action_seq_is_not_empty,
-        };
-        Ok(!action_seq_is_not_empty)
+        match (other, &self.scratch) {
+            (None, Some(scratch)) => Ok(scratch.apply(|scratch| {
+                scratch
+                    .actions()
+                    .find(|shh| shh.action().action_seq() == seq && *shh.action_address() != hash)
+                    .map(|shh| shh.action_address().clone())
+            })?),
+            (other, _) => Ok(other),
+        }
+    }
+    /// Count the actions in the validated chain of this action's author which
+    /// come before it and were authored at or after `start`, by walking back
+    /// along `prev_action`. Stops counting once `limit` is reached.
+    ///
+    /// If an action in that part of the chain isn't held and validated yet,
+    /// the count can't be known and it is returned as a missing dependency.
+    pub async fn count_actions_since(
+        &self,
+        action: &Action,
+        start: Timestamp,
+        limit: usize,
+    ) -> SysValidationResult<usize> {
+        let author = action.author().clone();
+        let prev_action = action.prev_action().cloned();
+        self.dht_db
+            .async_reader(move |txn| {
+                let mut count = 0;
+                let mut next = prev_action;
+                while count < limit {
+                    let hash = match next {
+                        Some(hash) => hash,
+                        None => break,
+                    };
+                    let prev: Option<(Option<ActionHash>, Timestamp)> = txn
+                        .query_row(
+                            "
+                            SELECT
+                            Action.prev_hash, DhtOp.authored_timestamp
+                            FROM Action
+                            JOIN
+                            DhtOp ON Action.hash = DhtOp.action_hash
+                            WHERE
+                            Action.hash = :hash
+                            AND
+                            Action.author = :author
+                            AND
+                            DhtOp.type = :activity
+                            AND
+                            DhtOp.validation_status = :valid
+                            ",
+                            named_params! {
+                                ":hash": hash,
+                                ":author": author,
+                                ":activity": DhtOpType::RegisterAgentActivity,
+                                ":valid": ValidationStatus::Valid,
+                            },
+                            |row| Ok((row.get(0)?, row.get(1)?)),
+                        )
+                        .optional()
+                        .map_err(DatabaseError::from)?;
+                    match prev {
+                        Some((prev_hash, timestamp)) => {
+                            if timestamp < start {
+                                break;
+                            }
+                            count += 1;
+                            next = prev_hash;
+                        }
+                        None => return Err(ValidationOutcome::not_holding(&hash).into()),
+                    }
+                }
+                Ok(count)
+            })
+            .await
     }
+
     /// Create a cascade with local data only
     pub fn local_cascade(&self) -> Cascade {
         let cascade = Cascade::empty().with_dht(self.dht_db.clone());
@@ -946,6 +1058,7 @@ impl From<&HostFnWorkspace> for SysValidationWorkspace {
             dht_query_cache: None,
             cache,
             dna_def: h.dna_def(),
+            spam_policy: Default::default(),
         }
     }
 }
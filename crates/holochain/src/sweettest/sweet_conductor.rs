@@ -19,6 +19,8 @@ use holochain_state::prelude::test_db_dir;
 use holochain_state::test_utils::TestDir;
 use holochain_types::prelude::*;
 use holochain_websocket::*;
+use kitsune_p2p::dependencies::kitsune_p2p_proxy::ProxyUrl;
+use kitsune_p2p_types::Tx2Cert;
 use rand::Rng;
 use std::path::Path;
 use std::sync::Arc;
@@ -516,6 +518,19 @@ impl SweetConductor {
         }
     }
 
+    /// Get the cert which identifies this conductor on the network,
+    /// read from the agent info of its running cells.
+    /// `None` until one of its cells has joined the network.
+    pub async fn node_cert(&self) -> Option<Tx2Cert> {
+        for cell_id in self.running_cell_ids(None) {
+            let infos = self.get_agent_infos(Some(cell_id)).await.ok()?;
+            if let Some(url) = infos.iter().flat_map(|info| info.url_list.iter()).next() {
+                return Some(ProxyUrl::from(url.as_str()).digest().into());
+            }
+        }
+        None
+    }
+
     /// Let each conductor know about each others' agents so they can do networking
     pub async fn exchange_peer_info(conductors: impl IntoIterator<Item = &Self>) {
         let mut all = Vec::new();
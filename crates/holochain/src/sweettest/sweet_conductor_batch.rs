@@ -4,6 +4,9 @@ use ::fixt::prelude::StdRng;
 use futures::future;
 use hdk::prelude::*;
 use holochain_types::prelude::*;
+use kitsune_p2p_types::tx2::{mem_update_link_fault, MemLinkFault};
+use kitsune_p2p_types::Tx2Cert;
+use std::time::Duration;
 
 /// A collection of SweetConductors, with methods for operating on the entire collection
 #[derive(derive_more::From, derive_more::Into, derive_more::IntoIterator)]
@@ -178,6 +181,77 @@ impl SweetConductorBatch {
             c.force_all_publish_dht_ops().await;
         }
     }
+
+    /// Split the batch into groups of conductors which can't reach each other,
+    /// by index. Conductors which aren't in any group can still reach everyone.
+    ///
+    /// Network faults can only be injected between conductors using the
+    /// in-memory transport, see [`SweetConductorConfig::mem_transport`].
+    pub async fn partition(&self, groups: &[&[usize]]) {
+        let certs = self.node_certs().await;
+        for (i, a) in groups.iter().enumerate() {
+            for b in groups.iter().skip(i + 1) {
+                for &x in a.iter() {
+                    for &y in b.iter() {
+                        update_link(&certs, x, y, |f| f.partitioned = true);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove all partitions between the conductors in this batch.
+    /// Any latency or drop rate on the links is kept.
+    pub async fn heal_partitions(&self) {
+        let certs = self.node_certs().await;
+        for x in 0..certs.len() {
+            for y in (x + 1)..certs.len() {
+                update_link(&certs, x, y, |f| f.partitioned = false);
+            }
+        }
+    }
+
+    /// Delay everything sent between two conductors, in both directions.
+    pub async fn set_link_latency(&self, a: usize, b: usize, latency: Option<Duration>) {
+        let certs = self.node_certs().await;
+        update_link(&certs, a, b, |f| f.latency = latency);
+    }
+
+    /// Drop a fraction, from 0.0 to 1.0, of the frames
+    /// sent between two conductors, in both directions.
+    pub async fn set_link_drop_rate(&self, a: usize, b: usize, drop_rate: f64) {
+        let certs = self.node_certs().await;
+        update_link(&certs, a, b, |f| f.drop_rate = drop_rate);
+    }
+
+    /// Remove all network faults between the conductors in this batch.
+    pub async fn clear_network_faults(&self) {
+        let certs = self.node_certs().await;
+        for x in 0..certs.len() {
+            for y in (x + 1)..certs.len() {
+                update_link(&certs, x, y, |f| *f = MemLinkFault::default());
+            }
+        }
+    }
+
+    /// The network certs of all conductors in this batch, waiting for each
+    /// to have joined the network with at least one cell.
+    async fn node_certs(&self) -> Vec<Tx2Cert> {
+        let mut certs = Vec::with_capacity(self.0.len());
+        for (i, c) in self.0.iter().enumerate() {
+            let mut cert = None;
+            for _ in 0..100 {
+                cert = c.node_cert().await;
+                if cert.is_some() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            certs
+                .push(cert.unwrap_or_else(|| panic!("conductor {} has not joined the network", i)));
+        }
+        certs
+    }
 }
 
 impl std::ops::Index<usize> for SweetConductorBatch {
@@ -193,3 +267,9 @@ impl std::ops::IndexMut<usize> for SweetConductorBatch {
         &mut self.0[index]
     }
 }
+
+/// Update the faults on the link between two conductors, in both directions.
+fn update_link(certs: &[Tx2Cert], a: usize, b: usize, f: impl Fn(&mut MemLinkFault)) {
+    mem_update_link_fault(certs[a].clone(), certs[b].clone(), &f);
+    mem_update_link_fault(certs[b].clone(), certs[a].clone(), &f);
+}
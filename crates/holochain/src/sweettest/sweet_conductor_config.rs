@@ -160,4 +160,14 @@ impl SweetConductorConfig {
         }
         self
     }
+
+    /// Use the in-memory transport, which allows faults to be injected
+    /// on the network between conductors running in this process.
+    /// See [`SweetConductorBatch::partition`](super::SweetConductorBatch::partition).
+    pub fn mem_transport(mut self) -> Self {
+        if let Some(c) = self.0.network.as_mut() {
+            c.transport_pool = vec![kitsune_p2p::TransportConfig::Mem {}];
+        }
+        self
+    }
 }
@@ -1,5 +1,8 @@
 use super::SweetZome;
-use crate::conductor::{api::error::ConductorApiResult, ConductorHandle};
+use crate::conductor::api::error::{
+    zome_call_response_to_conductor_api_result, ConductorApiError, ConductorApiResult,
+};
+use crate::conductor::ConductorHandle;
 use holochain_types::prelude::*;
 
 /// A wrapper around ConductorHandle with more convenient methods for testing
@@ -80,6 +83,25 @@ impl SweetConductorHandle {
             .await
     }
 
+    /// Switch on recording of the zome calls made to a cell.
+    pub fn record_zome_calls(&self, cell_id: &CellId) {
+        self.0.set_zome_call_recording(cell_id.clone(), true)
+    }
+
+    /// Re-execute a recorded zome call against the recorded results of its
+    /// host function calls, and return its output.
+    /// The replay fails if the zome makes different host function calls
+    /// than it did when it was recorded.
+    pub async fn replay<O>(&self, recording: ZomeCallRecording) -> ConductorApiResult<O>
+    where
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        match self.0.replay_zome_call(recording).await? {
+            Ok(response) => zome_call_response_to_conductor_api_result(response),
+            Err(error) => Err(ConductorApiError::Other(Box::new(error))),
+        }
+    }
+
     /// Get a stream of all Signals emitted since the time of this function call.
     pub async fn signal_stream(&self) -> impl tokio_stream::Stream<Item = Signal> {
         self.0.signal_broadcaster().subscribe_merged()
@@ -0,0 +1,78 @@
+#![cfg(feature = "test_utils")]
+
+use hdk::prelude::*;
+use holochain::sweettest::{SweetConductor, SweetDnaFile, SweetInlineZomes};
+use holochain::test_utils::inline_zomes::simple_crud_zome;
+use holochain::test_utils::{consistency_10s, wait_for_integration_1m};
+use holochain_state::prelude::fresh_reader_test;
+use holochain_types::db::{DbKindDht, DbWrite};
+use holochain_types::prelude::SignedDhtOpExport;
+
+fn integrated_count(db: &DbWrite<DbKindDht>) -> usize {
+    fresh_reader_test(db.clone(), |txn| {
+        txn.query_row(
+            "SELECT COUNT(hash) FROM DhtOp WHERE when_integrated IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that ops exported from one conductor can be imported into another,
+/// with no network connection between them.
+async fn export_and_import_dht_ops() {
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let dna_hash = dna_file.dna_hash().clone();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("ops.export");
+
+    let mut conductor_a = SweetConductor::from_standard_config().await;
+    let (alice,) = conductor_a
+        .setup_app("app", &[dna_file.clone()])
+        .await
+        .unwrap()
+        .into_tuple();
+    let _: ActionHash = conductor_a
+        .call(
+            &alice.zome(SweetInlineZomes::COORDINATOR),
+            "create_unit",
+            (),
+        )
+        .await;
+    consistency_10s([&alice]).await;
+
+    let mut conductor_b = SweetConductor::from_standard_config().await;
+    let (bob,) = conductor_b
+        .setup_app("app", &[dna_file.clone()])
+        .await
+        .unwrap()
+        .into_tuple();
+    consistency_10s([&bob]).await;
+    let bob_count = integrated_count(bob.dht_db());
+
+    let exported = conductor_a
+        .export_dht_ops(
+            dna_hash.clone(),
+            alice.agent_pubkey().clone(),
+            path.clone(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(exported, integrated_count(alice.dht_db()));
+
+    let (imported_dna, signer, imported) = conductor_b.import_dht_ops(path.clone()).await.unwrap();
+    assert_eq!(imported_dna, dna_hash);
+    assert_eq!(&signer, alice.agent_pubkey());
+    assert_eq!(imported, exported);
+    wait_for_integration_1m(bob.dht_db(), bob_count + exported).await;
+
+    // A file with a bad signature is rejected
+    let content = std::fs::read(&path).unwrap();
+    let mut signed = SignedDhtOpExport::from_file_content(&content).unwrap();
+    signed.signer = bob.agent_pubkey().clone();
+    std::fs::write(&path, signed.to_file_content().unwrap()).unwrap();
+    assert!(conductor_b.import_dht_ops(path).await.is_err());
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "test_utils")]
+
+use hdk::prelude::*;
+use holochain::sweettest::{SweetConductor, SweetDnaFile, SweetInlineZomes};
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that introspecting a cell lists what each of its zomes exposes.
+async fn introspect_cell_zomes() {
+    let zomes = SweetInlineZomes::new(vec![EntryDef::from_id("post")], 2)
+        .function("create_post", |_api, ()| Ok(()))
+        .function("init", |_api, ()| Ok(InitCallbackResult::Pass));
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes.0).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice,) = conductor
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+
+    let introspection = conductor
+        .introspect_cell(alice.cell_id().clone())
+        .await
+        .unwrap();
+    assert_eq!(introspection.cell_id, *alice.cell_id());
+
+    let integrity = &introspection.zomes[0];
+    assert_eq!(integrity.zome_name, SweetInlineZomes::INTEGRITY.into());
+    assert!(integrity.integrity);
+    assert_eq!(integrity.callbacks, vec!["entry_defs".into()]);
+    assert_eq!(
+        integrity
+            .entry_defs
+            .iter()
+            .map(|def| (def.id.clone(), def.visibility))
+            .collect::<Vec<_>>(),
+        vec![("post".into(), EntryVisibility::Public)]
+    );
+    assert_eq!(integrity.link_types, vec![LinkType(0), LinkType(1)]);
+
+    let coordinator = &introspection.zomes[1];
+    assert_eq!(coordinator.zome_name, SweetInlineZomes::COORDINATOR.into());
+    assert!(!coordinator.integrity);
+    assert_eq!(coordinator.zome_fns, vec!["create_post".into()]);
+    assert_eq!(coordinator.callbacks, vec!["init".into()]);
+    assert!(coordinator.scheduled_fns.is_empty());
+    assert!(coordinator.entry_defs.is_empty());
+
+    // An app can only introspect its own cells.
+    assert!(conductor
+        .introspect_app_cell(&"other app".to_string(), alice.cell_id().clone())
+        .await
+        .is_err());
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "test_utils")]
+
+use hdk::prelude::*;
+use holochain::sweettest::{
+    SweetConductorBatch, SweetConductorConfig, SweetDnaFile, SweetInlineZomes,
+};
+use holochain::test_utils::consistency_10s;
+use holochain::test_utils::inline_zomes::simple_crud_zome;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that data doesn't cross a partition, and is synced once it heals.
+async fn partitioned_conductors_sync_after_healing() {
+    holochain_trace::test_run().ok();
+    let config = SweetConductorConfig::standard().mem_transport();
+    let mut conductors = SweetConductorBatch::from_config(2, config).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    let ((alice,), (bob,)) = apps.into_tuples();
+    conductors.exchange_peer_info().await;
+
+    conductors.partition(&[&[0], &[1]]).await;
+    let hash: ActionHash = conductors[0]
+        .call(
+            &alice.zome(SweetInlineZomes::COORDINATOR),
+            "create_unit",
+            (),
+        )
+        .await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let record: Option<Record> = conductors[1]
+        .call(
+            &bob.zome(SweetInlineZomes::COORDINATOR),
+            "read",
+            hash.clone(),
+        )
+        .await;
+    assert!(record.is_none());
+
+    conductors.heal_partitions().await;
+    consistency_10s([&alice, &bob]).await;
+    let record: Option<Record> = conductors[1]
+        .call(&bob.zome(SweetInlineZomes::COORDINATOR), "read", hash)
+        .await;
+    assert!(record.is_some());
+}
@@ -511,6 +511,7 @@ async fn run_fixt_network(
                             updates: btreeset! {},
                             invalid_actions: btreeset! {},
                             entry_dht_status: None,
+                            ..Default::default()
                         };
                         respond.respond(Ok(async move { Ok(metadata.try_into().unwrap()) }
                             .boxed()
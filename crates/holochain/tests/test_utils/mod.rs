@@ -108,6 +108,9 @@ pub async fn grant_zome_call_capability(
                 assignees,
             },
             functions,
+            expires_at: None,
+            max_uses: None,
+            curry_payloads: Default::default(),
         },
     }));
     let response = admin_tx.request(request);
@@ -192,7 +195,7 @@ pub async fn retry_admin_interface(
 }
 
 pub async fn generate_agent_pubkey(client: &mut WebsocketSender, timeout: u64) -> AgentPubKey {
-    let request = AdminRequest::GenerateAgentPubKey;
+    let request = AdminRequest::GenerateAgentPubKey(None);
     let response = client.request(request);
     let response = check_timeout_named("GenerateAgentPubkey", response, timeout).await;
 
@@ -276,6 +279,7 @@ pub async fn register_and_install_dna_named(
         source: AppBundleSource::Bundle(bundle),
         installed_app_id: Some(name),
         network_seed: None,
+        quotas: Default::default(),
         membrane_proofs: std::collections::HashMap::new(),
     };
     let request = AdminRequest::InstallApp(Box::new(payload));
@@ -293,6 +293,7 @@ async fn remote_signals() -> anyhow::Result<()> {
             RemoteSignal {
                 signal: signal.clone(),
                 agents: all_agents,
+                reliable: false,
             },
         )
         .await;
@@ -0,0 +1,89 @@
+#![cfg(feature = "test_utils")]
+
+use hdk::prelude::*;
+use holochain::sweettest::{SweetConductor, SweetDnaFile, SweetInlineZomes};
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that a recorded zome call replays to the same output, even though
+/// it depends on host functions which return something different each time.
+async fn record_and_replay_zome_call() {
+    let zome = SweetInlineZomes::new(vec![], 0).function("roll", |api, ()| {
+        let bytes = api.random_bytes(8)?;
+        let time = api.sys_time(())?;
+        Ok((bytes, time))
+    });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zome.0).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice,) = conductor
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    let zome = alice.zome(SweetInlineZomes::COORDINATOR);
+
+    // Nothing is recorded until recording is switched on.
+    let _: (Bytes, Timestamp) = conductor.call(&zome, "roll", ()).await;
+    assert!(conductor.zome_call_recordings(alice.cell_id()).is_empty());
+
+    conductor.record_zome_calls(alice.cell_id());
+    let rolled: (Bytes, Timestamp) = conductor.call(&zome, "roll", ()).await;
+
+    let recordings = conductor.zome_call_recordings(alice.cell_id());
+    assert_eq!(recordings.len(), 1);
+    let recording = recordings[0].clone();
+    assert_eq!(recording.call.fn_name, "roll".into());
+    assert_eq!(
+        recording
+            .host_calls
+            .iter()
+            .map(|c| c.host_fn.as_str())
+            .collect::<Vec<_>>(),
+        vec!["random_bytes", "sys_time"]
+    );
+
+    // The replay gets the recorded random bytes and time back.
+    let replayed: (Bytes, Timestamp) = conductor.replay(recording.clone()).await.unwrap();
+    assert_eq!(replayed, rolled);
+
+    // A replay which runs out of recorded host calls diverges.
+    let mut truncated = recording;
+    truncated.host_calls.pop();
+    assert!(conductor
+        .replay::<(Bytes, Timestamp)>(truncated)
+        .await
+        .is_err());
+
+    conductor.set_zome_call_recording(alice.cell_id().clone(), false);
+    assert!(conductor.zome_call_recordings(alice.cell_id()).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+/// Test that a zome call which fails outside of the zome's own code is
+/// still recorded, along with the error it failed with.
+async fn record_failed_zome_call() {
+    let zome = SweetInlineZomes::new(vec![], 0).function("crash", |_api, ()| {
+        if true {
+            panic!("the zome call crashed");
+        }
+        Ok(())
+    });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zome.0).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (alice,) = conductor
+        .setup_app("app", &[dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    let zome = alice.zome(SweetInlineZomes::COORDINATOR);
+
+    conductor.record_zome_calls(alice.cell_id());
+    assert!(conductor
+        .call_fallible::<_, (), _>(&zome, "crash", ())
+        .await
+        .is_err());
+
+    let recordings = conductor.zome_call_recordings(alice.cell_id());
+    assert_eq!(recordings.len(), 1);
+    assert_eq!(recordings[0].call.fn_name, "crash".into());
+    assert!(recordings[0].response.is_err());
+}
@@ -4,6 +4,7 @@ use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_agent_activity_query::must_get_agent_activity::must_get_agent_activity;
 use self::get_entry_ops_query::GetEntryOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
+use self::get_meta_query::GetMetaQuery;
 use self::{
     get_agent_activity_query::deterministic::DeterministicGetAgentActivityQuery,
     get_record_query::GetRecordOpsQuery,
@@ -12,7 +13,9 @@ use self::{
 use super::error::CascadeResult;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holochain_state::chain_fork::get_chain_fork;
 use holochain_state::query::Query;
+use holochain_state::query::StateQueryResult;
 use holochain_state::query::Txn;
 use holochain_types::prelude::*;
 use holochain_zome_types::agent_activity::DeterministicGetAgentActivityFilter;
@@ -24,6 +27,7 @@ mod test;
 pub(crate) mod get_agent_activity_query;
 pub(crate) mod get_entry_ops_query;
 pub(crate) mod get_links_ops_query;
+pub(crate) mod get_meta_query;
 pub(crate) mod get_record_query;
 
 /// Handler for get_entry query to an Entry authority
@@ -40,6 +44,20 @@ pub async fn handle_get_entry(
     Ok(results)
 }
 
+/// Handler for get_meta query to any authority
+#[instrument(skip(db, _options))]
+pub async fn handle_get_meta(
+    db: DbRead<DbKindDht>,
+    hash: AnyDhtHash,
+    _options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let query = GetMetaQuery::new(hash);
+    let results = db
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
+
 /// Handler for get_record query to a Record authority
 #[tracing::instrument(skip(env))]
 pub async fn handle_get_record(
@@ -62,9 +80,24 @@ pub async fn handle_get_agent_activity(
     query: ChainQueryFilter,
     options: holochain_p2p::event::GetActivityOptions,
 ) -> CascadeResult<AgentActivityResponse<ActionHash>> {
-    let query = GetAgentActivityQuery::new(agent, query, options);
+    let query = GetAgentActivityQuery::new(agent.clone(), query, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut activity = query.run(Txn::from(&txn))?;
+            // A fork found by sys validation is reported even when this
+            // authority doesn't hold both sides of it as valid.
+            if let Some(fork) = get_chain_fork(&txn, &agent)? {
+                let earlier = match &activity.status {
+                    ChainStatus::Forked(found) => fork.fork_seq < found.fork_seq,
+                    ChainStatus::Invalid(head) => fork.fork_seq < head.action_seq,
+                    ChainStatus::Valid(_) | ChainStatus::Empty => true,
+                };
+                if earlier {
+                    activity.status = ChainStatus::Forked(fork);
+                }
+            }
+            StateQueryResult::Ok(activity)
+        })
         .await?;
     Ok(results)
 }
@@ -82,7 +82,10 @@ async fn returns_full_sequence_from_filter(
     => MustGetAgentActivityResponse::ChainTopNotFound(action_hash(&[15])) ; "Starting chain_top not found")]
 #[test_case(
     vec![(agent_hash(&[0]), forked_chain(&[0..6, 3..8]))], agent_hash(&[0]), ChainFilter::new(action_hash(&[7, 1])).take(7)
-    => matches MustGetAgentActivityResponse::Activity(a) if a.len() == 7 ; "Handles forks")]
+    => matches MustGetAgentActivityResponse::Forked(f) if f.fork_seq == 3 ; "Detects forks")]
+#[test_case(
+    vec![(agent_hash(&[0]), forked_chain(&[0..6, 3..8]))], agent_hash(&[0]), ChainFilter::new(action_hash(&[7, 1])).take(2)
+    => matches MustGetAgentActivityResponse::Activity(a) if a.len() == 2 ; "Ignores forks outside the range")]
 #[test_case(
     agent_chain(&[(0, 0..5)]), agent_hash(&[0]), ChainFilter::new(action_hash(&[4])).until(action_hash(&[2, 1]))
     => matches MustGetAgentActivityResponse::Activity(_) ; "Until hash not found")]
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use holo_hash::ActionHash;
+use holo_hash::AnyDhtHash;
+use holo_hash::HasHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::prelude::*;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::metadata::merge_validation_status;
+use holochain_types::metadata::EntryDhtStatus;
+use holochain_types::metadata::MetadataSet;
+use holochain_types::metadata::TimedActionHash;
+use holochain_zome_types::Action;
+use holochain_zome_types::ActionHashed;
+use holochain_zome_types::HasValidationStatus;
+use holochain_zome_types::Judged;
+use holochain_zome_types::SignedAction;
+use holochain_zome_types::ValidationStatus;
+
+#[derive(Debug, Clone)]
+pub struct GetMetaQuery(Arc<AnyDhtHash>);
+
+impl GetMetaQuery {
+    pub fn new(hash: AnyDhtHash) -> Self {
+        Self(Arc::new(hash))
+    }
+}
+
+pub struct Item {
+    action: ActionHashed,
+    op_type: DhtOpType,
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    meta: MetadataSet,
+    is_entry: bool,
+    deleted_actions: HashSet<ActionHash>,
+    links: HashSet<ActionHash>,
+    deleted_links: HashSet<ActionHash>,
+}
+
+impl Query for GetMetaQuery {
+    type Item = Judged<Item>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        "
+        SELECT Action.blob AS action_blob, DhtOp.type AS dht_type,
+        DhtOp.validation_status AS status
+        FROM DhtOp
+        JOIN Action On DhtOp.action_hash = Action.hash
+        WHERE DhtOp.basis_hash = :basis_hash
+        AND
+        DhtOp.when_integrated IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        named_params! {
+            ":basis_hash": self.0,
+        }
+        .to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let SignedAction(action, _) =
+                from_blob::<SignedAction>(row.get(row.as_ref().column_index("action_blob")?)?)?;
+            let op_type = row.get(row.as_ref().column_index("dht_type")?)?;
+            let validation_status = row.get(row.as_ref().column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    action: ActionHashed::from_content_sync(action),
+                    op_type,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(State::default())
+    }
+
+    fn fold(&self, mut state: Self::State, dht_op: Self::Item) -> StateQueryResult<Self::State> {
+        let validation_status = dht_op.validation_status();
+        let Item { action, op_type } = dht_op.data;
+        let valid = matches!(validation_status, Some(ValidationStatus::Valid));
+        let meta = &mut state.meta;
+        match op_type {
+            DhtOpType::StoreRecord | DhtOpType::StoreEntry => {
+                state.is_entry |= op_type == DhtOpType::StoreEntry;
+                meta.validation_status =
+                    merge_validation_status(meta.validation_status, validation_status);
+                meta.authors
+                    .insert(action.as_hash().clone(), action.author().clone());
+                match validation_status {
+                    Some(ValidationStatus::Valid) => {
+                        meta.actions.insert(action.into());
+                    }
+                    Some(ValidationStatus::Rejected) => {
+                        meta.invalid_actions.insert(action.into());
+                    }
+                    _ => (),
+                }
+            }
+            DhtOpType::RegisterUpdatedContent | DhtOpType::RegisterUpdatedRecord if valid => {
+                meta.authors
+                    .insert(action.as_hash().clone(), action.author().clone());
+                meta.updates.insert(action.into());
+            }
+            DhtOpType::RegisterDeletedBy | DhtOpType::RegisterDeletedEntryAction if valid => {
+                if let Action::Delete(delete) = action.as_content() {
+                    state.deleted_actions.insert(delete.deletes_address.clone());
+                }
+                meta.authors
+                    .insert(action.as_hash().clone(), action.author().clone());
+                meta.deletes.insert(action.into());
+            }
+            DhtOpType::RegisterAddLink if valid => {
+                state.links.insert(action.as_hash().clone());
+            }
+            DhtOpType::RegisterRemoveLink if valid => {
+                if let Action::DeleteLink(delete) = action.as_content() {
+                    state.deleted_links.insert(delete.link_add_address.clone());
+                }
+            }
+            // Activity ops and ops that are still pending or
+            // rejected don't contribute to the metadata.
+            _ => (),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let State {
+            mut meta,
+            is_entry,
+            deleted_actions,
+            links,
+            deleted_links,
+        } = state;
+        meta.link_count = links.difference(&deleted_links).count();
+        if is_entry {
+            let is_live = meta
+                .actions
+                .iter()
+                .any(|TimedActionHash { action_hash, .. }| !deleted_actions.contains(action_hash));
+            meta.entry_dht_status = Some(if is_live {
+                EntryDhtStatus::Live
+            } else {
+                EntryDhtStatus::Dead
+            });
+        }
+        Ok(meta)
+    }
+}
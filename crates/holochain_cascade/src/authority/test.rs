@@ -62,6 +62,59 @@ async fn get_entry() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta() {
+    holochain_trace::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+    let get_meta = || {
+        handle_get_meta(
+            db.to_db().into(),
+            td.hash.clone().into(),
+            holochain_p2p::event::GetMetaOptions {},
+        )
+    };
+
+    let result = get_meta().await.unwrap();
+    assert!(result.is_empty());
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.create_link_op.clone());
+
+    let result = get_meta().await.unwrap();
+    assert_eq!(result.validation_status, Some(ValidationStatus::Valid));
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Live));
+    assert_eq!(
+        result
+            .actions
+            .iter()
+            .map(|t| t.action_hash.clone())
+            .collect::<Vec<_>>(),
+        vec![td.create_hash.clone()]
+    );
+    assert!(result.authors.contains_key(&td.create_hash));
+    assert_eq!(result.link_count, 1);
+
+    fill_db(&db.to_db(), td.delete_entry_action_op.clone());
+    fill_db(&db.to_db(), td.update_content_op.clone());
+    fill_db(&db.to_db(), td.delete_link_op.clone());
+
+    let result = get_meta().await.unwrap();
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Dead));
+    assert_eq!(result.deletes.len(), 1);
+    assert_eq!(result.updates.len(), 1);
+    assert_eq!(result.link_count, 0);
+
+    let merged = MetadataSet::merge(vec![result.clone(), MetadataSet::default(), result]).unwrap();
+    assert_eq!(merged.holders, 2);
+    assert_eq!(merged.actions.len(), 1);
+    assert_eq!(
+        merged.actions[0].author,
+        Some(td.store_entry_op.action().author().clone())
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_record() {
     holochain_trace::test_run().ok();
@@ -243,3 +296,37 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_reports_recorded_fork() {
+    holochain_trace::test_run().ok();
+    let db = test_dht_db();
+
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&db.to_db(), hash_op);
+    }
+    let fork = ChainFork {
+        fork_seq: td.chain_head.action_seq,
+        first_action: td.chain_head.hash.clone(),
+        second_action: ActionHash::from_raw_36(vec![1; 36]),
+    };
+    db.to_db().test_commit({
+        let agent = td.agent.clone();
+        let fork = fork.clone();
+        move |txn| {
+            holochain_state::chain_fork::insert_chain_fork(txn, &agent, &fork).unwrap();
+        }
+    });
+
+    let result = handle_get_agent_activity(
+        db.to_db().into(),
+        td.agent.clone(),
+        QueryFilter::new(),
+        (&actor::GetActivityOptions::default()).into(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.status, ChainStatus::Forked(fork));
+}
@@ -32,6 +32,7 @@ use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
 use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::actor::GetLinksOptions;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
@@ -316,7 +317,7 @@ where
     /// Add new activity to the Cache.
     async fn add_activity_into_cache(
         &self,
-        responses: Vec<MustGetAgentActivityResponse>,
+        mut responses: Vec<MustGetAgentActivityResponse>,
     ) -> CascadeResult<MustGetAgentActivityResponse> {
         // Choose a response from all the responses.
         let response = if responses
@@ -331,10 +332,18 @@ where
                 "Got different must_get_agent_activity responses from different authorities"
             );
             // TODO: Handle conflict.
-            // For now try to find one that has got the activity.
+            // For now prefer any authority that has seen a fork, as another
+            // authority may simply not have received both sides of it yet.
+            // Otherwise try to find one that has got the activity.
             responses
-                .into_iter()
-                .find(|a| matches!(a, MustGetAgentActivityResponse::Activity(_)))
+                .iter()
+                .position(|a| matches!(a, MustGetAgentActivityResponse::Forked(_)))
+                .or_else(|| {
+                    responses
+                        .iter()
+                        .position(|a| matches!(a, MustGetAgentActivityResponse::Activity(_)))
+                })
+                .map(|i| responses.swap_remove(i))
         };
 
         let cache = some_or_return!(
@@ -845,6 +854,42 @@ where
         }
     }
 
+    /// Get the metadata the DHT holds for any hash.
+    ///
+    /// The authorities are asked and their answers merged. If we are an
+    /// authority for the hash our own view is merged in as well, so that
+    /// `holders` counts every authority which holds something.
+    #[instrument(skip(self, _options))]
+    pub async fn get_meta(
+        &self,
+        hash: AnyDhtHash,
+        _options: GetOptions,
+    ) -> CascadeResult<Option<DhtMetadata>> {
+        let authority = self.am_i_an_authority(hash.clone().into()).await?;
+        let mut sets = Vec::new();
+        if let (Some(dht), true) = (&self.dht, authority) {
+            sets.push(
+                authority::handle_get_meta(
+                    dht.clone(),
+                    hash.clone(),
+                    (&GetMetaOptions::default()).into(),
+                )
+                .await?,
+            );
+        }
+        if let Some(network) = self.network.as_ref() {
+            match network.get_meta(hash, GetMetaOptions::default()).await {
+                Ok(remote) => sets.extend(remote),
+                // Our own view is still an answer if no other authority is reachable.
+                Err(e) if !sets.is_empty() => {
+                    tracing::warn!(?e, "Failed to get metadata from other authorities")
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(MetadataSet::merge(sets))
+    }
+
     #[instrument(skip(self, options))]
     /// Gets an links from the cas or cache depending on it's metadata
     // The default behavior is to skip deleted or replaced entries.
@@ -920,7 +965,10 @@ where
                 )?;
 
             // Short circuit if we have a result.
-            if matches!(result, MustGetAgentActivityResponse::Activity(_)) {
+            if matches!(
+                result,
+                MustGetAgentActivityResponse::Activity(_) | MustGetAgentActivityResponse::Forked(_)
+            ) {
                 return Ok(result);
             }
         }
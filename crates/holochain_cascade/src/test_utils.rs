@@ -134,10 +134,17 @@ impl HolochainP2pDnaT for PassThroughNetwork {
 
     async fn get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: actor::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
     ) -> actor::HolochainP2pResult<Vec<MetadataSet>> {
-        todo!()
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_get_meta(env.clone(), dht_hash.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
     }
 
     async fn get_links(
@@ -311,6 +311,37 @@ async fn entry_authority() {
     assert_can_get(&td_entry, &td_record, &cascade, GetOptions::latest()).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn meta_authority_merges_other_authorities() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let local = test_dht_db();
+    let remote = test_dht_db();
+
+    // Data
+    let td_entry = EntryTestData::create();
+    fill_db(&local.to_db(), td_entry.store_entry_op.clone());
+    fill_db(&remote.to_db(), td_entry.store_entry_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_all(vec![remote.to_db().clone().into()]);
+
+    // Cascade
+    let cascade = Cascade::empty()
+        .with_dht(local.to_db().into())
+        .with_network(network, cache.to_db());
+
+    let meta = cascade
+        .get_meta(td_entry.hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap()
+        .expect("Failed to get metadata");
+    assert_eq!(meta.holders, 2);
+    assert_eq!(meta.actions.len(), 1);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn content_not_authority_or_authoring() {
     holochain_trace::test_run().ok();
@@ -3,7 +3,10 @@ use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
-use crate::{AppInfo, FullStateDump, StorageInfo};
+use crate::{
+    AppInfo, CapGrantInfo, CellIntrospection, CountersigningSessionInfo, FullStateDump,
+    StorageInfo, WasmMeteringStats,
+};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -27,6 +30,17 @@ pub enum AdminRequest {
     /// [`AdminResponse::AdminInterfacesAdded`]
     AddAdminInterfaces(Vec<crate::config::AdminInterfaceConfig>),
 
+    /// Read the config file the conductor was started with again and apply
+    /// what changed, as far as it can be applied while running: the tracing
+    /// filter, new admin interfaces, the gossip bandwidth limits and the
+    /// DHT cache limits.
+    /// Other changes take effect once the conductor is restarted.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ConfigReloaded`]
+    ReloadConfig,
+
     /// Register a DNA for later app installation.
     ///
     /// Stores the given DNA into the Holochain DNA database and returns the hash of it.
@@ -87,6 +101,19 @@ pub enum AdminRequest {
         installed_app_id: InstalledAppId,
     },
 
+    /// Replace the limits on the resources an installed app may use,
+    /// which were set when installing it.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppQuotasSet`]
+    SetAppQuotas {
+        /// The app to limit.
+        installed_app_id: InstalledAppId,
+        /// The new limits. Resources without a limit are unlimited.
+        quotas: AppQuotas,
+    },
+
     /// List the hashes of all installed DNAs.
     ///
     /// # Returns
@@ -96,10 +123,43 @@ pub enum AdminRequest {
 
     /// Generate a new [`AgentPubKey`].
     ///
+    /// Without a derivation path the key is random. With a derivation path
+    /// the key is derived from this device's seed, e.g. with one index for
+    /// the app and one for the DNA, so it can be derived again after the
+    /// seed is restored with [`AdminRequest::ImportDeviceSeed`]. The device
+    /// seed is created the first time a key is derived.
+    ///
     /// # Returns
     ///
     /// [`AdminResponse::AgentPubKeyGenerated`]
-    GenerateAgentPubKey,
+    GenerateAgentPubKey(#[serde(default)] Option<Vec<u32>>),
+
+    /// Export this device's seed, encrypted with a passphrase, to back up
+    /// the agent keys derived from it.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DeviceSeedExported`]
+    ExportDeviceSeed {
+        /// The passphrase to encrypt the seed with.
+        passphrase: Passphrase,
+    },
+
+    /// Import a device seed exported by [`AdminRequest::ExportDeviceSeed`],
+    /// after which keys derived from it can be generated again with
+    /// [`AdminRequest::GenerateAgentPubKey`]. Fails if this conductor
+    /// already has a device seed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DeviceSeedImported`]
+    ImportDeviceSeed {
+        /// The encrypted seed.
+        #[serde(with = "serde_bytes")]
+        seed: Vec<u8>,
+        /// The passphrase the seed was encrypted with.
+        passphrase: Passphrase,
+    },
 
     /// List the IDs of all live cells currently running in the conductor.
     ///
@@ -317,6 +377,30 @@ pub enum AdminRequest {
     /// [`AdminResponse::ZomeCallCapabilityGranted`]
     GrantZomeCallCapability(Box<GrantZomeCallCapabilityPayload>),
 
+    /// List the zome call capability grants on a cell's chain.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CapabilityGrantsInfo`]
+    ListCapabilityGrants {
+        /// The cell whose grants are listed.
+        cell_id: CellId,
+        /// Whether to include grants which have been deleted or updated.
+        #[serde(default)]
+        include_revoked: bool,
+    },
+
+    /// Revoke a zome call capability grant by deleting it from the chain
+    /// of the cell which created it. Zome calls can no longer be made with it.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ZomeCallCapabilityRevoked`]
+    RevokeZomeCallCapability {
+        /// The hash of the action which created the grant.
+        action_hash: ActionHash,
+    },
+
     /// Delete a clone cell that was previously disabled.
     ///
     /// # Returns
@@ -326,6 +410,134 @@ pub enum AdminRequest {
 
     /// Info about storage used by apps
     StorageInfo,
+
+    /// Delete everything in the cache of a DNA, i.e. the DHT data this
+    /// conductor fetched with `get` and `get_links` without being an
+    /// authority for it. The data is fetched again when it's next needed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DhtCacheCleared`]
+    ClearDhtCache {
+        /// The DNA whose cache is cleared.
+        dna_hash: DnaHash,
+    },
+
+    /// Export the integrated DHT ops of a DNA into a signed, gzipped file.
+    ///
+    /// The file can be carried to a conductor which has no network connection
+    /// to this one, and imported there with [`AdminRequest::ImportDhtOps`].
+    /// Private entries are never included.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DhtOpsExported`]
+    ExportDhtOps {
+        /// The DNA whose ops are exported.
+        dna_hash: DnaHash,
+        /// The agent which signs the export.
+        /// Its private key must be in this conductor's keystore.
+        signing_agent: AgentPubKey,
+        /// The path of the file to write the export to.
+        path: std::path::PathBuf,
+        /// Which ops to export. By default all integrated ops are exported.
+        #[serde(default)]
+        filter: DhtOpExportFilter,
+    },
+
+    /// Import a file written by [`AdminRequest::ExportDhtOps`].
+    ///
+    /// The signature of the file is checked, and then the ops are
+    /// validated and integrated just as if they had been received
+    /// through gossip. An app with a cell of the DNA must be
+    /// running on this conductor.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DhtOpsImported`]
+    ImportDhtOps {
+        /// The path of the file to import.
+        path: std::path::PathBuf,
+    },
+
+    /// Switch recording of the zome calls made to a cell on or off.
+    ///
+    /// While recording is on, each zome call made to the cell is recorded,
+    /// along with every host function the zome calls and the result it gets
+    /// back, and the final response. Only the most recent calls are kept.
+    /// Switching recording off discards the cell's recordings.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ZomeCallRecordingSet`]
+    SetZomeCallRecording {
+        /// The cell whose zome calls are recorded.
+        cell_id: CellId,
+        /// Whether recording is switched on or off.
+        enabled: bool,
+    },
+
+    /// Get the most recent zome calls recorded for a cell, oldest first.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ZomeCallRecordings`]
+    GetZomeCallRecordings {
+        /// The cell to get the recordings of.
+        cell_id: CellId,
+    },
+
+    /// List the countersigning sessions that the conductor's cells have
+    /// locked their chains for.
+    ///
+    /// Sessions survive a conductor restart, so a cell's chain stays locked
+    /// until its session completes, expires or is abandoned.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CountersigningSessions`]
+    ListCountersigningSessions,
+
+    /// Forcibly abandon the countersigning session a cell has locked its
+    /// chain for.
+    ///
+    /// The cell's chain is unlocked. If its agent has already committed the
+    /// countersigned entry it stays on their chain, but its ops are never
+    /// published. Other signers won't be able to complete the session.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CountersigningSessionAbandoned`]
+    AbandonCountersigningSession {
+        /// The cell whose session is abandoned.
+        cell_id: CellId,
+    },
+
+    /// List the zome functions, lifecycle callbacks, scheduled functions
+    /// and entry and link types of each zome of a cell.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CellIntrospection`]
+    IntrospectCell {
+        /// The cell to introspect.
+        cell_id: CellId,
+    },
+
+    /// Get how many wasm metering points each kind of call into each zome
+    /// has used since the conductor started, along with its budget.
+    ///
+    /// Budgets can be set per DNA, per zome and per kind of call under the
+    /// `metering` key of the DNA properties.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::WasmMeteringStats`]
+    GetWasmMeteringStats {
+        /// Only get the stats of this DNA. By default all DNAs are included.
+        #[serde(default)]
+        dna_hash: Option<DnaHash>,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -365,16 +577,33 @@ pub enum AdminResponse {
     /// It means the app was uninstalled successfully.
     AppUninstalled,
 
+    /// The successful response to an [`AdminRequest::SetAppQuotas`].
+    AppQuotasSet,
+
     /// The successful response to an [`AdminRequest::AddAdminInterfaces`].
     ///
     /// It means the `AdminInterface`s have successfully been added.
     AdminInterfacesAdded,
 
+    /// The successful response to an [`AdminRequest::ReloadConfig`].
+    ///
+    /// Lists the changed settings which were applied and those which
+    /// require a restart.
+    ConfigReloaded(crate::config::conductor::ConductorConfigReload),
+
     /// The successful response to an [`AdminRequest::GenerateAgentPubKey`].
     ///
     /// Contains a new [`AgentPubKey`] generated by the keystore.
     AgentPubKeyGenerated(AgentPubKey),
 
+    /// The successful response to an [`AdminRequest::ExportDeviceSeed`].
+    ///
+    /// Contains the encrypted device seed.
+    DeviceSeedExported(#[serde(with = "serde_bytes")] Vec<u8>),
+
+    /// The successful response to an [`AdminRequest::ImportDeviceSeed`].
+    DeviceSeedImported,
+
     /// The successful response to an [`AdminRequest::ListDnas`].
     ///
     /// Contains a list of the hashes of all installed DNAs.
@@ -459,11 +688,60 @@ pub enum AdminResponse {
     /// The successful response to an [`AdminRequest::GrantZomeCallCapability`].
     ZomeCallCapabilityGranted,
 
+    /// The successful response to an [`AdminRequest::ListCapabilityGrants`].
+    CapabilityGrantsInfo(Vec<CapGrantInfo>),
+
+    /// The successful response to an [`AdminRequest::RevokeZomeCallCapability`].
+    ZomeCallCapabilityRevoked,
+
     /// The successful response to an [`AdminRequest::DeleteCloneCell`].
     CloneCellDeleted,
 
     /// The successful response to an [`AdminRequest::StorageInfo`].
     StorageInfo(StorageInfo),
+
+    /// The successful response to an [`AdminRequest::ClearDhtCache`].
+    ///
+    /// Contains the number of ops which were deleted from the cache.
+    DhtCacheCleared(usize),
+
+    /// The successful response to an [`AdminRequest::ExportDhtOps`].
+    ///
+    /// Contains the number of ops written to the file.
+    DhtOpsExported(usize),
+
+    /// The successful response to an [`AdminRequest::ImportDhtOps`].
+    ///
+    /// The ops have been queued for validation.
+    DhtOpsImported {
+        /// The DNA the imported ops belong to.
+        dna_hash: DnaHash,
+        /// The agent who signed the imported file.
+        signer: AgentPubKey,
+        /// The number of ops in the file.
+        op_count: usize,
+    },
+
+    /// The successful response to an [`AdminRequest::SetZomeCallRecording`].
+    ZomeCallRecordingSet,
+
+    /// The successful response to an [`AdminRequest::GetZomeCallRecordings`].
+    ZomeCallRecordings(Vec<ZomeCallRecording>),
+
+    /// The successful response to an [`AdminRequest::ListCountersigningSessions`].
+    CountersigningSessions(Vec<CountersigningSessionInfo>),
+
+    /// The successful response to an [`AdminRequest::AbandonCountersigningSession`].
+    ///
+    /// Contains the hash of the countersigned entry which was removed from
+    /// the cell's chain, if the agent had committed it.
+    CountersigningSessionAbandoned(Option<EntryHash>),
+
+    /// The successful response to an [`AdminRequest::IntrospectCell`].
+    CellIntrospection(CellIntrospection),
+
+    /// The successful response to an [`AdminRequest::GetWasmMeteringStats`].
+    WasmMeteringStats(Vec<WasmMeteringStats>),
 }
 
 /// Error type that goes over the websocket wire.
@@ -509,3 +787,15 @@ pub enum AppStatusFilter {
     Stopped,
     Paused,
 }
+
+/// A passphrase sent over the admin interface.
+/// It is left out of the debug output so that it never ends up in logs.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Passphrase(pub String);
+
+impl std::fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Passphrase(..)")
+    }
+}
@@ -1,4 +1,6 @@
+use crate::CellIntrospection;
 use crate::ExternalApiWireError;
+use crate::RemoteSignalDelivery;
 use holo_hash::AgentPubKey;
 use holochain_keystore::LairResult;
 use holochain_keystore::MetaLairClient;
@@ -38,6 +40,14 @@ pub enum AppRequest {
     /// [`AppResponse::ZomeCalled`]
     CallZome(Box<ZomeCall>),
 
+    /// Call a zome function just like [`AppRequest::CallZome`], and also
+    /// report how many wasm metering points the function used.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::ZomeCalledMetered`]
+    CallZomeMetered(Box<ZomeCall>),
+
     /// Clone a DNA (in the biological sense), thus creating a new `Cell`.
     ///
     /// Using the provided, already-registered DNA, create a new DNA with a unique
@@ -77,6 +87,33 @@ pub enum AppRequest {
     ///
     /// [`AppResponse::ListWasmHostFunctions`]
     ListWasmHostFunctions,
+
+    /// List the zome functions, lifecycle callbacks, scheduled functions
+    /// and entry and link types of each zome of a cell of the app.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::CellIntrospection`]
+    IntrospectCell {
+        /// The app ID the cell belongs to.
+        installed_app_id: InstalledAppId,
+        /// The cell to introspect.
+        cell_id: CellId,
+    },
+
+    /// List the remote signals a cell of the app has sent in reliable mode
+    /// which are still held by the conductor, whether they have been
+    /// delivered or are still waiting to be.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::RemoteSignalDeliveries`]
+    RemoteSignalDeliveries {
+        /// The app ID the cell belongs to.
+        installed_app_id: InstalledAppId,
+        /// The cell which sent the signals.
+        cell_id: CellId,
+    },
 }
 
 /// Represents the possible responses to an [`AppRequest`].
@@ -101,6 +138,15 @@ pub enum AppResponse {
     /// [msgpack]: https://msgpack.org/
     ZomeCalled(Box<ExternIO>),
 
+    /// The successful response to an [`AppRequest::CallZomeMetered`].
+    ZomeCalledMetered {
+        /// The output of the zome function, as in [`AppResponse::ZomeCalled`].
+        output: Box<ExternIO>,
+        /// The wasm metering points used by the zome function itself.
+        /// Calls it made to other zomes are metered separately.
+        metering_points: u64,
+    },
+
     /// The successful response to an [`AppRequest::CreateCloneCell`].
     ///
     /// The response contains the created clone [`ClonedCell`].
@@ -122,6 +168,13 @@ pub enum AppResponse {
 
     /// All the wasm host functions supported by this conductor.
     ListWasmHostFunctions(Vec<String>),
+
+    /// The successful response to an [`AppRequest::IntrospectCell`].
+    CellIntrospection(CellIntrospection),
+
+    /// The successful response to an [`AppRequest::RemoteSignalDeliveries`],
+    /// oldest signal first.
+    RemoteSignalDeliveries(Vec<RemoteSignalDelivery>),
 }
 
 /// The data provided over an app interface in order to make a zome call
@@ -444,6 +497,44 @@ pub struct NetworkInfo {
     pub total_network_peers: u32,
     pub bytes_since_last_time_queried: u64,
     pub completed_rounds_since_last_time_queried: u32,
+    /// The arc size this agent is converging on, given the estimated
+    /// number of peers and the gossip redundancy target.
+    #[serde(default)]
+    pub target_arc_size: f64,
+    /// The average number of peers holding each point of this agent's arc,
+    /// extrapolated from the peers this conductor knows about.
+    #[serde(default)]
+    pub extrapolated_coverage: Option<f64>,
+    /// Gossip history with each peer node this conductor has gossiped with.
+    #[serde(default)]
+    pub peer_gossip_info: Vec<PeerGossipInfo>,
+    /// The number of authored ops that have not yet collected enough
+    /// validation receipts and so will be published again.
+    #[serde(default)]
+    pub publish_queue_length: u32,
+    /// The number of ops received from the network that are not yet
+    /// integrated, by op type.
+    #[serde(default)]
+    pub validation_backlog: HashMap<DhtOpType, u32>,
+}
+
+/// Gossip history with a single peer node.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PeerGossipInfo {
+    /// The base64 encoded TLS certificate digest of the peer node.
+    pub node_id: String,
+    /// When the most recent completed round with this peer ended.
+    pub last_round_time: Option<Timestamp>,
+    /// The number of remembered rounds that completed successfully.
+    pub completed_rounds: u32,
+    /// The number of remembered rounds that ended in an error.
+    pub errored_rounds: u32,
+    /// The bytes of gossip received from this peer since the conductor started.
+    pub bytes_in: u64,
+    /// The bytes of gossip sent to this peer since the conductor started.
+    pub bytes_out: u64,
+    /// Whether a round with this peer is currently in progress.
+    pub round_in_progress: bool,
 }
 
 #[test]
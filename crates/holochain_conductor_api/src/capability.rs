@@ -0,0 +1,20 @@
+use holochain_types::prelude::*;
+
+/// A zome call capability grant on a cell's chain.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct CapGrantInfo {
+    /// The grant.
+    pub cap_grant: ZomeCallCapGrant,
+    /// The hash of the action which created the grant.
+    /// Pass this to [`AdminRequest::RevokeZomeCallCapability`] to revoke it.
+    ///
+    /// [`AdminRequest::RevokeZomeCallCapability`]: crate::AdminRequest::RevokeZomeCallCapability
+    pub action_hash: ActionHash,
+    /// When the grant was created.
+    pub created_at: Timestamp,
+    /// When the grant was deleted or updated, if it has been.
+    pub revoked_at: Option<Timestamp>,
+    /// The number of zome calls made with the grant,
+    /// if the number of calls it can be used for is limited.
+    pub uses: Option<u32>,
+}
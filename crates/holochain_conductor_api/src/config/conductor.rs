@@ -7,6 +7,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 mod admin_interface_config;
+mod dht_cache_config;
 mod dpki_config;
 #[allow(missing_docs)]
 mod error;
@@ -17,6 +18,7 @@ pub mod paths;
 pub use paths::DatabaseRootPath;
 
 pub use super::*;
+pub use dht_cache_config::DhtCacheConfig;
 pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
@@ -50,6 +52,11 @@ pub struct ConductorConfig {
     /// Optional config for the network module.
     pub network: Option<holochain_p2p::kitsune_p2p::KitsuneP2pConfig>,
 
+    /// Optional limits on the size of the caches of DHT data.
+    /// If omitted, the caches grow without bound.
+    #[serde(default)]
+    pub dht_cache: Option<DhtCacheConfig>,
+
     /// **PLACEHOLDER**: Optional specification of the Cloudflare namespace to use in Chain Head Coordination
     /// service URLs. This is a placeholder for future work and may even go away.
     /// Setting this to anything other than `None` will surely lead to no good.
@@ -94,6 +101,21 @@ impl ConductorConfig {
     }
 }
 
+/// What changed when the config of a running conductor was reloaded.
+/// Each setting is named by its path in the config file.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq, Eq)]
+pub struct ConductorConfigReload {
+    /// The settings which changed and now apply to the running conductor.
+    pub applied: Vec<String>,
+    /// The settings which changed but only apply once the conductor is restarted.
+    /// `admin_interfaces` is listed here when an admin interface was removed,
+    /// since running admin interfaces can't be stopped.
+    pub requires_restart: Vec<String>,
+    /// The settings which changed to a value that can't be used,
+    /// so the running conductor keeps its current value.
+    pub invalid: Vec<String>,
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -134,6 +156,7 @@ pub mod tests {
                 tracing_override: None,
                 environment_path: PathBuf::from("/path/to/env").into(),
                 network: None,
+                dht_cache: None,
                 dpki: None,
                 keystore: KeystoreConfig::DangerTestKeystore,
                 admin_interfaces: None,
@@ -181,6 +204,10 @@ pub mod tests {
         proxy_to_expire_ms: 42
       network_type: quic_bootstrap
 
+    dht_cache:
+      max_bytes_per_dna: 1000000
+      max_age_s: 3600
+
     db_sync_strategy: Fast
     "#;
         let result: ConductorConfigResult<ConductorConfig> = config_from_yaml(yaml);
@@ -215,6 +242,11 @@ pub mod tests {
                     driver: InterfaceDriver::Websocket { port: 1234 }
                 }]),
                 network: Some(network_config),
+                dht_cache: Some(DhtCacheConfig {
+                    max_bytes_per_dna: Some(1_000_000),
+                    max_bytes: None,
+                    max_age_s: Some(3600),
+                }),
                 db_sync_strategy: DbSyncStrategy::Fast,
                 chc_namespace: None,
             }
@@ -238,6 +270,7 @@ pub mod tests {
                 tracing_override: None,
                 environment_path: PathBuf::from("/path/to/env").into(),
                 network: None,
+                dht_cache: None,
                 dpki: None,
                 keystore: KeystoreConfig::LairServer {
                     connection_url: url2::url2!("unix:///var/run/lair-keystore/socket?k=EcRDnP3xDIZ9Rk_1E-egPE0mGZi5CcszeRxVkb2QXXQ").into(),
@@ -0,0 +1,24 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Limits on the caches of DHT data, i.e. the data fetched with `get` and
+/// `get_links` which this conductor isn't an authority for.
+///
+/// When a cache is over a limit, the ops fetched least recently are evicted
+/// until it's within the limit again. Ops in the storage arc of one of
+/// this conductor's agents are never evicted.
+#[derive(Clone, Deserialize, Serialize, Default, Debug, PartialEq, Eq)]
+pub struct DhtCacheConfig {
+    /// The most bytes the cache of a single DNA may take up.
+    #[serde(default)]
+    pub max_bytes_per_dna: Option<u64>,
+
+    /// The most bytes the caches of all DNAs together may take up.
+    #[serde(default)]
+    pub max_bytes: Option<u64>,
+
+    /// Evict ops which haven't been fetched for this many seconds,
+    /// whatever the size of the cache.
+    #[serde(default)]
+    pub max_age_s: Option<u64>,
+}
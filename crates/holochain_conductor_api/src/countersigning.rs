@@ -0,0 +1,14 @@
+use holochain_types::prelude::*;
+
+/// A countersigning session that one of the conductor's cells has locked
+/// its chain for.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct CountersigningSessionInfo {
+    /// The cell whose chain is locked for the session.
+    pub cell_id: CellId,
+    /// The preflight request the cell's agent accepted to join the session.
+    pub preflight_request: PreflightRequest,
+    /// The hash of the countersigned entry, once the agent has committed it.
+    /// Until then the session can't complete.
+    pub entry_hash: Option<EntryHash>,
+}
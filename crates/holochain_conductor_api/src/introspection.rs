@@ -0,0 +1,33 @@
+use holochain_types::prelude::*;
+
+/// What each zome of a cell exposes, for tooling which needs to know the
+/// shape of a hApp without reading its source.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct CellIntrospection {
+    /// The cell which was introspected.
+    pub cell_id: CellId,
+    /// The integrity zomes followed by the coordinator zomes of the cell,
+    /// in the order of the DNA definition.
+    pub zomes: Vec<ZomeIntrospection>,
+}
+
+/// What a single zome exposes.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ZomeIntrospection {
+    /// The name of the zome.
+    pub zome_name: ZomeName,
+    /// Whether this is an integrity zome or a coordinator zome.
+    pub integrity: bool,
+    /// The extern functions which can be called as zome functions,
+    /// excluding callbacks.
+    pub zome_fns: Vec<FunctionName>,
+    /// The lifecycle callbacks the zome exports, such as `init`, `validate`,
+    /// `post_commit` and `migrate_agent`.
+    pub callbacks: Vec<FunctionName>,
+    /// The zome functions the cell's agent currently has scheduled.
+    pub scheduled_fns: Vec<FunctionName>,
+    /// The entry types an integrity zome defines, with their visibility.
+    pub entry_defs: Vec<EntryDef>,
+    /// The link types an integrity zome defines.
+    pub link_types: Vec<LinkType>,
+}
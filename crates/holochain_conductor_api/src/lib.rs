@@ -26,13 +26,23 @@
 
 mod admin_interface;
 mod app_interface;
+pub mod capability;
 pub mod config;
+pub mod countersigning;
+pub mod introspection;
+pub mod metering;
+pub mod remote_signal;
 pub mod signal_subscription;
 pub mod state_dump;
 pub mod storage_info;
 
 pub use admin_interface::*;
 pub use app_interface::*;
+pub use capability::*;
 pub use config::*;
+pub use countersigning::*;
+pub use introspection::*;
+pub use metering::*;
+pub use remote_signal::*;
 pub use state_dump::*;
 pub use storage_info::*;
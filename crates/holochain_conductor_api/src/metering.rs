@@ -0,0 +1,49 @@
+use holochain_types::prelude::*;
+
+/// The kinds of wasm call which can be given their own metering budget.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    SerializedBytes,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteredCallKind {
+    /// A zome function called by a client or another zome.
+    ZomeCall,
+    /// The `validate` callback.
+    Validate,
+    /// The `init` callback.
+    Init,
+    /// Every other callback, such as `post_commit` or scheduled functions.
+    Other,
+}
+
+/// How many wasm metering points one kind of call into one zome has used
+/// since the conductor started.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct WasmMeteringStats {
+    /// The DNA of the zome.
+    pub dna_hash: DnaHash,
+    /// The zome called.
+    pub zome_name: ZomeName,
+    /// The kind of call.
+    pub kind: MeteredCallKind,
+    /// The points each call may use before it is aborted.
+    pub budget: u64,
+    /// The number of calls made.
+    pub calls: u64,
+    /// The points used by all calls together.
+    pub total_points: u64,
+    /// The most points used by a single call.
+    pub max_points: u64,
+    /// The number of calls aborted for using their whole budget.
+    pub exhausted: u64,
+}
@@ -0,0 +1,19 @@
+use holochain_types::prelude::*;
+
+/// The delivery state of a remote signal sent in reliable mode.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct RemoteSignalDelivery {
+    /// The agent the signal is sent to.
+    pub to_agent: AgentPubKey,
+    /// The zome which sent the signal and will receive it on the recipient's cell.
+    pub zome_name: ZomeName,
+    /// The signal payload.
+    pub signal: ExternIO,
+    /// When the signal was queued by the sending conductor.
+    pub queued_at: Timestamp,
+    /// How many times delivery has been attempted so far.
+    pub attempts: u32,
+    /// When the recipient's conductor acknowledged the signal,
+    /// or `None` if it has not been delivered yet.
+    pub delivered_at: Option<Timestamp>,
+}
@@ -20,8 +20,26 @@ pub enum StorageBlob {
     Dna(DnaStorageInfo),
 }
 
+/// The resources used by a hApp, as counted against its quotas.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct AppStorageInfo {
+    pub installed_app_id: InstalledAppId,
+    /// The bytes of actions and entries authored by the app's agents.
+    pub authored_data_size: usize,
+    /// The size of the caches of the app's DNAs.
+    pub cache_data_size: usize,
+    /// The number of clone cells of the app, enabled or disabled.
+    pub clone_cells: usize,
+    /// The number of zome calls into the app's cells which are running,
+    /// only counted for apps with quotas.
+    pub concurrent_zome_calls: u32,
+    pub quotas: AppQuotas,
+}
+
 /// Response type for storage used by holochain and applications
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct StorageInfo {
     pub blobs: Vec<StorageBlob>,
+    #[serde(default)]
+    pub apps: Vec<AppStorageInfo>,
 }
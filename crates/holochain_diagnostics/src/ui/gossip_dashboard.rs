@@ -37,11 +37,13 @@ use self::widgets::{
     gossip_round_table::{gossip_round_table, GossipRoundTableState},
 };
 
+mod headless;
 mod input;
 mod layout;
 mod render;
 mod widgets;
 
+pub use headless::*;
 pub use input::*;
 
 // 999, 99, or 9
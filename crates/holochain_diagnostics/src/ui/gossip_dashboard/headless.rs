@@ -0,0 +1,433 @@
+//! A headless counterpart to the [`GossipDashboard`], which writes the same
+//! state model as JSON-lines snapshots, followed by a summary of the run.
+//! This lets diagnostic scenarios run in CI and be compared across versions.
+
+use super::*;
+use serde::Serialize;
+use std::io::{self, Write};
+
+/// One line of headless output
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadlessRecord {
+    Snapshot(Snapshot),
+    Summary(RunSummary),
+}
+
+/// The state of every node at a point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct Snapshot {
+    /// Milliseconds since the start of the run
+    pub elapsed_ms: u64,
+    pub total_commits: usize,
+    pub nodes: Vec<NodeSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeSnapshot {
+    pub agent: String,
+    /// The number of links each base is still missing, as last seen by this node
+    pub missing_links: Vec<usize>,
+    /// The number of ops this node has integrated
+    pub integrated_ops: usize,
+    pub num_ops_to_fetch: usize,
+    pub op_bytes_to_fetch: usize,
+    pub current_rounds: usize,
+    pub completed_rounds: usize,
+    pub errored_rounds: usize,
+    /// Bytes of gossip received from all peers
+    pub gossip_bytes_in: u64,
+    /// Bytes of gossip sent to all peers
+    pub gossip_bytes_out: u64,
+}
+
+/// A summary of a whole run, written after the last snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub duration_ms: u64,
+    /// When every node last reached a state with no missing links and
+    /// nothing left to fetch, if the run ended in that state
+    pub convergence_time_ms: Option<u64>,
+    pub num_snapshots: usize,
+    pub total_commits: usize,
+    pub ops_per_node: Vec<usize>,
+    pub gossip_bytes_in: u64,
+    pub gossip_bytes_out: u64,
+    /// Bytes received through gossip per second, across all nodes
+    pub gossip_throughput_bytes_per_sec: f64,
+}
+
+impl Snapshot {
+    /// Capture the current state of every node
+    pub async fn capture<S: ClientState>(start_time: Instant, state: &RwShare<S>) -> Self {
+        let (total_commits, nodes) = state.share_ref(|state| {
+            let nodes: Vec<_> = state
+                .nodes()
+                .iter()
+                .zip(state.link_counts())
+                .map(|(node, link_counts)| (node.clone(), node_stats(node, link_counts)))
+                .collect();
+            (state.total_commits(), nodes)
+        });
+
+        let nodes =
+            futures::future::join_all(nodes.into_iter().map(|(node, mut snapshot)| async move {
+                snapshot.integrated_ops = integrated_ops(&node).await.unwrap_or_else(|err| {
+                    tracing::error!("Failed to count integrated ops: {:?}", err);
+                    0
+                });
+                snapshot
+            }))
+            .await;
+
+        Self {
+            elapsed_ms: start_time.elapsed().as_millis() as u64,
+            total_commits,
+            nodes,
+        }
+    }
+
+    /// Whether every node has seen every commit, has nothing left to fetch
+    /// and has integrated as many ops as every other node
+    pub fn is_converged(&self) -> bool {
+        let integrated_ops = self.nodes.first().map(|n| n.integrated_ops);
+        self.total_commits > 0
+            && self.nodes.iter().all(|n| {
+                n.num_ops_to_fetch == 0
+                    && n.missing_links.iter().all(|missing| *missing == 0)
+                    && Some(n.integrated_ops) == integrated_ops
+            })
+    }
+}
+
+fn node_stats(node: &Node, link_counts: &[(usize, Instant)]) -> NodeSnapshot {
+    let fetch_pool_info = node.diagnostics.fetch_pool.info(
+        [node.zome.cell_id().dna_hash().to_kitsune()]
+            .into_iter()
+            .collect(),
+    );
+    let mut snapshot = NodeSnapshot {
+        agent: node.agent().to_string(),
+        missing_links: link_counts.iter().map(|(count, _)| *count).collect(),
+        integrated_ops: 0,
+        num_ops_to_fetch: fetch_pool_info.num_ops_to_fetch,
+        op_bytes_to_fetch: fetch_pool_info.op_bytes_to_fetch,
+        current_rounds: 0,
+        completed_rounds: 0,
+        errored_rounds: 0,
+        gossip_bytes_in: 0,
+        gossip_bytes_out: 0,
+    };
+    let metrics = node.diagnostics.metrics.read();
+    for history in metrics.peer_node_histories().values() {
+        snapshot.current_rounds += history.current_round.is_some() as usize;
+        for round in history.completed_rounds.iter() {
+            if round.error {
+                snapshot.errored_rounds += 1;
+            } else {
+                snapshot.completed_rounds += 1;
+            }
+        }
+        snapshot.gossip_bytes_in += history.bytes_in;
+        snapshot.gossip_bytes_out += history.bytes_out;
+    }
+    snapshot
+}
+
+async fn integrated_ops(node: &Node) -> anyhow::Result<usize> {
+    let db = node.conductor.get_dht_db(node.zome.cell_id().dna_hash())?;
+    let count = db
+        .async_reader(|txn| {
+            anyhow::Ok(txn.query_row(
+                "SELECT COUNT(hash) FROM DhtOp WHERE when_integrated IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )?)
+        })
+        .await?;
+    Ok(count)
+}
+
+/// Writes snapshots of the dashboard state as JSON lines instead of
+/// rendering them to a terminal.
+pub struct HeadlessDashboard {
+    start_time: Instant,
+    refresh_rate: Duration,
+    out: Box<dyn Write + Send>,
+    num_snapshots: usize,
+    converged_at: Option<u64>,
+    last: Option<Snapshot>,
+}
+
+impl HeadlessDashboard {
+    pub fn new(
+        start_time: Instant,
+        refresh_rate: Duration,
+        out: impl Write + Send + 'static,
+    ) -> Self {
+        Self {
+            start_time,
+            refresh_rate,
+            out: Box::new(out),
+            num_snapshots: 0,
+            converged_at: None,
+            last: None,
+        }
+    }
+
+    /// Capture a snapshot and write it out
+    pub async fn record<S: ClientState>(&mut self, state: &RwShare<S>) -> io::Result<&Snapshot> {
+        let snapshot = Snapshot::capture(self.start_time, state).await;
+        self.observe(snapshot)
+    }
+
+    /// Write out a snapshot and track convergence with it
+    fn observe(&mut self, snapshot: Snapshot) -> io::Result<&Snapshot> {
+        self.write(&HeadlessRecord::Snapshot(snapshot.clone()))?;
+
+        self.num_snapshots += 1;
+        if snapshot.is_converged() {
+            self.converged_at.get_or_insert(snapshot.elapsed_ms);
+        } else {
+            self.converged_at = None;
+        }
+        Ok(self.last.insert(snapshot))
+    }
+
+    /// Record a snapshot at the refresh rate until one satisfies `done`,
+    /// or until the timeout elapses, then write out the summary.
+    ///
+    /// If the timeout elapses first the summary is still written, and a
+    /// [`io::ErrorKind::TimedOut`] error is returned so the run fails.
+    pub async fn run<S: ClientState>(
+        mut self,
+        state: RwShare<S>,
+        done: impl Fn(&Snapshot) -> bool,
+        timeout: Duration,
+    ) -> io::Result<RunSummary> {
+        let is_done = loop {
+            let snapshot = self.record(&state).await?;
+            if done(snapshot) {
+                break true;
+            }
+            if self.start_time.elapsed() >= timeout {
+                break false;
+            }
+            tokio::time::sleep(self.refresh_rate).await;
+        };
+        let summary = self.finish()?;
+        if is_done {
+            Ok(summary)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("The run did not finish within {:?}", timeout),
+            ))
+        }
+    }
+
+    /// Summarize the run so far
+    pub fn summary(&self) -> RunSummary {
+        let duration_ms = self.start_time.elapsed().as_millis() as u64;
+        let nodes = self
+            .last
+            .as_ref()
+            .map(|s| s.nodes.as_slice())
+            .unwrap_or(&[]);
+        let gossip_bytes_in = nodes.iter().map(|n| n.gossip_bytes_in).sum();
+        RunSummary {
+            duration_ms,
+            convergence_time_ms: self.converged_at,
+            num_snapshots: self.num_snapshots,
+            total_commits: self.last.as_ref().map(|s| s.total_commits).unwrap_or(0),
+            ops_per_node: nodes.iter().map(|n| n.integrated_ops).collect(),
+            gossip_bytes_in,
+            gossip_bytes_out: nodes.iter().map(|n| n.gossip_bytes_out).sum(),
+            gossip_throughput_bytes_per_sec: if duration_ms > 0 {
+                gossip_bytes_in as f64 * 1000.0 / duration_ms as f64
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// Write out the summary of the run
+    pub fn finish(mut self) -> io::Result<RunSummary> {
+        let summary = self.summary();
+        self.write(&HeadlessRecord::Summary(summary.clone()))?;
+        self.out.flush()?;
+        Ok(summary)
+    }
+
+    fn write(&mut self, record: &HeadlessRecord) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, record)?;
+        writeln!(self.out)
+    }
+}
+
+/// Where headless output is written: the file named by the `SNAPSHOTS`
+/// environment variable, or stdout if it isn't set.
+pub fn headless_output() -> io::Result<Box<dyn Write + Send>> {
+    Ok(match std::env::var("SNAPSHOTS") {
+        Ok(path) => Box::new(std::fs::File::create(path)?),
+        Err(_) => Box::new(io::stdout()),
+    })
+}
+
+/// A [`ClientState`] for scenarios which only count their commits
+/// and have no link bases to track.
+pub struct HeadlessState {
+    time: Instant,
+    nodes: Vec<Node>,
+    commits: usize,
+    link_counts: LinkCounts,
+}
+
+impl HeadlessState {
+    pub fn new(nodes: Vec<Node>) -> Self {
+        Self {
+            time: Instant::now(),
+            link_counts: vec![vec![]; nodes.len()],
+            nodes,
+            commits: 0,
+        }
+    }
+
+    /// Count commits made by any of the nodes
+    pub fn add_commits(&mut self, commits: usize) {
+        self.commits += commits;
+        self.time = Instant::now();
+    }
+}
+
+impl ClientState for HeadlessState {
+    fn time(&self) -> Instant {
+        self.time
+    }
+
+    fn num_bases(&self) -> usize {
+        0
+    }
+
+    fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    fn total_commits(&self) -> usize {
+        self.commits
+    }
+
+    fn link_counts(&self) -> LinkCountsRef {
+        &self.link_counts
+    }
+
+    fn node_rounds_sorted<'a>(&self, metrics: &'a Metrics) -> NodeRounds<'a, usize> {
+        let mut histories: Vec<_> = metrics
+            .peer_node_histories()
+            .iter()
+            .filter_map(|(id, history)| {
+                self.nodes
+                    .iter()
+                    .position(|node| node.id == *id)
+                    .map(|i| (i, history))
+            })
+            .collect();
+        histories.sort_unstable_by_key(|(i, _)| *i);
+        NodeRounds::new(histories)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// A writer whose output can be read back after it's been boxed
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn node(missing_links: usize, integrated_ops: usize, num_ops_to_fetch: usize) -> NodeSnapshot {
+        NodeSnapshot {
+            agent: "agent".to_string(),
+            missing_links: vec![missing_links],
+            integrated_ops,
+            num_ops_to_fetch,
+            op_bytes_to_fetch: 0,
+            current_rounds: 0,
+            completed_rounds: 0,
+            errored_rounds: 0,
+            gossip_bytes_in: 100,
+            gossip_bytes_out: 50,
+        }
+    }
+
+    fn snapshot(elapsed_ms: u64, total_commits: usize, nodes: Vec<NodeSnapshot>) -> Snapshot {
+        Snapshot {
+            elapsed_ms,
+            total_commits,
+            nodes,
+        }
+    }
+
+    #[test]
+    fn convergence_check() {
+        assert!(snapshot(0, 1, vec![node(0, 10, 0), node(0, 10, 0)]).is_converged());
+        // Nothing has been committed yet
+        assert!(!snapshot(0, 0, vec![node(0, 10, 0), node(0, 10, 0)]).is_converged());
+        // A node is missing links
+        assert!(!snapshot(0, 1, vec![node(1, 10, 0), node(0, 10, 0)]).is_converged());
+        // A node still has ops to fetch
+        assert!(!snapshot(0, 1, vec![node(0, 10, 0), node(0, 10, 2)]).is_converged());
+        // A node hasn't integrated every op yet
+        assert!(!snapshot(0, 1, vec![node(0, 10, 0), node(0, 8, 0)]).is_converged());
+    }
+
+    #[test]
+    fn summary_reports_the_last_convergence() {
+        let out = SharedBuf::default();
+        let mut headless =
+            HeadlessDashboard::new(Instant::now(), Duration::from_millis(1), out.clone());
+
+        headless
+            .observe(snapshot(10, 1, vec![node(0, 10, 0), node(0, 10, 0)]))
+            .unwrap();
+        headless
+            .observe(snapshot(20, 2, vec![node(0, 12, 0), node(0, 10, 1)]))
+            .unwrap();
+        assert_eq!(headless.summary().convergence_time_ms, None);
+
+        headless
+            .observe(snapshot(30, 2, vec![node(0, 12, 0), node(0, 12, 0)]))
+            .unwrap();
+        headless
+            .observe(snapshot(40, 2, vec![node(0, 12, 0), node(0, 12, 0)]))
+            .unwrap();
+        let summary = headless.finish().unwrap();
+        assert_eq!(summary.convergence_time_ms, Some(30));
+        assert_eq!(summary.num_snapshots, 4);
+        assert_eq!(summary.total_commits, 2);
+        assert_eq!(summary.ops_per_node, vec![12, 12]);
+        assert_eq!(summary.gossip_bytes_in, 200);
+        assert_eq!(summary.gossip_bytes_out, 100);
+
+        // One JSON line per snapshot, followed by the summary
+        let out = String::from_utf8(out.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[..4].iter().all(|line| line.get("snapshot").is_some()));
+        assert_eq!(lines[4]["summary"]["convergence_time_ms"], 30);
+    }
+}
@@ -1,9 +1,12 @@
 use super::CapSecret;
+use crate::timestamp::Timestamp;
 use crate::zome::FunctionName;
 use crate::zome::ZomeName;
 use holo_hash::*;
+use holochain_serialized_bytes::SerializedBytes;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 
 /// Represents a _potentially_ valid access grant to a zome call.
@@ -50,25 +53,62 @@ pub struct ZomeCallCapGrant {
     pub access: CapAccess,
     /// Set of functions to which this capability grants ZomeCall access
     pub functions: GrantedFunctions,
-    // @todo the payloads to curry to the functions
-    // pub curry_payloads: CurryPayloads,
+    /// The time after which this grant can no longer be used.
+    /// A grant without an expiry is valid until it is deleted.
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+    /// The number of zome calls which may be made with this grant.
+    /// Uses are counted by the conductor of the grantor.
+    /// A grant without a limit can be used any number of times.
+    #[serde(default)]
+    pub max_uses: Option<u32>,
+    /// Fixed payload fields for calls to some of the granted functions.
+    #[serde(default)]
+    pub curry_payloads: CurryPayloads,
 }
 
 impl ZomeCallCapGrant {
     /// Constructor
-    pub fn new(
-        tag: String,
-        access: CapAccess,
-        functions: GrantedFunctions,
-        // @todo curry_payloads: CurryPayloads,
-    ) -> Self {
+    pub fn new(tag: String, access: CapAccess, functions: GrantedFunctions) -> Self {
         Self {
             tag,
             access,
             functions,
-            // @todo curry_payloads,
+            expires_at: None,
+            max_uses: None,
+            curry_payloads: CurryPayloads::default(),
         }
     }
+
+    /// Make the grant unusable after the given time.
+    pub fn with_expiry(mut self, expires_at: Timestamp) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Limit the number of zome calls which may be made with the grant.
+    pub fn with_max_uses(mut self, max_uses: u32) -> Self {
+        self.max_uses = Some(max_uses);
+        self
+    }
+
+    /// Fix fields of the payload of calls to a function.
+    /// The payload is a map of field names to values.
+    pub fn with_curry_payload(
+        mut self,
+        function: GrantedFunction,
+        payload: SerializedBytes,
+    ) -> Self {
+        self.curry_payloads.0.insert(function, payload);
+        self
+    }
+
+    /// Has the grant expired at the given time?
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= now)
+            .unwrap_or(false)
+    }
 }
 
 impl From<ZomeCallCapGrant> for CapGrant {
@@ -185,6 +225,16 @@ impl CapAccess {
 
 /// a single zome/function pair
 pub type GrantedFunction = (ZomeName, FunctionName);
+
+/// Payload fields which are curried into calls made with a grant, by function.
+///
+/// Each payload is a map of field names to values. The conductor of the
+/// grantor adds these fields to the payload of a call to the function, and
+/// rejects the call if its payload already has one of them with a different
+/// value, so the grant only allows calls with those values.
+#[derive(Default, PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct CurryPayloads(pub BTreeMap<GrantedFunction, SerializedBytes>);
 /// A collection of zome/function pairs
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
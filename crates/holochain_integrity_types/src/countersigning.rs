@@ -513,47 +513,95 @@ impl CounterSigningSessionData {
     }
 
     /// Get the agent state for a specific agent.
+    /// Required signers are found by their position in the signing agents,
+    /// optional signers by the agent index of their optional response.
     pub fn agent_state_for_agent(
         &self,
         agent: &AgentPubKey,
     ) -> Result<&CounterSigningAgentState, CounterSigningError> {
-        match self
+        if let Some(agent_index) = self
             .preflight_request
             .signing_agents
             .iter()
             .position(|(pubkey, _)| pubkey == agent)
         {
-            Some(agent_index) => match self.responses.get(agent_index) {
+            return match self.responses.get(agent_index) {
                 Some((agent_state, _)) => Ok(agent_state),
                 None => Err(CounterSigningError::AgentIndexOutOfBounds),
-            },
+            };
+        }
+        match self
+            .preflight_request
+            .optional_signing_agents
+            .iter()
+            .position(|(pubkey, _)| pubkey == agent)
+        {
+            Some(agent_index) => self
+                .optional_responses
+                .iter()
+                .find(|(agent_state, _)| *agent_state.agent_index() as usize == agent_index)
+                .map(|(agent_state, _)| agent_state)
+                .ok_or(CounterSigningError::AgentIndexOutOfBounds),
             None => Err(CounterSigningError::AgentIndexOutOfBounds),
         }
     }
 
     /// Attempt to map countersigning session data to a set of actions.
     /// A given countersigning session always maps to the same ordered set of actions or an error.
+    /// The set is every required signer followed by every optional signer
+    /// with a response who is not also a required signer, i.e. the enzyme.
     /// Note the actions are not signed as the intent is to build actions for other agents without their private keys.
     pub fn build_action_set(
         &self,
         entry_hash: EntryHash,
         weight: EntryRateWeight,
     ) -> Result<Vec<Action>, CounterSigningError> {
-        let mut actions = vec![];
-        let mut build_actions = |countersigning_agents: &CounterSigningAgents| -> Result<(), _> {
-            for (agent, _role) in countersigning_agents.iter() {
-                actions.push(Action::from_countersigning_data(
+        let mut actions = self.build_required_action_set(entry_hash.clone(), weight.clone())?;
+        for action in self.build_optional_action_set(entry_hash, weight)? {
+            if !self.signing_agents().any(|agent| agent == action.author()) {
+                actions.push(action);
+            }
+        }
+        Ok(actions)
+    }
+
+    /// The actions of the required signers, all of which must be authored
+    /// for the session to complete.
+    pub fn build_required_action_set(
+        &self,
+        entry_hash: EntryHash,
+        weight: EntryRateWeight,
+    ) -> Result<Vec<Action>, CounterSigningError> {
+        self.signing_agents()
+            .map(|agent| {
+                Action::from_countersigning_data(
                     entry_hash.clone(),
                     self,
                     agent.clone(),
                     weight.clone(),
-                )?);
-            }
-            Ok(())
-        };
-        build_actions(&self.preflight_request.signing_agents)?;
-        build_actions(&self.preflight_request.optional_signing_agents)?;
-        Ok(actions)
+                )
+            })
+            .collect()
+    }
+
+    /// The actions of the optional signers which have a response in this
+    /// session, at least `minimum_optional_signing_agents` of which must be
+    /// authored for the session to complete.
+    pub fn build_optional_action_set(
+        &self,
+        entry_hash: EntryHash,
+        weight: EntryRateWeight,
+    ) -> Result<Vec<Action>, CounterSigningError> {
+        self.optional_signing_agents()
+            .map(|agent| {
+                Action::from_countersigning_data(
+                    entry_hash.clone(),
+                    self,
+                    agent.clone(),
+                    weight.clone(),
+                )
+            })
+            .collect()
     }
 
     /// Fallible constructor.
@@ -573,7 +621,36 @@ impl CounterSigningSessionData {
 
     /// Combines all integrity checks.
     pub fn check_integrity(&self) -> Result<(), CounterSigningError> {
-        self.check_responses_indexes()
+        self.check_responses_indexes()?;
+        self.check_optional_responses()
+    }
+
+    /// Check that there are enough optional responses to meet the minimum
+    /// and that each one is for a distinct optional signing agent, in the
+    /// order of the optional signing agents.
+    pub fn check_optional_responses(&self) -> Result<(), CounterSigningError> {
+        let minimum = self.preflight_request().minimum_optional_signing_agents;
+        if self.optional_responses().len() < minimum as usize {
+            return Err(
+                CounterSigningError::CounterSigningSessionOptionalResponsesLength(
+                    self.optional_responses().len(),
+                    minimum,
+                ),
+            );
+        }
+        let mut previous_index = None;
+        for (i, (response, _response_signature)) in self.optional_responses().iter().enumerate() {
+            let index = *response.agent_index();
+            if index as usize >= self.preflight_request().optional_signing_agents.len()
+                || matches!(previous_index, Some(previous) if index <= previous)
+            {
+                return Err(
+                    CounterSigningError::CounterSigningSessionOptionalResponsesOrder(index, i),
+                );
+            }
+            previous_index = Some(index);
+        }
+        Ok(())
     }
 
     /// Check that the countersigning session data responses all have the
@@ -630,6 +707,30 @@ impl CounterSigningSessionData {
     pub fn responses_mut(&mut self) -> &mut Vec<(CounterSigningAgentState, Signature)> {
         &mut self.responses
     }
+
+    /// Get the optional agents which responded to this session, in the
+    /// order of their responses.
+    pub fn optional_signing_agents(&self) -> impl Iterator<Item = &AgentPubKey> {
+        self.optional_responses
+            .iter()
+            .filter_map(|(agent_state, _)| {
+                self.preflight_request
+                    .optional_signing_agents
+                    .get(*agent_state.agent_index() as usize)
+                    .map(|(a, _)| a)
+            })
+    }
+
+    /// Accessor to optional responses.
+    pub fn optional_responses(&self) -> &Vec<(CounterSigningAgentState, Signature)> {
+        &self.optional_responses
+    }
+
+    /// Mutable optional responses accessor for testing.
+    #[cfg(feature = "test_utils")]
+    pub fn optional_responses_mut(&mut self) -> &mut Vec<(CounterSigningAgentState, Signature)> {
+        &mut self.optional_responses
+    }
 }
 
 #[cfg(test)]
@@ -638,11 +739,14 @@ pub mod test {
     use crate::CounterSigningSessionData;
     use crate::Signature;
     use holo_hash::AgentPubKey;
+    use holo_hash::EntryHash;
 
     use super::CounterSigningError;
     use super::CounterSigningSessionTimes;
     use super::PreflightRequest;
     use super::SESSION_ACTION_TIME_OFFSET;
+    use crate::Action;
+    use crate::EntryRateWeight;
     use crate::Role;
     use arbitrary::Arbitrary;
 
@@ -905,4 +1009,148 @@ pub mod test {
         (*session_data.responses_mut()).push((bob_state, bob_signature));
         assert_eq!(session_data.check_responses_indexes().unwrap(), (),);
     }
+
+    #[test]
+    pub fn test_check_countersigning_session_data_optional_responses() {
+        let mut u = arbitrary::Unstructured::new(&[0; 1000]);
+        let mut session_data = CounterSigningSessionData::arbitrary(&mut u).unwrap();
+
+        let data: Vec<_> = (0u8..255).cycle().take(100000).collect();
+        let mut uk = arbitrary::Unstructured::new(&data);
+        let alice = AgentPubKey::arbitrary(&mut uk).unwrap();
+        let bob = AgentPubKey::arbitrary(&mut uk).unwrap();
+        let carol = AgentPubKey::arbitrary(&mut uk).unwrap();
+
+        // No optional agents and no optional responses is a pass.
+        assert_eq!(session_data.check_optional_responses().unwrap(), ());
+
+        // 2 of 3 optional agents without any responses is a fail.
+        session_data.preflight_request_mut().optional_signing_agents = vec![
+            (alice.clone(), vec![]),
+            (bob.clone(), vec![]),
+            (carol.clone(), vec![]),
+        ];
+        session_data
+            .preflight_request_mut()
+            .minimum_optional_signing_agents = 2;
+        assert!(matches!(
+            session_data.check_optional_responses(),
+            Err(CounterSigningError::CounterSigningSessionOptionalResponsesLength(_, _))
+        ));
+
+        // Two responses for the same optional agent is a fail.
+        let mut alice_state = CounterSigningAgentState::arbitrary(&mut u).unwrap();
+        *alice_state.agent_index_mut() = 0;
+        let mut carol_state = alice_state.clone();
+        let signature = Signature::arbitrary(&mut u).unwrap();
+        (*session_data.optional_responses_mut()).push((alice_state.clone(), signature.clone()));
+        (*session_data.optional_responses_mut()).push((carol_state.clone(), signature.clone()));
+        assert!(matches!(
+            session_data.check_optional_responses(),
+            Err(CounterSigningError::CounterSigningSessionOptionalResponsesOrder(0, 1))
+        ));
+
+        // A response for an agent that is not an optional agent is a fail.
+        *carol_state.agent_index_mut() = 3;
+        (*session_data.optional_responses_mut()).pop();
+        (*session_data.optional_responses_mut()).push((carol_state.clone(), signature.clone()));
+        assert!(matches!(
+            session_data.check_optional_responses(),
+            Err(CounterSigningError::CounterSigningSessionOptionalResponsesOrder(3, 1))
+        ));
+
+        // Responses from 2 of the 3 optional agents is a pass.
+        *carol_state.agent_index_mut() = 2;
+        (*session_data.optional_responses_mut()).pop();
+        (*session_data.optional_responses_mut()).push((carol_state, signature));
+        assert_eq!(session_data.check_optional_responses().unwrap(), ());
+        assert_eq!(
+            session_data.optional_signing_agents().collect::<Vec<_>>(),
+            vec![&alice, &carol]
+        );
+    }
+
+    #[test]
+    pub fn test_countersigning_session_data_optional_action_set() {
+        let mut u = arbitrary::Unstructured::new(&[0; 1000]);
+        let mut session_data = CounterSigningSessionData::arbitrary(&mut u).unwrap();
+
+        let data: Vec<_> = (0u8..255).cycle().take(100000).collect();
+        let mut uk = arbitrary::Unstructured::new(&data);
+        let alice = AgentPubKey::arbitrary(&mut uk).unwrap();
+        let bob = AgentPubKey::arbitrary(&mut uk).unwrap();
+        let carol = AgentPubKey::arbitrary(&mut uk).unwrap();
+        let dave = AgentPubKey::arbitrary(&mut uk).unwrap();
+        let signature = Signature::arbitrary(&mut u).unwrap();
+        let mut state = |index: u8| {
+            let mut state = CounterSigningAgentState::arbitrary(&mut u).unwrap();
+            *state.agent_index_mut() = index;
+            state
+        };
+
+        // Alice is the enzyme so she is the first of both the required and
+        // optional agents.
+        let alice_state = state(0);
+        let bob_state = state(1);
+        let alice_optional_state = alice_state.clone();
+        let carol_state = state(1);
+        session_data.preflight_request_mut().signing_agents =
+            vec![(alice.clone(), vec![]), (bob.clone(), vec![])];
+        session_data.preflight_request_mut().optional_signing_agents = vec![
+            (alice.clone(), vec![]),
+            (carol.clone(), vec![]),
+            (dave.clone(), vec![]),
+        ];
+        session_data
+            .preflight_request_mut()
+            .minimum_optional_signing_agents = 2;
+        *session_data.responses_mut() = vec![
+            (alice_state.clone(), signature.clone()),
+            (bob_state, signature.clone()),
+        ];
+        *session_data.optional_responses_mut() = vec![
+            (alice_optional_state, signature.clone()),
+            (carol_state.clone(), signature),
+        ];
+        assert_eq!(session_data.check_integrity().unwrap(), ());
+
+        // The optional agents take their state from their optional response.
+        assert_eq!(
+            session_data.agent_state_for_agent(&carol).unwrap(),
+            &carol_state
+        );
+        assert_eq!(
+            session_data.agent_state_for_agent(&alice).unwrap(),
+            &alice_state
+        );
+        assert!(matches!(
+            session_data.agent_state_for_agent(&dave),
+            Err(CounterSigningError::AgentIndexOutOfBounds)
+        ));
+
+        let entry_hash = EntryHash::from_raw_36(vec![0; 36]);
+        let weight = EntryRateWeight::default();
+        let authors = |actions: Vec<Action>| -> Vec<AgentPubKey> {
+            actions.iter().map(|a| a.author().clone()).collect()
+        };
+
+        // The enzyme is only in the action set once and dave, who did not
+        // respond, is not in it at all.
+        assert_eq!(
+            authors(
+                session_data
+                    .build_action_set(entry_hash.clone(), weight.clone())
+                    .unwrap()
+            ),
+            vec![alice.clone(), bob.clone(), carol.clone()]
+        );
+        assert_eq!(
+            authors(
+                session_data
+                    .build_optional_action_set(entry_hash, weight)
+                    .unwrap()
+            ),
+            vec![alice, carol]
+        );
+    }
 }
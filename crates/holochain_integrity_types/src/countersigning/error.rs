@@ -11,6 +11,10 @@ pub enum CounterSigningError {
     CounterSigningSessionResponsesLength(usize, usize),
     /// Session response agents all need to be in the correct positions.
     CounterSigningSessionResponsesOrder(u8, usize),
+    /// Session optional responses must meet the minimum optional signing agents.
+    CounterSigningSessionOptionalResponsesLength(usize, u8),
+    /// Session optional responses must be for distinct optional agents, in order.
+    CounterSigningSessionOptionalResponsesOrder(u8, usize),
     /// Enzyme must match for required and optional signers if set.
     EnzymeMismatch(
         Option<(holo_hash::AgentPubKey, Vec<Role>)>,
@@ -53,6 +57,17 @@ impl core::fmt::Display for CounterSigningError {
                     "The countersigning session response with agent index {} was found in index position {}",
                     index, pos
             ),
+            CounterSigningError::CounterSigningSessionOptionalResponsesLength(resp, min) => {
+                write!(f,
+                    "The countersigning session optional responses ({}) are fewer than the minimum optional signing agents ({})",
+                    resp,
+                    min
+                )
+            }
+            CounterSigningError::CounterSigningSessionOptionalResponsesOrder(index, pos) => write!(f,
+                    "The countersigning session optional response with agent index {} was found in index position {}",
+                    index, pos
+            ),
             CounterSigningError::EnzymeMismatch(required_signer, optional_signer) => write!(f,
                 "The enzyme is mismatche for required signer {:?} and optional signer {:?}",
                 required_signer, optional_signer
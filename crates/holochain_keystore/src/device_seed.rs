@@ -0,0 +1,232 @@
+//! Encryption of a device seed with a passphrase, so that it can be moved
+//! to another device and the agent keys derived from it restored there.
+
+use kitsune_p2p_types::dependencies::lair_keystore_api;
+use lair_keystore_api::prelude::*;
+use std::sync::Arc;
+
+/// The lair tag of the seed agent keys are derived from.
+pub const DEVICE_SEED_TAG: &str = "hc_device_seed";
+
+/// The length of a seed, and of the key it is encrypted with.
+const SEED_BYTES: usize = 32;
+
+/// The most work an exported device seed may ask for to hash its passphrase,
+/// so that a crafted export can't exhaust the cpu or memory of the importer.
+const MAX_OPS_LIMIT: u32 = sodoken::hash::argon2id::OPSLIMIT_SENSITIVE;
+const MAX_MEM_LIMIT: u32 = sodoken::hash::argon2id::MEMLIMIT_SENSITIVE;
+
+/// A device seed encrypted with a key hashed from a passphrase.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct LockedDeviceSeed {
+    salt: [u8; sodoken::hash::argon2id::SALTBYTES],
+    ops_limit: u32,
+    mem_limit: u32,
+    nonce: [u8; sodoken::secretbox::xsalsa20poly1305::NONCEBYTES],
+    #[serde(with = "serde_bytes")]
+    cipher: Vec<u8>,
+}
+
+/// The lair tag of the seed derived from the device seed at this path.
+pub(crate) fn derived_seed_tag(derivation_path: &[u32]) -> String {
+    derivation_path
+        .iter()
+        .fold(DEVICE_SEED_TAG.to_string(), |tag, index| {
+            format!("{}/{}", tag, index)
+        })
+}
+
+/// Encrypt a seed with the given passphrase, hashed with the given limits.
+pub(crate) async fn lock_device_seed(
+    seed: sodoken::BufRead,
+    passphrase: sodoken::BufRead,
+    limits: PwHashLimits,
+) -> LairResult<Vec<u8>> {
+    let (ops_limit, mem_limit) = (limits.as_ops_limit(), limits.as_mem_limit());
+
+    let salt = sodoken::BufWriteSized::new_no_lock();
+    sodoken::random::bytes_buf(salt.clone()).await?;
+    let salt = salt.to_read_sized();
+
+    let key = sodoken::BufWriteSized::<SEED_BYTES>::new_mem_locked()?;
+    sodoken::hash::argon2id::hash(key.clone(), passphrase, salt.clone(), ops_limit, mem_limit)
+        .await?;
+
+    let nonce = sodoken::BufWriteSized::new_no_lock();
+    sodoken::random::bytes_buf(nonce.clone()).await?;
+    let nonce = nonce.to_read_sized();
+
+    let cipher = sodoken::secretbox::xsalsa20poly1305::easy(nonce.clone(), seed, key).await?;
+
+    let locked = LockedDeviceSeed {
+        salt: *salt.read_lock_sized(),
+        ops_limit,
+        mem_limit,
+        nonce: *nonce.read_lock_sized(),
+        cipher: cipher.read_lock().to_vec(),
+    };
+    holochain_serialized_bytes::encode(&locked).map_err(one_err::OneErr::new)
+}
+
+/// Decrypt a seed encrypted by [`lock_device_seed`] with the given passphrase.
+pub(crate) async fn unlock_device_seed(
+    locked: &[u8],
+    passphrase: sodoken::BufRead,
+) -> LairResult<sodoken::BufReadSized<SEED_BYTES>> {
+    let locked: LockedDeviceSeed =
+        holochain_serialized_bytes::decode(locked).map_err(one_err::OneErr::new)?;
+    if locked.cipher.len() != SEED_BYTES + sodoken::secretbox::xsalsa20poly1305::MACBYTES {
+        return Err("Bad Seed Length".into());
+    }
+    if locked.ops_limit > MAX_OPS_LIMIT || locked.mem_limit > MAX_MEM_LIMIT {
+        return Err("The device seed asks for too much work to hash its passphrase".into());
+    }
+
+    let key = sodoken::BufWriteSized::<SEED_BYTES>::new_mem_locked()?;
+    sodoken::hash::argon2id::hash(
+        key.clone(),
+        passphrase,
+        locked.salt,
+        locked.ops_limit,
+        locked.mem_limit,
+    )
+    .await?;
+
+    let seed = sodoken::BufWriteSized::<SEED_BYTES>::new_mem_locked()?;
+    sodoken::secretbox::xsalsa20poly1305::open_easy(locked.nonce, seed.clone(), locked.cipher, key)
+        .await
+        .map_err(|_| {
+            one_err::OneErr::new("Could not decrypt the device seed, wrong passphrase?")
+        })?;
+    Ok(seed.to_read_sized())
+}
+
+/// Box a seed from its own x25519 key, derived as lair derives it, to a key
+/// held by lair, which is how lair expects seeds to be imported.
+/// Returns the seed's x25519 pub key, the nonce and the cipher.
+pub(crate) async fn box_seed_for_import(
+    seed: sodoken::BufReadSized<SEED_BYTES>,
+    recipient_pub_key: X25519PubKey,
+) -> LairResult<(X25519PubKey, [u8; 24], Arc<[u8]>)> {
+    let pub_key = sodoken::BufWriteSized::new_no_lock();
+    let sec_key = sodoken::BufWriteSized::new_mem_locked()?;
+    sodoken::crypto_box::curve25519xchacha20poly1305::seed_keypair(
+        pub_key.clone(),
+        sec_key.clone(),
+        seed.clone(),
+    )
+    .await?;
+
+    let nonce = sodoken::BufWriteSized::new_no_lock();
+    sodoken::random::bytes_buf(nonce.clone()).await?;
+    let nonce = nonce.to_read_sized();
+
+    let cipher = sodoken::crypto_box::curve25519xsalsa20poly1305::easy(
+        nonce.clone(),
+        seed,
+        recipient_pub_key.cloned_inner(),
+        sec_key.to_read_sized(),
+    )
+    .await?;
+
+    let pub_key = *pub_key.to_read_sized().read_lock_sized();
+    let nonce = *nonce.read_lock_sized();
+    let cipher = cipher.read_lock().to_vec().into();
+    Ok((pub_key.into(), nonce, cipher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn lock_and_unlock_device_seed() {
+        let seed: sodoken::BufRead = vec![7; SEED_BYTES].into();
+        let locked = lock_device_seed(
+            seed,
+            b"correct horse".to_vec().into(),
+            PwHashLimits::Minimum,
+        )
+        .await
+        .unwrap();
+
+        let unlocked = unlock_device_seed(&locked, b"correct horse".to_vec().into())
+            .await
+            .unwrap();
+        assert_eq!(*unlocked.read_lock_sized(), [7; SEED_BYTES]);
+
+        assert!(unlock_device_seed(&locked, b"wrong horse".to_vec().into())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unlock_rejects_excessive_hash_limits() {
+        let seed: sodoken::BufRead = vec![7; SEED_BYTES].into();
+        let locked = lock_device_seed(
+            seed,
+            b"correct horse".to_vec().into(),
+            PwHashLimits::Minimum,
+        )
+        .await
+        .unwrap();
+        let mut locked: LockedDeviceSeed = holochain_serialized_bytes::decode(&locked).unwrap();
+        locked.mem_limit = u32::MAX;
+        let locked = holochain_serialized_bytes::encode(&locked).unwrap();
+
+        assert!(
+            unlock_device_seed(&locked, b"correct horse".to_vec().into())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn derived_agent_keys_are_restored_from_exported_device_seed() {
+        let keystore = crate::spawn_mem_keystore().await.unwrap();
+        let key_a = keystore.derive_agent_pub_key(vec![0, 1]).await.unwrap();
+        let key_b = keystore.derive_agent_pub_key(vec![0, 2]).await.unwrap();
+        assert_ne!(key_a, key_b);
+        // Deriving again with the same path gives the same key.
+        assert_eq!(
+            key_a,
+            keystore.derive_agent_pub_key(vec![0, 1]).await.unwrap()
+        );
+        assert!(keystore.derive_agent_pub_key(vec![]).await.is_err());
+
+        let locked = PwHashLimits::Minimum
+            .with_exec(|| keystore.export_device_seed(b"passphrase".to_vec().into()))
+            .await
+            .unwrap();
+
+        let other_keystore = crate::spawn_mem_keystore().await.unwrap();
+        assert!(other_keystore
+            .import_device_seed(locked.clone(), b"not the passphrase".to_vec().into())
+            .await
+            .is_err());
+        other_keystore
+            .import_device_seed(locked.clone(), b"passphrase".to_vec().into())
+            .await
+            .unwrap();
+        assert_eq!(
+            key_b,
+            other_keystore
+                .derive_agent_pub_key(vec![0, 2])
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            key_a,
+            other_keystore
+                .derive_agent_pub_key(vec![0, 1])
+                .await
+                .unwrap()
+        );
+
+        // A keystore with a device seed can't import another one.
+        assert!(keystore
+            .import_device_seed(locked, b"passphrase".to_vec().into())
+            .await
+            .is_err());
+    }
+}
@@ -40,6 +40,9 @@ pub use meta_lair_client::*;
 mod agent_pubkey_ext;
 pub use agent_pubkey_ext::*;
 
+mod device_seed;
+pub use device_seed::DEVICE_SEED_TAG;
+
 pub mod crude_mock_keystore;
 pub mod lair_keystore;
 pub mod test_keystore;
@@ -169,6 +169,140 @@ impl MetaLairClient {
         }
     }
 
+    /// Derive a signature keypair from the device seed with the given
+    /// derivation path, e.g. one index for the app and one for the DNA.
+    /// The device seed is created if this keystore doesn't have one yet.
+    /// The same device seed and path always derive the same keypair, so
+    /// deriving again returns the existing key.
+    pub fn derive_agent_pub_key(
+        &self,
+        derivation_path: Vec<u32>,
+    ) -> impl Future<Output = LairResult<holo_hash::AgentPubKey>> + 'static + Send {
+        let (client, esnd) = self.cli();
+        async move {
+            if derivation_path.is_empty() {
+                return Err("The derivation path must not be empty".into());
+            }
+            let device_tag: Arc<str> = crate::DEVICE_SEED_TAG.into();
+            // don't echk! these lookups, it is valid for the entries to be missing
+            if client.get_entry(device_tag.clone()).await.is_err() {
+                // the device seed must be exportable to be backed up
+                echk!(esnd, client.new_seed(device_tag.clone(), None, true).await);
+            }
+            let tag: Arc<str> = crate::device_seed::derived_seed_tag(&derivation_path).into();
+            let info = match client.get_entry(tag.clone()).await {
+                Ok(LairEntryInfo::Seed { seed_info, .. }) => seed_info,
+                Ok(oth) => {
+                    return Err(format!("invalid entry type, expecting seed: {:?}", oth).into())
+                }
+                Err(_) => echk!(
+                    esnd,
+                    client
+                        .derive_seed(
+                            device_tag,
+                            None,
+                            tag,
+                            None,
+                            derivation_path.into_boxed_slice(),
+                        )
+                        .await
+                ),
+            };
+            Ok(holo_hash::AgentPubKey::from_raw_32(
+                info.ed25519_pub_key.0.to_vec(),
+            ))
+        }
+    }
+
+    /// Export the device seed, encrypted with the given passphrase.
+    /// Importing it on another device with [`Self::import_device_seed`]
+    /// restores every agent key derived from it.
+    /// Respects hc_seed_bundle::PwHashLimits.
+    pub fn export_device_seed(
+        &self,
+        passphrase: sodoken::BufRead,
+    ) -> impl Future<Output = LairResult<Vec<u8>>> + 'static + Send {
+        let (client, esnd) = self.cli();
+        let limits = PwHashLimits::current();
+        async move {
+            let device_tag: Arc<str> = crate::DEVICE_SEED_TAG.into();
+            let x25519_pub_key = match echk!(esnd, client.get_entry(device_tag.clone()).await) {
+                LairEntryInfo::Seed { seed_info, .. } => seed_info.x25519_pub_key,
+                oth => return Err(format!("invalid entry type, expecting seed: {:?}", oth).into()),
+            };
+            // box the seed to the device seed's own encryption key,
+            // which lair can open for us again
+            let (nonce, cipher) = echk!(
+                esnd,
+                client
+                    .export_seed_by_tag(
+                        device_tag,
+                        x25519_pub_key.clone(),
+                        x25519_pub_key.clone(),
+                        None,
+                    )
+                    .await
+            );
+            let seed = echk!(
+                esnd,
+                client
+                    .crypto_box_xsalsa_open_by_pub_key(
+                        x25519_pub_key.clone(),
+                        x25519_pub_key,
+                        None,
+                        nonce,
+                        cipher,
+                    )
+                    .await
+            );
+            crate::device_seed::lock_device_seed(seed.into(), passphrase, limits).await
+        }
+    }
+
+    /// Import a device seed exported by [`Self::export_device_seed`].
+    /// Fails if this keystore already has a device seed.
+    pub fn import_device_seed(
+        &self,
+        locked: Vec<u8>,
+        passphrase: sodoken::BufRead,
+    ) -> impl Future<Output = LairResult<()>> + 'static + Send {
+        let (client, esnd) = self.cli();
+        async move {
+            let device_tag: Arc<str> = crate::DEVICE_SEED_TAG.into();
+            if client.get_entry(device_tag.clone()).await.is_ok() {
+                return Err("This keystore already has a device seed".into());
+            }
+            let seed = crate::device_seed::unlock_device_seed(&locked, passphrase).await?;
+            // lair only imports seeds boxed to one of its encryption keys,
+            // the connection check stub is always there to receive it
+            let stub_tag: Arc<str> = CON_CHECK_STUB_TAG.into();
+            let recipient_pub_key = match client.get_entry(stub_tag.clone()).await {
+                Ok(LairEntryInfo::Seed { seed_info, .. }) => seed_info.x25519_pub_key,
+                Ok(oth) => {
+                    return Err(format!("invalid entry type, expecting seed: {:?}", oth).into())
+                }
+                Err(_) => echk!(esnd, client.new_seed(stub_tag, None, false).await).x25519_pub_key,
+            };
+            let (sender_pub_key, nonce, cipher) =
+                crate::device_seed::box_seed_for_import(seed, recipient_pub_key.clone()).await?;
+            echk!(
+                esnd,
+                client
+                    .import_seed(
+                        sender_pub_key,
+                        recipient_pub_key,
+                        None,
+                        nonce,
+                        cipher,
+                        device_tag,
+                        true,
+                    )
+                    .await
+            );
+            Ok(())
+        }
+    }
+
     /// Generate a new signature for given keypair / data
     pub fn sign(
         &self,
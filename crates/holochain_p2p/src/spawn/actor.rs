@@ -1434,6 +1434,19 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    fn handle_set_gossip_bandwidth(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    ) -> HolochainP2pHandlerResult<()> {
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            kitsune_p2p.set_gossip_bandwidth(tuning_params).await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     fn handle_get_diagnostics(
         &mut self,
         dna_hash: DnaHash,
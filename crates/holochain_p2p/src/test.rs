@@ -172,6 +172,13 @@ impl HolochainP2pHandler for StubNetwork {
         Err("stub".into())
     }
 
+    fn handle_set_gossip_bandwidth(
+        &mut self,
+        _tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+
     fn handle_get_diagnostics(
         &mut self,
         dna_hash: DnaHash,
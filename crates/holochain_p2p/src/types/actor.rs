@@ -364,6 +364,11 @@ ghost_actor::ghost_chan! {
 
         /// Get struct for diagnostic data
         fn get_diagnostics(dna_hash: DnaHash) -> KitsuneDiagnostics;
+
+        /// Replace the gossip bandwidth limits with those in these tuning params.
+        fn set_gossip_bandwidth(
+            tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+        ) -> ();
     }
 }
 
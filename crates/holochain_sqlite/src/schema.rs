@@ -25,6 +25,26 @@ pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| Schema {
             forward: include_str!("sql/cell/schema/1-up.sql").into(),
             _schema: include_str!("sql/cell/schema/1.sql").into(),
         },
+        M {
+            forward: include_str!("sql/cell/schema/2-up.sql").into(),
+            _schema: include_str!("sql/cell/schema/2.sql").into(),
+        },
+        M {
+            forward: include_str!("sql/cell/schema/3-up.sql").into(),
+            _schema: include_str!("sql/cell/schema/3.sql").into(),
+        },
+        M {
+            forward: include_str!("sql/cell/schema/4-up.sql").into(),
+            _schema: include_str!("sql/cell/schema/4.sql").into(),
+        },
+        M {
+            forward: include_str!("sql/cell/schema/5-up.sql").into(),
+            _schema: include_str!("sql/cell/schema/5.sql").into(),
+        },
+        M {
+            forward: include_str!("sql/cell/schema/6-up.sql").into(),
+            _schema: include_str!("sql/cell/schema/6.sql").into(),
+        },
     ],
 });
 
@@ -35,6 +55,14 @@ pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| Schema {
             forward: include_str!("sql/conductor/schema/1.sql").into(),
             _schema: "".into(),
         },
+        M {
+            forward: include_str!("sql/conductor/schema/2.sql").into(),
+            _schema: "".into(),
+        },
+        M {
+            forward: include_str!("sql/conductor/schema/3.sql").into(),
+            _schema: "".into(),
+        },
     ],
 });
 
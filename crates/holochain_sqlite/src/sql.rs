@@ -67,6 +67,14 @@ pub mod sql_conductor {
         include_str!("sql/conductor/select_valid_cap_grant_for_cap_secret.sql");
     pub const SELECT_VALID_UNRESTRICTED_CAP_GRANT: &str =
         include_str!("sql/conductor/select_valid_unrestricted_cap_grant.sql");
+    pub const USE_CAP_GRANT: &str = include_str!("sql/conductor/use_cap_grant.sql");
+    pub const SELECT_CAP_GRANT_USES: &str = include_str!("sql/conductor/select_cap_grant_uses.sql");
+    pub const SELECT_CAP_GRANTS_WITH_USES: &str =
+        include_str!("sql/conductor/select_cap_grants_with_uses.sql");
+    pub const DELETE_CAP_GRANT_USES: &str = include_str!("sql/conductor/delete_cap_grant_uses.sql");
+    pub const DELETE_EXPIRED_CAP_GRANT_USES: &str =
+        include_str!("sql/conductor/delete_expired_cap_grant_uses.sql");
+    pub const SELECT_LIVE_CAP_GRANT: &str = include_str!("sql/conductor/select_live_cap_grant.sql");
 }
 
 pub(crate) mod sql_p2p_agent_store {
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS FetchPoolItem (
+  key BLOB PRIMARY KEY ON CONFLICT REPLACE,
+  blob BLOB NOT NULL
+);
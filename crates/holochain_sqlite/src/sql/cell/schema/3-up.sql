@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS CountersigningSession (
+  author BLOB PRIMARY KEY ON CONFLICT REPLACE,
+  -- The serialized PreflightRequest which the author accepted
+  preflight_request BLOB NOT NULL,
+  -- The expiration time of the session as a Timestamp (microseconds)
+  expires_at_timestamp INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS CountersigningSessionOp (
+  hash BLOB PRIMARY KEY ON CONFLICT IGNORE,
+  -- The hash of the countersigned entry this op belongs to
+  entry_hash BLOB NOT NULL,
+  -- The expiration time of the session as a Timestamp (microseconds)
+  expires_at_timestamp INTEGER NOT NULL,
+  -- The serialized DhtOp
+  blob BLOB NOT NULL
+);
@@ -0,0 +1,7 @@
+-- The target of a CreateLink, to filter links by the author of what they link.
+-- Links written before this migration only have the target in their blob,
+-- from which the conductor fills it in at startup.
+ALTER TABLE
+  Action
+ADD
+  COLUMN target_hash BLOB NULL;
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS ChainFork (
+  author BLOB NOT NULL,
+  fork_seq INTEGER NOT NULL,
+  -- The hashes of two actions by the author at fork_seq
+  first_action BLOB NOT NULL,
+  second_action BLOB NOT NULL,
+  PRIMARY KEY (author, fork_seq) ON CONFLICT IGNORE
+);
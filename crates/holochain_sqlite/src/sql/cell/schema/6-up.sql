@@ -0,0 +1,6 @@
+-- The time a CapGrant expires, to filter out expired grants.
+-- Grants written before this migration only have their expiry in their blob.
+ALTER TABLE
+  Entry
+ADD
+  COLUMN cap_expires_at INTEGER NULL;
@@ -0,0 +1,6 @@
+DELETE FROM
+  CapGrantUse
+WHERE
+  dna_hash = :dna_hash
+  AND agent = :agent
+  AND grant_hash = :grant_hash
@@ -0,0 +1,4 @@
+DELETE FROM
+  CapGrantUse
+WHERE
+  expires_at <= :now
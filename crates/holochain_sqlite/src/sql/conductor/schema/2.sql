@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS CapGrantUse (
+  -- the cell which committed the grant
+  dna_hash BLOB NOT NULL,
+  agent BLOB NOT NULL,
+  -- the entry hash of the grant
+  grant_hash BLOB NOT NULL,
+  uses INTEGER NOT NULL,
+  -- when the grant expires, if it does
+  expires_at INTEGER NULL,
+  PRIMARY KEY (dna_hash, agent, grant_hash)
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS RemoteSignal (
+  id INTEGER PRIMARY KEY,
+  -- the cell which sent the signal
+  dna_hash BLOB NOT NULL,
+  agent BLOB NOT NULL,
+  to_agent BLOB NOT NULL,
+  zome_name TEXT NOT NULL,
+  signal BLOB NOT NULL,
+  queued_at INTEGER NOT NULL,
+  attempts INTEGER NOT NULL,
+  delivered_at INTEGER NULL
+);
+CREATE INDEX IF NOT EXISTS remote_signal_sender_idx ON RemoteSignal(dna_hash, agent);
+CREATE INDEX IF NOT EXISTS remote_signal_queued_at_idx ON RemoteSignal(queued_at);
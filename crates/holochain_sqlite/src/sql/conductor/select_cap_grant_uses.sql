@@ -0,0 +1,8 @@
+SELECT
+  uses
+FROM
+  CapGrantUse
+WHERE
+  dna_hash = :dna_hash
+  AND agent = :agent
+  AND grant_hash = :grant_hash
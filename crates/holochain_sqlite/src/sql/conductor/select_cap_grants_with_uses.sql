@@ -0,0 +1,7 @@
+SELECT
+  grant_hash
+FROM
+  CapGrantUse
+WHERE
+  dna_hash = :dna_hash
+  AND agent = :agent
@@ -0,0 +1,18 @@
+SELECT
+  Entry.hash
+FROM
+  Entry
+WHERE
+  Entry.hash = ?1
+  AND (
+    SELECT
+      COUNT(Action.hash)
+    FROM
+      Action
+    WHERE
+      Action.author = ?2
+      AND (
+        Action.original_entry_hash = Entry.hash
+        OR Action.deletes_entry_hash = Entry.hash
+      )
+  ) = 0
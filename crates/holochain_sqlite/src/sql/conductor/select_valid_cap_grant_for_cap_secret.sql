@@ -16,3 +16,7 @@ WHERE
         OR Action.deletes_entry_hash = Entry.hash
       )
   ) = 0
+  AND (
+    Entry.cap_expires_at IS NULL
+    OR Entry.cap_expires_at > ?3
+  )
@@ -15,4 +15,8 @@ WHERE
         Action.original_entry_hash = Entry.hash
         OR Action.deletes_entry_hash = Entry.hash
       )
-  ) = 0;
+  ) = 0
+  AND (
+    Entry.cap_expires_at IS NULL
+    OR Entry.cap_expires_at > ?3
+  );
@@ -0,0 +1,9 @@
+INSERT INTO
+  CapGrantUse (dna_hash, agent, grant_hash, uses, expires_at)
+VALUES
+  (:dna_hash, :agent, :grant_hash, 1, :expires_at) ON CONFLICT (dna_hash, agent, grant_hash) DO
+UPDATE
+SET
+  uses = uses + 1
+WHERE
+  uses < :max_uses
@@ -10,3 +10,15 @@ pub fn get_used_size(txn: Transaction) -> Result<usize, DatabaseError> {
     txn.query_row("select sum(pgsize - unused) from dbstat", (), |r| r.get(0))
         .map_err(DatabaseError::SqliteError)
 }
+
+/// The size of the pages in use, read from the page counts rather than by
+/// scanning every page like [`get_used_size`], so it's cheap for large databases.
+pub fn get_allocated_size(txn: Transaction) -> Result<usize, DatabaseError> {
+    txn.query_row(
+        "select (page_count - freelist_count) * page_size
+        from pragma_page_count(), pragma_freelist_count(), pragma_page_size()",
+        (),
+        |r| r.get(0),
+    )
+    .map_err(DatabaseError::SqliteError)
+}
@@ -0,0 +1,261 @@
+//! Counting the uses of capability grants with a limited number of uses.
+//!
+//! The uses of expired grants are forgotten as other uses are counted,
+//! and the uses of deleted grants by [`prune_cap_grant_uses`].
+
+use holochain_sqlite::db::ReadAccess;
+use holochain_sqlite::prelude::DatabaseResult;
+use holochain_sqlite::prelude::DbWrite;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::sql::sql_conductor;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::DbKindAuthored;
+use holochain_types::prelude::DbKindConductor;
+use holochain_types::prelude::EntryHash;
+use holochain_types::prelude::Timestamp;
+
+/// Count a use of the grant with this entry hash, committed by this cell,
+/// unless it has already been used `max_uses` times.
+/// The count is kept until the grant expires at `expires_at`, if it does.
+/// Returns whether the use was counted.
+pub async fn use_cap_grant(
+    db: &DbWrite<DbKindConductor>,
+    cell_id: CellId,
+    grant_hash: EntryHash,
+    max_uses: u32,
+    expires_at: Option<Timestamp>,
+) -> DatabaseResult<bool> {
+    if max_uses == 0 {
+        return Ok(false);
+    }
+    db.async_commit(move |txn| {
+        txn.execute(
+            sql_conductor::DELETE_EXPIRED_CAP_GRANT_USES,
+            named_params! {":now": Timestamp::now()},
+        )?;
+        let changed = txn.execute(
+            sql_conductor::USE_CAP_GRANT,
+            named_params! {
+                ":dna_hash": cell_id.dna_hash(),
+                ":agent": cell_id.agent_pubkey(),
+                ":grant_hash": grant_hash,
+                ":max_uses": max_uses,
+                ":expires_at": expires_at,
+            },
+        )?;
+        Ok(changed > 0)
+    })
+    .await
+}
+
+/// Forget the uses of the grants committed by this cell which have
+/// since been updated or deleted, as they can't be used any more.
+/// Returns the number of grants whose uses were forgotten.
+pub async fn prune_cap_grant_uses<Db: ReadAccess<DbKindAuthored>>(
+    db: &DbWrite<DbKindConductor>,
+    authored_db: &Db,
+    cell_id: CellId,
+) -> DatabaseResult<usize> {
+    let grant_hashes: Vec<EntryHash> = db
+        .async_reader({
+            let cell_id = cell_id.clone();
+            move |txn| {
+                let mut stmt = txn.prepare(sql_conductor::SELECT_CAP_GRANTS_WITH_USES)?;
+                let hashes = stmt
+                    .query_map(
+                        named_params! {
+                            ":dna_hash": cell_id.dna_hash(),
+                            ":agent": cell_id.agent_pubkey(),
+                        },
+                        |row| row.get(0),
+                    )?
+                    .collect::<Result<_, _>>()?;
+                DatabaseResult::Ok(hashes)
+            }
+        })
+        .await?;
+    if grant_hashes.is_empty() {
+        return Ok(0);
+    }
+
+    let dead: Vec<EntryHash> = authored_db
+        .async_reader({
+            let author = cell_id.agent_pubkey().clone();
+            move |txn| {
+                let mut dead = Vec::new();
+                for grant_hash in grant_hashes {
+                    let live = txn
+                        .query_row(
+                            sql_conductor::SELECT_LIVE_CAP_GRANT,
+                            params![grant_hash, author],
+                            |_| Ok(()),
+                        )
+                        .optional()?
+                        .is_some();
+                    if !live {
+                        dead.push(grant_hash);
+                    }
+                }
+                DatabaseResult::Ok(dead)
+            }
+        })
+        .await?;
+    if dead.is_empty() {
+        return Ok(0);
+    }
+
+    db.async_commit(move |txn| {
+        for grant_hash in &dead {
+            txn.execute(
+                sql_conductor::DELETE_CAP_GRANT_USES,
+                named_params! {
+                    ":dna_hash": cell_id.dna_hash(),
+                    ":agent": cell_id.agent_pubkey(),
+                    ":grant_hash": grant_hash,
+                },
+            )?;
+        }
+        Ok(dead.len())
+    })
+    .await
+}
+
+/// The number of times the grant with this entry hash,
+/// committed by this cell, has been used.
+pub async fn cap_grant_uses(
+    db: &DbWrite<DbKindConductor>,
+    cell_id: CellId,
+    grant_hash: EntryHash,
+) -> DatabaseResult<u32> {
+    db.async_reader(move |txn| {
+        Ok(txn
+            .query_row(
+                sql_conductor::SELECT_CAP_GRANT_USES,
+                named_params! {
+                    ":dna_hash": cell_id.dna_hash(),
+                    ":agent": cell_id.agent_pubkey(),
+                    ":grant_hash": grant_hash,
+                },
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations::insert_entry;
+    use crate::prelude::test_authored_db;
+    use crate::prelude::test_conductor_db;
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cap_grant_uses_are_limited() {
+        let db = test_conductor_db();
+        let cell_id = fixt!(CellId);
+        let grant_hash = fixt!(EntryHash);
+
+        assert_eq!(
+            cap_grant_uses(&db, cell_id.clone(), grant_hash.clone())
+                .await
+                .unwrap(),
+            0
+        );
+        for _ in 0..2 {
+            assert!(
+                use_cap_grant(&db, cell_id.clone(), grant_hash.clone(), 2, None)
+                    .await
+                    .unwrap()
+            );
+        }
+        assert!(
+            !use_cap_grant(&db, cell_id.clone(), grant_hash.clone(), 2, None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            cap_grant_uses(&db, cell_id.clone(), grant_hash.clone())
+                .await
+                .unwrap(),
+            2
+        );
+
+        // The same grant committed by another cell is counted separately.
+        let other_cell_id = CellId::new(cell_id.dna_hash().clone(), fixt!(AgentPubKey));
+        assert!(
+            use_cap_grant(&db, other_cell_id, grant_hash.clone(), 2, None)
+                .await
+                .unwrap()
+        );
+
+        // A grant with no uses can never be used.
+        assert!(!use_cap_grant(&db, cell_id, fixt!(EntryHash), 0, None)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn uses_of_expired_and_deleted_grants_are_forgotten() {
+        let db = test_conductor_db();
+        let authored_db = test_authored_db();
+        let cell_id = fixt!(CellId);
+
+        // Uses of an expired grant are forgotten when another use is counted.
+        let expired_hash = fixt!(EntryHash);
+        let expired = (Timestamp::now() - std::time::Duration::from_secs(1)).unwrap();
+        assert!(
+            use_cap_grant(&db, cell_id.clone(), expired_hash.clone(), 2, Some(expired))
+                .await
+                .unwrap()
+        );
+        let live = ZomeCallCapGrant::new(
+            "live".into(),
+            CapAccess::Unrestricted,
+            GrantedFunctions::All,
+        )
+        .with_max_uses(2);
+        let live_entry = Entry::CapGrant(live);
+        let live_hash = EntryHash::with_data_sync(&live_entry);
+        assert!(
+            use_cap_grant(&db, cell_id.clone(), live_hash.clone(), 2, None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            cap_grant_uses(&db, cell_id.clone(), expired_hash)
+                .await
+                .unwrap(),
+            0
+        );
+
+        // Uses of a grant which is no longer live are pruned.
+        let deleted_hash = fixt!(EntryHash);
+        assert!(
+            use_cap_grant(&db, cell_id.clone(), deleted_hash.clone(), 2, None)
+                .await
+                .unwrap()
+        );
+        authored_db
+            .to_db()
+            .test_commit(|txn| insert_entry(txn, &live_hash, &live_entry).unwrap());
+        assert_eq!(
+            prune_cap_grant_uses(&db, &authored_db.to_db(), cell_id.clone())
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            cap_grant_uses(&db, cell_id.clone(), deleted_hash)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(cap_grant_uses(&db, cell_id, live_hash).await.unwrap(), 1);
+    }
+}
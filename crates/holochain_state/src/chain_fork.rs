@@ -0,0 +1,88 @@
+//! Forks of source chains found by the authorities of their agent activity.
+
+use crate::mutations::StateMutationResult;
+use crate::query::StateQueryResult;
+use holo_hash::AgentPubKey;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::ChainFork;
+
+/// Record a fork of an author's chain.
+/// Only the first fork recorded at each sequence number is kept.
+pub fn insert_chain_fork(
+    txn: &Transaction,
+    author: &AgentPubKey,
+    fork: &ChainFork,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "
+        INSERT INTO ChainFork (author, fork_seq, first_action, second_action)
+        VALUES (:author, :fork_seq, :first_action, :second_action)
+        ",
+        named_params! {
+            ":author": author,
+            ":fork_seq": fork.fork_seq,
+            ":first_action": fork.first_action,
+            ":second_action": fork.second_action,
+        },
+    )?;
+    Ok(())
+}
+
+/// The earliest recorded fork of an author's chain.
+pub fn get_chain_fork(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateQueryResult<Option<ChainFork>> {
+    Ok(txn
+        .query_row(
+            "
+            SELECT fork_seq, first_action, second_action
+            FROM ChainFork
+            WHERE author = :author
+            ORDER BY fork_seq ASC
+            LIMIT 1
+            ",
+            named_params! {
+                ":author": author,
+            },
+            |row| {
+                Ok(ChainFork {
+                    fork_seq: row.get("fork_seq")?,
+                    first_action: row.get("first_action")?,
+                    second_action: row.get("second_action")?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::test_dht_db;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::*;
+
+    #[test]
+    fn the_earliest_fork_is_returned() {
+        let db = test_dht_db();
+        let author = fixt!(AgentPubKey);
+        let fork_at = |fork_seq| ChainFork {
+            fork_seq,
+            first_action: fixt!(ActionHash),
+            second_action: fixt!(ActionHash),
+        };
+        let first = fork_at(3);
+        db.to_db().test_commit(|txn| {
+            assert_eq!(get_chain_fork(txn, &author).unwrap(), None);
+            insert_chain_fork(txn, &author, &fork_at(5)).unwrap();
+            insert_chain_fork(txn, &author, &first).unwrap();
+            // A second fork at the same seq is ignored.
+            insert_chain_fork(txn, &author, &fork_at(3)).unwrap();
+            insert_chain_fork(txn, &fixt!(AgentPubKey), &fork_at(1)).unwrap();
+            assert_eq!(get_chain_fork(txn, &author).unwrap(), Some(first));
+        });
+    }
+}
@@ -1,7 +1,13 @@
 use crate::prelude::StateMutationResult;
+use crate::query::from_blob;
 use holo_hash::AgentPubKey;
+use holo_hash::DhtOpHash;
+use holo_hash::EntryHash;
 use holochain_sqlite::rusqlite::OptionalExtension;
 use holochain_sqlite::rusqlite::{named_params, Transaction};
+use holochain_types::countersigning::CountersigningSessionActions;
+use holochain_types::dht_op::DhtOp;
+use holochain_zome_types::PreflightRequest;
 use holochain_zome_types::Timestamp;
 
 /// True if the chain is currently locked for the given lock id.
@@ -66,3 +72,66 @@ pub fn is_lock_expired(
     // If there's no lock then it's expired.
     Ok(r.unwrap_or(true))
 }
+
+/// Get the preflight request of the countersigning session the author
+/// has locked their chain for, if that session hasn't expired.
+pub fn get_countersigning_session(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateMutationResult<Option<PreflightRequest>> {
+    let blob = txn
+        .query_row(
+            "
+            SELECT preflight_request
+            FROM CountersigningSession
+            WHERE author = :author
+            AND expires_at_timestamp >= :now
+            ",
+            named_params! {
+                ":author": author,
+                ":now": holochain_zome_types::Timestamp::now(),
+            },
+            |row| row.get("preflight_request"),
+        )
+        .optional()?;
+    Ok(blob.map(from_blob).transpose()?)
+}
+
+/// Get all the ops collected for countersigning sessions which haven't
+/// expired, along with the entry hash, expiry and actions of their session.
+#[allow(clippy::type_complexity)]
+pub fn get_countersigning_ops(
+    txn: &Transaction,
+) -> StateMutationResult<
+    Vec<(
+        EntryHash,
+        Timestamp,
+        DhtOpHash,
+        DhtOp,
+        CountersigningSessionActions,
+    )>,
+> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT entry_hash, expires_at_timestamp, hash, blob
+        FROM CountersigningSessionOp
+        WHERE expires_at_timestamp > :now
+        ",
+    )?;
+    let rows = stmt.query_and_then(
+        named_params! {
+            ":now": holochain_zome_types::Timestamp::now(),
+        },
+        |row| {
+            let (op, session_actions) = from_blob(row.get("blob")?)?;
+            StateMutationResult::Ok((
+                row.get("entry_hash")?,
+                row.get("expires_at_timestamp")?,
+                row.get("hash")?,
+                op,
+                session_actions,
+            ))
+        },
+    )?;
+    rows.collect()
+}
@@ -29,6 +29,8 @@
 
 #[allow(missing_docs)]
 pub mod block;
+pub mod cap_grant_use;
+pub mod chain_fork;
 pub mod chain_lock;
 #[allow(missing_docs)]
 pub mod dna_def;
@@ -40,6 +42,7 @@ pub mod nonce;
 #[allow(missing_docs)]
 pub mod prelude;
 pub mod query;
+pub mod remote_signal;
 pub mod schedule;
 pub mod scratch;
 #[allow(missing_docs)]
@@ -12,6 +12,8 @@ use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::types::Null;
 use holochain_sqlite::rusqlite::Transaction;
 use holochain_sqlite::sql::sql_conductor;
+use holochain_types::countersigning::CountersigningSessionActions;
+use holochain_types::dht_op::DhtOp;
 use holochain_types::dht_op::DhtOpLight;
 use holochain_types::dht_op::OpOrder;
 use holochain_types::dht_op::{DhtOpHashed, DhtOpType};
@@ -535,6 +537,37 @@ pub fn set_receipts_complete(
     Ok(())
 }
 
+/// Fill in the `target_hash` of the `CreateLink` actions written before the
+/// column was added, which only have their target in the action blob.
+/// Returns the number of actions filled in.
+pub fn backfill_link_target_hashes(txn: &mut Transaction) -> StateMutationResult<usize> {
+    let links = txn
+        .prepare("SELECT hash, blob FROM Action WHERE type = :create_link AND target_hash IS NULL")?
+        .query_map(
+            named_params! {":create_link": ActionType::CreateLink.as_sql()},
+            |row| {
+                Ok((
+                    row.get::<_, ActionHash>("hash")?,
+                    row.get::<_, Vec<u8>>("blob")?,
+                ))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut stmt =
+        txn.prepare("UPDATE Action SET target_hash = :target_hash WHERE hash = :hash")?;
+    let mut filled = 0;
+    for (hash, blob) in links {
+        let SignedAction(action, _) = from_blob(blob)?;
+        if let Action::CreateLink(create_link) = action {
+            filled += stmt.execute(named_params! {
+                ":hash": hash,
+                ":target_hash": create_link.target_address,
+            })?;
+        }
+    }
+    Ok(filled)
+}
+
 /// Insert a [`Action`] into the database.
 #[tracing::instrument(skip(txn))]
 pub fn insert_action(
@@ -569,6 +602,7 @@ pub fn insert_action(
                 "zome_index": create_link.zome_index.0,
                 "link_type": create_link.link_type.0,
                 "tag": create_link.tag.as_sql(),
+                "target_hash": create_link.target_address,
                 "blob": to_blob(&signed_action)?,
             })?;
         }
@@ -651,12 +685,15 @@ pub fn insert_entry(
     let mut cap_secret = None;
     let mut cap_access = None;
     let mut cap_grantor = None;
+    let mut cap_expires_at = None;
     let cap_tag = match &entry {
         Entry::CapGrant(ZomeCallCapGrant {
             tag,
             access,
-            functions: _,
+            expires_at,
+            ..
         }) => {
+            cap_expires_at = *expires_at;
             cap_secret = match access {
                 CapAccess::Unrestricted => None,
                 CapAccess::Transferable { secret } => Some(to_blob(secret)?),
@@ -690,6 +727,7 @@ pub fn insert_entry(
         "access_type": cap_access,
         "grantor": cap_grantor,
         "cap_secret": cap_secret,
+        "cap_expires_at": cap_expires_at,
         // TODO: add cap functions and assignees
     })?;
     Ok(())
@@ -722,8 +760,74 @@ pub fn lock_chain(
 /// Unlock the chain by dropping all records in the lock table.
 /// This should be done very carefully as it can e.g. invalidate a shared
 /// countersigning session that is inflight.
+/// Any countersigning session stored for the author is dropped with the lock.
 pub fn unlock_chain(txn: &mut Transaction, author: &AgentPubKey) -> StateMutationResult<()> {
     txn.execute("DELETE FROM ChainLock WHERE author = ?", [author])?;
+    txn.execute(
+        "DELETE FROM CountersigningSession WHERE author = ?",
+        [author],
+    )?;
+    Ok(())
+}
+
+/// Store the preflight request of the countersigning session the author has
+/// locked their chain for, so that the session survives a restart.
+/// An author can only be in one session at a time so this replaces any
+/// previous session.
+pub fn put_countersigning_session(
+    txn: &mut Transaction,
+    author: &AgentPubKey,
+    preflight_request: &PreflightRequest,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, CountersigningSession, {
+        "author": author,
+        "preflight_request": to_blob(preflight_request)?,
+        "expires_at_timestamp": preflight_request.session_times.end(),
+    })?;
+    Ok(())
+}
+
+/// Store an op received for a countersigning session this conductor is
+/// collecting the signed actions of, along with the hashes of the actions
+/// which complete the session.
+pub fn insert_countersigning_op(
+    txn: &mut Transaction,
+    entry_hash: &EntryHash,
+    op_hash: &DhtOpHash,
+    op: &DhtOp,
+    session_actions: &CountersigningSessionActions,
+    expires_at: &Timestamp,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, CountersigningSessionOp, {
+        "hash": op_hash,
+        "entry_hash": entry_hash,
+        "expires_at_timestamp": expires_at,
+        "blob": to_blob(&(op, session_actions))?,
+    })?;
+    Ok(())
+}
+
+/// Drop all ops collected for a countersigning session.
+pub fn delete_countersigning_ops(
+    txn: &mut Transaction,
+    entry_hash: &EntryHash,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM CountersigningSessionOp WHERE entry_hash = ?",
+        [entry_hash],
+    )?;
+    Ok(())
+}
+
+/// Drop all ops collected for countersigning sessions which have expired.
+pub fn delete_expired_countersigning_ops(
+    txn: &mut Transaction,
+    now: Timestamp,
+) -> StateMutationResult<()> {
+    txn.execute(
+        "DELETE FROM CountersigningSessionOp WHERE expires_at_timestamp <= ?",
+        [now],
+    )?;
     Ok(())
 }
 
@@ -0,0 +1,326 @@
+//! The remote signals sent in reliable mode, kept in the conductor database
+//! until they expire so that undelivered signals survive a restart.
+
+use holochain_sqlite::db::ReadAccess;
+use holochain_sqlite::prelude::DatabaseResult;
+use holochain_sqlite::prelude::DbWrite;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Row;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::prelude::*;
+use std::time::Duration;
+
+/// A remote signal sent in reliable mode by a cell of this conductor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedRemoteSignal {
+    /// Identifies the signal in the queue.
+    pub id: i64,
+    /// The cell which sent the signal.
+    pub from: CellId,
+    /// The agent the signal is sent to.
+    pub to_agent: AgentPubKey,
+    /// The zome which sent the signal.
+    pub zome_name: ZomeName,
+    /// The signal payload.
+    pub signal: ExternIO,
+    /// When the signal was queued.
+    pub queued_at: Timestamp,
+    /// How many times delivery has been attempted so far.
+    pub attempts: u32,
+    /// When the recipient's conductor acknowledged the signal, if it has.
+    pub delivered_at: Option<Timestamp>,
+}
+
+/// Queue a signal from a cell, dropping the signals kept for longer than
+/// `ttl`. Once `max_per_cell` signals are kept for the cell, the oldest
+/// delivered signal is dropped, or the oldest undelivered signal if all of
+/// them are undelivered. Returns the id of the queued signal.
+pub async fn queue_remote_signal(
+    db: &DbWrite<DbKindConductor>,
+    from: CellId,
+    to_agent: AgentPubKey,
+    zome_name: ZomeName,
+    signal: ExternIO,
+    max_per_cell: usize,
+    ttl: Duration,
+) -> DatabaseResult<i64> {
+    let now = Timestamp::now();
+    db.async_commit(move |txn| {
+        purge_expired(txn, now, ttl)?;
+        let queued: usize = txn.query_row(
+            "
+            SELECT COUNT(*) FROM RemoteSignal
+            WHERE dna_hash = :dna_hash AND agent = :agent
+            ",
+            named_params! {
+                ":dna_hash": from.dna_hash(),
+                ":agent": from.agent_pubkey(),
+            },
+            |row| row.get(0),
+        )?;
+        if queued >= max_per_cell {
+            txn.execute(
+                "
+                DELETE FROM RemoteSignal
+                WHERE id = (
+                    SELECT id FROM RemoteSignal
+                    WHERE dna_hash = :dna_hash AND agent = :agent
+                    ORDER BY delivered_at IS NULL, id
+                    LIMIT 1
+                )
+                ",
+                named_params! {
+                    ":dna_hash": from.dna_hash(),
+                    ":agent": from.agent_pubkey(),
+                },
+            )?;
+        }
+        txn.execute(
+            "
+            INSERT INTO RemoteSignal
+            (dna_hash, agent, to_agent, zome_name, signal, queued_at, attempts)
+            VALUES (:dna_hash, :agent, :to_agent, :zome_name, :signal, :queued_at, 0)
+            ",
+            named_params! {
+                ":dna_hash": from.dna_hash(),
+                ":agent": from.agent_pubkey(),
+                ":to_agent": to_agent,
+                ":zome_name": zome_name.0.as_ref(),
+                ":signal": signal.0,
+                ":queued_at": now,
+            },
+        )?;
+        Ok(txn.last_insert_rowid())
+    })
+    .await
+}
+
+/// Count an attempt at delivering a signal.
+/// Returns the signal, or `None` if it has been delivered or dropped.
+pub async fn attempt_remote_signal(
+    db: &DbWrite<DbKindConductor>,
+    id: i64,
+) -> DatabaseResult<Option<QueuedRemoteSignal>> {
+    db.async_commit(move |txn| {
+        let attempted = txn.execute(
+            "
+            UPDATE RemoteSignal SET attempts = attempts + 1
+            WHERE id = :id AND delivered_at IS NULL
+            ",
+            named_params! {":id": id},
+        )?;
+        if attempted == 0 {
+            return Ok(None);
+        }
+        Ok(txn
+            .query_row(
+                "SELECT * FROM RemoteSignal WHERE id = :id",
+                named_params! {":id": id},
+                from_row,
+            )
+            .optional()?)
+    })
+    .await
+}
+
+/// Mark a signal as acknowledged by the recipient's conductor.
+pub async fn remote_signal_delivered(
+    db: &DbWrite<DbKindConductor>,
+    id: i64,
+    delivered_at: Timestamp,
+) -> DatabaseResult<()> {
+    db.async_commit(move |txn| {
+        txn.execute(
+            "UPDATE RemoteSignal SET delivered_at = :delivered_at WHERE id = :id",
+            named_params! {
+                ":id": id,
+                ":delivered_at": delivered_at,
+            },
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+/// Drop a signal which the recipient's conductor refused.
+pub async fn drop_remote_signal(db: &DbWrite<DbKindConductor>, id: i64) -> DatabaseResult<()> {
+    db.async_commit(move |txn| {
+        txn.execute(
+            "DELETE FROM RemoteSignal WHERE id = :id",
+            named_params! {":id": id},
+        )?;
+        Ok(())
+    })
+    .await
+}
+
+/// The ids of the undelivered signals sent by cells of this DNA
+/// to any of these agents which haven't been kept for longer than `ttl`.
+pub async fn undelivered_remote_signals<Db: ReadAccess<DbKindConductor>>(
+    db: &Db,
+    dna_hash: DnaHash,
+    to_agents: Vec<AgentPubKey>,
+    ttl: Duration,
+) -> DatabaseResult<Vec<i64>> {
+    let kept_since = kept_since(Timestamp::now(), ttl);
+    db.async_reader(move |txn| {
+        let mut stmt = txn.prepare(
+            "
+            SELECT id FROM RemoteSignal
+            WHERE dna_hash = :dna_hash AND to_agent = :to_agent
+            AND delivered_at IS NULL AND queued_at > :kept_since
+            ORDER BY id
+            ",
+        )?;
+        let mut ids = Vec::new();
+        for to_agent in to_agents {
+            for id in stmt.query_map(
+                named_params! {
+                    ":dna_hash": dna_hash,
+                    ":to_agent": to_agent,
+                    ":kept_since": kept_since,
+                },
+                |row| row.get(0),
+            )? {
+                ids.push(id?);
+            }
+        }
+        Ok(ids)
+    })
+    .await
+}
+
+/// The signals sent by a cell which haven't been kept for longer than
+/// `ttl`, oldest first.
+pub async fn remote_signals<Db: ReadAccess<DbKindConductor>>(
+    db: &Db,
+    from: CellId,
+    ttl: Duration,
+) -> DatabaseResult<Vec<QueuedRemoteSignal>> {
+    let kept_since = kept_since(Timestamp::now(), ttl);
+    db.async_reader(move |txn| {
+        let mut stmt = txn.prepare(
+            "
+            SELECT * FROM RemoteSignal
+            WHERE dna_hash = :dna_hash AND agent = :agent AND queued_at > :kept_since
+            ORDER BY id
+            ",
+        )?;
+        let signals = stmt
+            .query_map(
+                named_params! {
+                    ":dna_hash": from.dna_hash(),
+                    ":agent": from.agent_pubkey(),
+                    ":kept_since": kept_since,
+                },
+                from_row,
+            )?
+            .collect::<Result<_, _>>()?;
+        Ok(signals)
+    })
+    .await
+}
+
+fn kept_since(now: Timestamp, ttl: Duration) -> Timestamp {
+    (now - ttl).unwrap_or(Timestamp::MIN)
+}
+
+fn purge_expired(txn: &Transaction, now: Timestamp, ttl: Duration) -> DatabaseResult<()> {
+    txn.execute(
+        "DELETE FROM RemoteSignal WHERE queued_at <= :kept_since",
+        named_params! {":kept_since": kept_since(now, ttl)},
+    )?;
+    Ok(())
+}
+
+fn from_row(row: &Row) -> holochain_sqlite::rusqlite::Result<QueuedRemoteSignal> {
+    let zome_name: String = row.get("zome_name")?;
+    Ok(QueuedRemoteSignal {
+        id: row.get("id")?,
+        from: CellId::new(row.get("dna_hash")?, row.get("agent")?),
+        to_agent: row.get("to_agent")?,
+        zome_name: zome_name.into(),
+        signal: ExternIO(row.get("signal")?),
+        queued_at: row.get("queued_at")?,
+        attempts: row.get("attempts")?,
+        delivered_at: row.get("delivered_at")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::test_conductor_db;
+    use ::fixt::prelude::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remote_signals_are_queued_and_delivered() {
+        let test_db = test_conductor_db();
+        let db = test_db.to_db();
+        let from = fixt!(CellId);
+        let bob = fixt!(AgentPubKey);
+        let carol = fixt!(AgentPubKey);
+
+        let mut ids = Vec::new();
+        for (to_agent, n) in [(&bob, 0u8), (&carol, 1), (&bob, 2)] {
+            ids.push(
+                queue_remote_signal(
+                    &db,
+                    from.clone(),
+                    to_agent.clone(),
+                    "zome".into(),
+                    ExternIO(vec![n]),
+                    2,
+                    TTL,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+
+        // Only two signals are kept for the cell, so the oldest is dropped.
+        let signals = remote_signals(&db, from.clone(), TTL).await.unwrap();
+        assert_eq!(
+            signals.iter().map(|s| s.id).collect::<Vec<_>>(),
+            ids[1..].to_vec()
+        );
+        assert_eq!(signals[1].to_agent, bob);
+        assert_eq!(signals[1].signal, ExternIO(vec![2]));
+
+        let undelivered = |agents: Vec<AgentPubKey>| {
+            undelivered_remote_signals(&db, from.dna_hash().clone(), agents, TTL)
+        };
+        assert_eq!(undelivered(vec![bob.clone()]).await.unwrap(), vec![ids[2]]);
+
+        let attempted = attempt_remote_signal(&db, ids[2]).await.unwrap().unwrap();
+        assert_eq!(attempted.attempts, 1);
+        remote_signal_delivered(&db, ids[2], Timestamp::now())
+            .await
+            .unwrap();
+        assert!(undelivered(vec![bob.clone()]).await.unwrap().is_empty());
+        // Delivered signals aren't attempted again.
+        assert!(attempt_remote_signal(&db, ids[2]).await.unwrap().is_none());
+
+        // The oldest delivered signal is dropped before any undelivered one.
+        queue_remote_signal(
+            &db,
+            from.clone(),
+            carol.clone(),
+            "zome".into(),
+            ExternIO(vec![3]),
+            2,
+            TTL,
+        )
+        .await
+        .unwrap();
+        assert_eq!(undelivered(vec![carol.clone()]).await.unwrap().len(), 2);
+
+        // Dropped signals are neither kept nor attempted again.
+        drop_remote_signal(&db, ids[1]).await.unwrap();
+        assert_eq!(undelivered(vec![carol]).await.unwrap().len(), 1);
+        assert!(attempt_remote_signal(&db, ids[1]).await.unwrap().is_none());
+    }
+}
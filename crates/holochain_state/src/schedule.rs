@@ -36,6 +36,36 @@ pub fn fn_is_scheduled(
         .is_some())
 }
 
+/// All the functions an author has scheduled, whether or not they are
+/// currently live.
+pub fn scheduled_fns(
+    txn: &Transaction,
+    author: &AgentPubKey,
+) -> StateMutationResult<Vec<ScheduledFn>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT
+        zome_name,
+        scheduled_fn
+        FROM ScheduledFunctions
+        WHERE
+        author = :author
+        ORDER BY zome_name, scheduled_fn",
+    )?;
+    let rows = stmt.query_map(
+        named_params! {
+            ":author": author,
+        },
+        |row| {
+            Ok(ScheduledFn::new(
+                ZomeName(row.get::<_, String>(0)?.into()),
+                FunctionName(row.get(1)?),
+            ))
+        },
+    )?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
 pub fn live_scheduled_fns(
     txn: &Transaction,
     now: Timestamp,
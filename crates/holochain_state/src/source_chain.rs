@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -9,6 +10,7 @@ use crate::scratch::SyncScratchError;
 use async_recursion::async_recursion;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyLinkableHash;
 use holo_hash::DhtOpHash;
 use holo_hash::DnaHash;
 use holo_hash::HasHash;
@@ -146,10 +148,15 @@ impl SourceChain {
 
         // This all needs to be ensured in a non-panicky way BEFORE calling into the source chain here.
         let author = self.author.clone();
-        assert_eq!(
-            *author,
-            preflight_request.signing_agents[agent_index as usize].0
-        );
+        assert!([
+            &preflight_request.signing_agents,
+            &preflight_request.optional_signing_agents
+        ]
+        .iter()
+        .any(|agents| matches!(
+            agents.get(agent_index as usize),
+            Some((agent, _)) if agent == author.as_ref()
+        )));
 
         let countersigning_agent_state = self
             .vault
@@ -170,6 +177,7 @@ impl SourceChain {
                     author.as_ref(),
                     preflight_request.session_times.end(),
                 )?;
+                put_countersigning_session(txn, author.as_ref(), &preflight_request)?;
                 SourceChainResult::Ok(countersigning_agent_state)
             })
             .await?;
@@ -642,6 +650,8 @@ where
             .async_reader({
                 let agent_pubkey = self.agent_pubkey().clone();
                 move |txn| -> Result<_, DatabaseError> {
+                    // expired grants are filtered out by the queries
+                    let now = Timestamp::now();
                     // closure to process resulting rows from query
                     let query_row_fn = |row: &Row| {
                         from_blob::<Entry>(row.get("blob")?).map_err(|err| {
@@ -668,7 +678,7 @@ where
                         })?;
                         txn.query_row(
                             SELECT_VALID_CAP_GRANT_FOR_CAP_SECRET,
-                            params![cap_secret_blob, agent_pubkey],
+                            params![cap_secret_blob, agent_pubkey, now],
                             query_row_fn,
                         )
                         .optional()?
@@ -677,7 +687,7 @@ where
                         // that has not been updated or deleted
                         txn.query_row(
                             SELECT_VALID_UNRESTRICTED_CAP_GRANT,
-                            params![CapAccess::Unrestricted.as_sql(), agent_pubkey],
+                            params![CapAccess::Unrestricted.as_sql(), agent_pubkey, now],
                             query_row_fn,
                         )
                         .optional()?
@@ -721,21 +731,22 @@ where
     /// used by the `query` host function, which crosses the wasm boundary
     // FIXME: This query needs to be tested.
     pub async fn query(&self, query: QueryFilter) -> SourceChainResult<Vec<Record>> {
-        if query.sequence_range != ChainQueryFilterRange::Unbounded
-            && (query.action_type.is_some()
-                || query.entry_type.is_some()
-                || query.entry_hashes.is_some()
-                || query.include_entries)
-        {
-            return Err(SourceChainError::UnsupportedQuery(query));
-        }
+        check_query(&query)?;
+        let scratch_cursor_seq = self.scratch_cursor_seq(&query)?;
+        let scratch_linked = self.scratch_linked_hashes(&query)?;
         let author = self.author.clone();
         let public_only = self.public_only;
-        let mut records = self
+        let records = self
             .vault
             .async_reader({
                 let query = query.clone();
                 move |txn| {
+                    let cursor_seq = match cursor_seq(&txn, &query, &author, scratch_cursor_seq)? {
+                        Some(cursor_seq) => cursor_seq,
+                        // An unknown cursor can't be continued from.
+                        None => return StateQueryResult::Ok(None),
+                    };
+                    let linked = authored_by_linked_agent(&txn, &query, scratch_linked)?;
                     let mut sql = "
                 SELECT DISTINCT
                 Action.hash AS action_hash, Action.blob AS action_blob
@@ -760,110 +771,27 @@ where
                     ",
                         );
                     }
-                    sql.push_str(
-                        "
-                JOIN DhtOp On DhtOp.action_hash = Action.hash
-                WHERE
-                Action.author = :author
-                AND
-                (
-                    (:range_start IS NULL AND :range_end IS NULL AND :range_start_hash IS NULL AND :range_end_hash IS NULL AND :range_prior_count IS NULL)
-                ",
-                    );
-                    sql.push_str(match query.sequence_range {
-                        ChainQueryFilterRange::Unbounded => "",
-                        ChainQueryFilterRange::ActionSeqRange(_, _) => "
-                        OR (Action.seq BETWEEN :range_start AND :range_end)",
-                        ChainQueryFilterRange::ActionHashRange(_, _) => "
-                        OR (
-                            Action.seq BETWEEN
-                            (SELECT Action.seq from Action WHERE Action.hash = :range_start_hash)
-                            AND
-                            (SELECT Action.seq from Action WHERE Action.hash = :range_end_hash)
-                        )",
-                        ChainQueryFilterRange::ActionHashTerminated(_, _) => "
-                        OR (
-                            Action.seq BETWEEN
-                            (SELECT Action.seq from Action WHERE Action.hash = :range_end_hash) - :range_prior_count
-                            AND
-                            (SELECT Action.seq from Action WHERE Action.hash = :range_end_hash)
-                        )",
+                    sql.push_str(&chain_query_condition(&query));
+                    sql.push_str(if query.order_descending {
+                        " ORDER BY Action.seq DESC"
+                    } else {
+                        " ORDER BY Action.seq ASC"
                     });
-
-                    let entry_type_filters_count = query.entry_type.as_ref().map_or(0, |t| t.len());
-                    let action_type_filters_count = query.action_type.as_ref().map_or(0, |t| t.len());
-
-                    sql.push_str(
-                        format!("
-                        )
-                        AND
-                        (:entry_type IS NULL OR Action.entry_type IN ({}))
-                        AND
-                        (:action_type IS NULL OR Action.type IN ({}))
-                        ORDER BY Action.seq
-                        ", named_param_seq("entry_type", entry_type_filters_count), named_param_seq("action_type", action_type_filters_count)).as_str(),
-                    );
-                    sql.push_str(if query.order_descending {" DESC"} else {" ASC"});
-                    let mut stmt = txn.prepare(&sql)?;
-
-                    // This type is similar to what `named_params!` from rusqlite creates, escept for the use of boxing to allow references to be passed to the query.
-                    // The reserved capacity here should account for the number of parameters inserted below, including the variable inputs like entry_types and actions_types.
-                    let mut args: Vec<(String, Box<dyn rusqlite::ToSql>)> = Vec::with_capacity(6 + entry_type_filters_count + action_type_filters_count);
-                    args.push((":author".to_string(), Box::new(author)));
-
-                    match &query.entry_type {
-                        None => {
-                            args.push((":entry_type".to_string(), Box::new(None::<EntryType>.as_sql())))
-                        }
-                        Some(types) => {
-                            // Value should not be 'Some' until it has at least one value
-                            args.push((":entry_type".to_string(), Box::new(types.get(0).unwrap().as_sql())));
-                            for i in 1..types.len() {
-                                args.push((format!(":entry_type_{}", i), Box::new(types.get(i).unwrap().as_sql())));
-                            }
+                    // Hash bounded ranges are disambiguated after the query, over
+                    // all actions in the range, so the limit is applied then.
+                    if !query.is_hash_bounded() {
+                        if let Some(limit) = query.limit {
+                            sql.push_str(&format!(" LIMIT {}", limit));
                         }
                     }
-
-                    match &query.action_type {
-                        None => args.push((":action_type".to_string(), Box::new(None::<EntryType>.as_sql()))),
-                        Some(types) => {
-                            // Value should not be 'Some' until it has at least one value
-                            args.push((":action_type".to_string(), Box::new(types.get(0).as_ref().unwrap().as_sql())));
-                            for i in 1..types.len() {
-                                args.push((format!(":action_type_{}", i), Box::new(types.get(i).unwrap().as_sql())));
-                            }
-                        }
-                    }
-
-                    args.push((":range_start".to_string(), Box::new(match query.sequence_range {
-                        ChainQueryFilterRange::ActionSeqRange(start, _) => Some(start),
-                        _ => None,
-                    })));
-
-                    args.push((":range_end".to_string(), Box::new(match query.sequence_range {
-                        ChainQueryFilterRange::ActionSeqRange(_, end) => Some(end),
-                        _ => None,
-                    })));
-
-                    args.push((":range_start_hash".to_string(), Box::new(match &query.sequence_range {
-                        ChainQueryFilterRange::ActionHashRange(start_hash, _) => Some(start_hash.clone()),
-                        _ => None,
-                    })));
-
-                    args.push((":range_end_hash".to_string(), Box::new(match &query.sequence_range {
-                        ChainQueryFilterRange::ActionHashRange(_, end_hash)
-                        | ChainQueryFilterRange::ActionHashTerminated(end_hash, _) => Some(end_hash.clone()),
-                        _ => None,
-                    })));
-
-                    args.push((":range_prior_count".to_string(), Box::new(match query.sequence_range {
-                        ChainQueryFilterRange::ActionHashTerminated(_, prior_count) => Some(prior_count),
-                        _ => None,
-                    })));
-
+                    let mut stmt = txn.prepare(&sql)?;
+                    let args = chain_query_args(&query, author, cursor_seq);
                     let records = stmt
                         .query_and_then(
-                            args.iter().map(|a| (a.0.as_str(), a.1.as_ref())).collect::<Vec<(&str, &dyn rusqlite::ToSql)>>().as_slice(),
+                            args.iter()
+                                .map(|a| (a.0.as_str(), a.1.as_ref()))
+                                .collect::<Vec<(&str, &dyn rusqlite::ToSql)>>()
+                                .as_slice(),
                             |row| {
                                 let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
                                 let SignedAction(action, signature) = action;
@@ -886,14 +814,184 @@ where
                                 StateQueryResult::Ok(Record::new(shh, entry))
                             },
                         )?
-                        .collect::<StateQueryResult<Vec<_>>>();
+                        .collect::<StateQueryResult<Vec<_>>>()?;
+                    StateQueryResult::Ok(Some((records, cursor_seq, linked)))
+                }
+            })
+            .await?;
+        let (mut records, cursor_seq, linked) = match records {
+            Some(r) => r,
+            None => return Ok(Vec::new()),
+        };
+        let mut scratch_records = self.matching_scratch_records(&query, cursor_seq, &linked)?;
+        // The scratch is always ahead of the database, so its records
+        // come first when the query is descending.
+        if query.order_descending {
+            scratch_records.append(&mut records);
+            records = scratch_records;
+        } else {
+            records.extend(scratch_records);
+        }
+        if query.is_hash_bounded() {
+            let in_range = query
+                .disambiguate_forks(
                     records
+                        .iter()
+                        .map(|record| record.action_hashed().clone())
+                        .collect(),
+                )
+                .into_iter()
+                .map(|action| action.into_hash())
+                .collect::<HashSet<_>>();
+            records.retain(|record| in_range.contains(record.action_address()));
+        }
+        if let Some(limit) = query.limit {
+            records.truncate(limit as usize);
+        }
+        Ok(records)
+    }
+
+    /// Count the records which a query of the source chain would return,
+    /// without returning them. The limit of the query is ignored, so with a
+    /// cursor this counts all of the remaining records.
+    pub async fn query_count(&self, query: QueryFilter) -> SourceChainResult<usize> {
+        let query = QueryFilter {
+            include_entries: false,
+            limit: None,
+            ..query
+        };
+        // Hash bounded ranges can only be disambiguated over the actions themselves.
+        if query.is_hash_bounded() {
+            return Ok(self.query(query).await?.len());
+        }
+        check_query(&query)?;
+        let scratch_cursor_seq = self.scratch_cursor_seq(&query)?;
+        let scratch_linked = self.scratch_linked_hashes(&query)?;
+        let author = self.author.clone();
+        let count = self
+            .vault
+            .async_reader({
+                let query = query.clone();
+                move |txn| {
+                    let cursor_seq = match cursor_seq(&txn, &query, &author, scratch_cursor_seq)? {
+                        Some(cursor_seq) => cursor_seq,
+                        None => return StateQueryResult::Ok(None),
+                    };
+                    let linked = authored_by_linked_agent(&txn, &query, scratch_linked)?;
+                    let sql = format!(
+                        "SELECT COUNT(DISTINCT Action.hash) FROM Action {}",
+                        chain_query_condition(&query)
+                    );
+                    let args = chain_query_args(&query, author, cursor_seq);
+                    let count: usize = txn.query_row(
+                        &sql,
+                        args.iter()
+                            .map(|a| (a.0.as_str(), a.1.as_ref()))
+                            .collect::<Vec<(&str, &dyn rusqlite::ToSql)>>()
+                            .as_slice(),
+                        |row| row.get(0),
+                    )?;
+                    StateQueryResult::Ok(Some((count, cursor_seq, linked)))
                 }
             })
             .await?;
-        self.scratch.apply(|scratch| {
+        match count {
+            Some((count, cursor_seq, linked)) => Ok(count
+                + self
+                    .matching_scratch_records(&query, cursor_seq, &linked)?
+                    .len()),
+            None => Ok(0),
+        }
+    }
+
+    /// The sequence number of the query's cursor, if it's in the scratch.
+    fn scratch_cursor_seq(&self, query: &QueryFilter) -> SourceChainResult<Option<u32>> {
+        Ok(match &query.cursor {
+            Some(cursor) => self.scratch.apply(|scratch| {
+                scratch
+                    .actions()
+                    .find(|shh| shh.action_address() == cursor)
+                    .map(|shh| shh.action().action_seq())
+            })?,
+            None => None,
+        })
+    }
+
+    /// The bases and targets of the links in the scratch, which have to be
+    /// checked against the database for the `linked_agent` filter.
+    fn scratch_linked_hashes(
+        &self,
+        query: &QueryFilter,
+    ) -> SourceChainResult<Vec<AnyLinkableHash>> {
+        if query.linked_agent.is_none() {
+            return Ok(Vec::new());
+        }
+        Ok(self.scratch.apply(|scratch| {
+            scratch
+                .actions()
+                .filter_map(|shh| match shh.action() {
+                    Action::CreateLink(create_link) => Some([
+                        create_link.base_address.clone(),
+                        create_link.target_address.clone(),
+                    ]),
+                    _ => None,
+                })
+                .flatten()
+                .collect()
+        })?)
+    }
+
+    /// The records in the scratch matching a query, in the query's order.
+    /// `linked` are the hashes in the database authored by the `linked_agent`.
+    fn matching_scratch_records(
+        &self,
+        query: &QueryFilter,
+        cursor_seq: Option<u32>,
+        linked: &HashSet<AnyLinkableHash>,
+    ) -> SourceChainResult<Vec<Record>> {
+        Ok(self.scratch.apply(|scratch| {
+            let linked_in_scratch = match &query.linked_agent {
+                Some(agent) => scratch
+                    .actions()
+                    .filter(|shh| shh.action().author() == agent)
+                    .flat_map(|shh| {
+                        std::iter::once(shh.action_address().clone().into())
+                            .chain(shh.action().entry_hash().cloned().map(Into::into))
+                    })
+                    .collect(),
+                None => HashSet::<AnyLinkableHash>::new(),
+            };
+            let in_range = if query.is_hash_bounded() {
+                // Disambiguated together with the database records.
+                None
+            } else {
+                Some(
+                    query
+                        .disambiguate_forks(
+                            scratch.actions().map(|shh| shh.hashed.clone()).collect(),
+                        )
+                        .into_iter()
+                        .map(|action| action.into_hash())
+                        .collect::<HashSet<_>>(),
+                )
+            };
             let mut scratch_records: Vec<_> = scratch
                 .actions()
+                .filter(|shh| match cursor_seq {
+                    Some(seq) if query.order_descending => shh.action().action_seq() < seq,
+                    Some(seq) => shh.action().action_seq() > seq,
+                    None => true,
+                })
+                .filter(|shh| {
+                    in_range
+                        .as_ref()
+                        .map_or(true, |in_range| in_range.contains(shh.action_address()))
+                })
+                .filter(|shh| {
+                    query.matches_action(shh.action(), |hash| {
+                        linked.contains(hash) || linked_in_scratch.contains(hash)
+                    })
+                })
                 .filter_map(|shh| {
                     let entry = match shh.action().entry_hash() {
                         Some(eh) if query.include_entries => scratch.get_entry(eh).ok()?,
@@ -903,10 +1001,11 @@ where
                 })
                 .collect();
             scratch_records.sort_unstable_by_key(|e| e.action().action_seq());
-
-            records.extend(scratch_records);
-        })?;
-        Ok(query.filter_records(records))
+            if query.order_descending {
+                scratch_records.reverse();
+            }
+            scratch_records
+        })?)
     }
 
     pub async fn is_chain_locked(&self, lock: Vec<u8>) -> SourceChainResult<bool> {
@@ -946,6 +1045,289 @@ where
     }
 }
 
+/// Check that the filters of a query can be combined.
+fn check_query(query: &QueryFilter) -> SourceChainResult<()> {
+    if query.sequence_range != ChainQueryFilterRange::Unbounded
+        && (query.action_type.is_some()
+            || query.entry_type.is_some()
+            || query.entry_hashes.is_some()
+            || query.timestamp_range.is_some()
+            || query.linked_agent.is_some()
+            || query.include_entries)
+    {
+        return Err(SourceChainError::UnsupportedQuery(query.clone()));
+    }
+    Ok(())
+}
+
+/// The sequence number of the query's cursor, looking it up in the database
+/// if it wasn't found in the scratch.
+/// Returns `None` if the cursor isn't an action of the chain.
+fn cursor_seq(
+    txn: &Transaction,
+    query: &QueryFilter,
+    author: &AgentPubKey,
+    scratch_cursor_seq: Option<u32>,
+) -> StateQueryResult<Option<Option<u32>>> {
+    match (&query.cursor, scratch_cursor_seq) {
+        (None, _) => Ok(Some(None)),
+        (Some(_), Some(seq)) => Ok(Some(Some(seq))),
+        (Some(cursor), None) => Ok(txn
+            .query_row(
+                "SELECT seq FROM Action WHERE hash = :hash AND author = :author",
+                named_params! {
+                    ":hash": cursor,
+                    ":author": author,
+                },
+                |row| row.get::<_, u32>(0),
+            )
+            .optional()?
+            .map(Some)),
+    }
+}
+
+/// Which of the hashes are of entries or actions in the database authored by
+/// the query's `linked_agent`.
+fn authored_by_linked_agent(
+    txn: &Transaction,
+    query: &QueryFilter,
+    hashes: Vec<AnyLinkableHash>,
+) -> StateQueryResult<HashSet<AnyLinkableHash>> {
+    let agent = match &query.linked_agent {
+        Some(agent) => agent,
+        None => return Ok(HashSet::new()),
+    };
+    let mut stmt = txn.prepare(
+        "
+        SELECT EXISTS(
+            SELECT 1 FROM Action
+            WHERE author = :author AND (hash = :hash OR entry_hash = :hash)
+        )
+        ",
+    )?;
+    let mut authored = HashSet::new();
+    for hash in hashes {
+        if stmt.query_row(named_params! {":author": agent, ":hash": hash}, |row| {
+            row.get::<_, bool>(0)
+        })? {
+            authored.insert(hash);
+        }
+    }
+    Ok(authored)
+}
+
+/// The joins and conditions of a source chain query, following the `FROM Action`.
+fn chain_query_condition(query: &QueryFilter) -> String {
+    let mut sql = "
+                JOIN DhtOp On DhtOp.action_hash = Action.hash
+                WHERE
+                Action.author = :author
+                AND
+                (
+                    (:range_start IS NULL AND :range_end IS NULL AND :range_start_hash IS NULL AND :range_end_hash IS NULL AND :range_prior_count IS NULL)
+                "
+    .to_string();
+    sql.push_str(match query.sequence_range {
+        ChainQueryFilterRange::Unbounded => "",
+        ChainQueryFilterRange::ActionSeqRange(_, _) => "
+                        OR (Action.seq BETWEEN :range_start AND :range_end)",
+        ChainQueryFilterRange::ActionHashRange(_, _) => "
+                        OR (
+                            Action.seq BETWEEN
+                            (SELECT Action.seq from Action WHERE Action.hash = :range_start_hash)
+                            AND
+                            (SELECT Action.seq from Action WHERE Action.hash = :range_end_hash)
+                        )",
+        ChainQueryFilterRange::ActionHashTerminated(_, _) => "
+                        OR (
+                            Action.seq BETWEEN
+                            (SELECT Action.seq from Action WHERE Action.hash = :range_end_hash) - :range_prior_count
+                            AND
+                            (SELECT Action.seq from Action WHERE Action.hash = :range_end_hash)
+                        )",
+    });
+
+    let entry_type_filters_count = query.entry_type.as_ref().map_or(0, |t| t.len());
+    let action_type_filters_count = query.action_type.as_ref().map_or(0, |t| t.len());
+    let entry_hash_filters_count = query.entry_hashes.as_ref().map_or(0, |h| h.len());
+
+    sql.push_str(
+        format!(
+            "
+                        )
+                        AND
+                        (:entry_type IS NULL OR Action.entry_type IN ({}))
+                        AND
+                        (:action_type IS NULL OR Action.type IN ({}))
+                        AND
+                        (:entry_hash_filter IS NULL OR Action.entry_hash IN ({}))
+                        AND
+                        (:timestamp_start IS NULL OR DhtOp.authored_timestamp BETWEEN :timestamp_start AND :timestamp_end)
+                        AND
+                        (
+                            :linked_agent IS NULL
+                            OR
+                            (
+                                Action.type = :create_link
+                                AND
+                                (
+                                    :linked_agent IN (Action.base_hash, Action.target_hash)
+                                    OR EXISTS (
+                                        SELECT 1 FROM Action AS Linked
+                                        WHERE Linked.author = :linked_agent
+                                        AND (
+                                            Linked.hash IN (Action.base_hash, Action.target_hash)
+                                            OR Linked.entry_hash IN (Action.base_hash, Action.target_hash)
+                                        )
+                                    )
+                                )
+                            )
+                        )
+                        AND
+                        (:cursor_seq IS NULL OR Action.seq {} :cursor_seq)
+                        ",
+            named_param_seq("entry_type", entry_type_filters_count),
+            named_param_seq("action_type", action_type_filters_count),
+            named_param_seq("entry_hash", entry_hash_filters_count),
+            if query.order_descending { "<" } else { ">" }
+        )
+        .as_str(),
+    );
+    sql
+}
+
+/// The parameters of the SQL built by [`chain_query_condition`].
+// This type is similar to what `named_params!` from rusqlite creates, except for the use of boxing to allow references to be passed to the query.
+fn chain_query_args(
+    query: &QueryFilter,
+    author: Arc<AgentPubKey>,
+    cursor_seq: Option<u32>,
+) -> Vec<(String, Box<dyn rusqlite::ToSql + '_>)> {
+    let entry_type_filters_count = query.entry_type.as_ref().map_or(0, |t| t.len());
+    let action_type_filters_count = query.action_type.as_ref().map_or(0, |t| t.len());
+    let entry_hash_filters_count = query.entry_hashes.as_ref().map_or(0, |h| h.len());
+    // The reserved capacity here should account for the number of parameters inserted below, including the variable inputs like entry_types, actions_types and entry_hashes.
+    let mut args: Vec<(String, Box<dyn rusqlite::ToSql + '_>)> = Vec::with_capacity(
+        13 + entry_type_filters_count + action_type_filters_count + entry_hash_filters_count,
+    );
+    args.push((":author".to_string(), Box::new(author)));
+    args.push((":cursor_seq".to_string(), Box::new(cursor_seq)));
+
+    match &query.entry_type {
+        None => args.push((
+            ":entry_type".to_string(),
+            Box::new(None::<EntryType>.as_sql()),
+        )),
+        Some(types) => {
+            // Value should not be 'Some' until it has at least one value
+            args.push((
+                ":entry_type".to_string(),
+                Box::new(types.get(0).unwrap().as_sql()),
+            ));
+            for i in 1..types.len() {
+                args.push((
+                    format!(":entry_type_{}", i),
+                    Box::new(types.get(i).unwrap().as_sql()),
+                ));
+            }
+        }
+    }
+
+    match &query.action_type {
+        None => args.push((
+            ":action_type".to_string(),
+            Box::new(None::<EntryType>.as_sql()),
+        )),
+        Some(types) => {
+            // Value should not be 'Some' until it has at least one value
+            args.push((
+                ":action_type".to_string(),
+                Box::new(types.get(0).as_ref().unwrap().as_sql()),
+            ));
+            for i in 1..types.len() {
+                args.push((
+                    format!(":action_type_{}", i),
+                    Box::new(types.get(i).unwrap().as_sql()),
+                ));
+            }
+        }
+    }
+
+    // An empty set matches no entry hash, so it's only null if there is no filter.
+    args.push((
+        ":entry_hash_filter".to_string(),
+        Box::new(query.entry_hashes.as_ref().map(|_| true)),
+    ));
+    for (i, entry_hash) in query.entry_hashes.iter().flatten().enumerate() {
+        let name = if i == 0 {
+            ":entry_hash".to_string()
+        } else {
+            format!(":entry_hash_{}", i)
+        };
+        args.push((name, Box::new(entry_hash.clone())));
+    }
+
+    args.push((
+        ":range_start".to_string(),
+        Box::new(match query.sequence_range {
+            ChainQueryFilterRange::ActionSeqRange(start, _) => Some(start),
+            _ => None,
+        }),
+    ));
+
+    args.push((
+        ":range_end".to_string(),
+        Box::new(match query.sequence_range {
+            ChainQueryFilterRange::ActionSeqRange(_, end) => Some(end),
+            _ => None,
+        }),
+    ));
+
+    args.push((
+        ":range_start_hash".to_string(),
+        Box::new(match &query.sequence_range {
+            ChainQueryFilterRange::ActionHashRange(start_hash, _) => Some(start_hash.clone()),
+            _ => None,
+        }),
+    ));
+
+    args.push((
+        ":range_end_hash".to_string(),
+        Box::new(match &query.sequence_range {
+            ChainQueryFilterRange::ActionHashRange(_, end_hash)
+            | ChainQueryFilterRange::ActionHashTerminated(end_hash, _) => Some(end_hash.clone()),
+            _ => None,
+        }),
+    ));
+
+    args.push((
+        ":range_prior_count".to_string(),
+        Box::new(match query.sequence_range {
+            ChainQueryFilterRange::ActionHashTerminated(_, prior_count) => Some(prior_count),
+            _ => None,
+        }),
+    ));
+
+    args.push((
+        ":timestamp_start".to_string(),
+        Box::new(query.timestamp_range.map(|(start, _)| start)),
+    ));
+    args.push((
+        ":timestamp_end".to_string(),
+        Box::new(query.timestamp_range.map(|(_, end)| end)),
+    ));
+    args.push((
+        ":linked_agent".to_string(),
+        Box::new(query.linked_agent.clone()),
+    ));
+    args.push((
+        ":create_link".to_string(),
+        Box::new(ActionType::CreateLink.as_sql()),
+    ));
+
+    args
+}
+
 fn named_param_seq(base_name: &str, repeat: usize) -> String {
     if repeat == 0 {
         return String::new();
@@ -1225,6 +1607,19 @@ pub fn current_countersigning_session(
     }
 }
 
+/// Abandon the countersigning session the author has locked their chain
+/// for by unlocking the chain.
+/// If the author has committed the countersigned entry it stays on their
+/// chain with its ops withheld from publishing, and its entry hash is returned.
+pub fn abandon_countersigning_session(
+    txn: &mut Transaction<'_>,
+    author: Arc<AgentPubKey>,
+) -> SourceChainResult<Option<EntryHash>> {
+    let abandoned = current_countersigning_session(txn, author.clone())?.map(|(h, _)| h);
+    unlock_chain(txn, &author)?;
+    Ok(abandoned)
+}
+
 #[cfg(test)]
 async fn _put_db<H: holochain_zome_types::ActionUnweighed, B: ActionBuilder<H>>(
     vault: holochain_types::db::DbWrite<DbKindAuthored>,
@@ -1917,6 +2312,79 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn expired_cap_grants_are_not_valid() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+
+        let mut agents = AgentPubKeyFixturator::new(Predictable);
+        let alice = agents.next().unwrap();
+        let bob = agents.next().unwrap();
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let chain = SourceChain::new(
+            db.clone(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+
+        let function: GrantedFunction = ("foo".into(), "bar".into());
+        let expired_secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let expired_grant = ZomeCallCapGrant::new(
+            "expired".into(),
+            expired_secret.into(),
+            GrantedFunctions::All,
+        )
+        .with_expiry((Timestamp::now() - std::time::Duration::from_secs(1)).unwrap());
+        let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+        let grant = ZomeCallCapGrant::new("live".into(), secret.into(), GrantedFunctions::All)
+            .with_expiry((Timestamp::now() + std::time::Duration::from_secs(60)).unwrap());
+        for grant in [&expired_grant, &grant] {
+            let (entry, entry_hash) =
+                EntryHashed::from_content_sync(Entry::CapGrant(grant.clone())).into_inner();
+            let action_builder = builder::Create {
+                entry_type: EntryType::CapGrant,
+                entry_hash,
+            };
+            chain
+                .put_weightless(action_builder, Some(entry), ChainTopOrdering::default())
+                .await?;
+        }
+        chain.flush(&mock).await.unwrap();
+
+        assert_eq!(
+            chain
+                .valid_cap_grant(function.clone(), bob.clone(), Some(expired_secret))
+                .await?,
+            None
+        );
+        assert_eq!(
+            chain.valid_cap_grant(function, bob, Some(secret)).await?,
+            Some(grant.into())
+        );
+
+        Ok(())
+    }
+
     // @todo bring all this back when we want to administer cap claims better
     // #[tokio::test(flavor = "multi_thread")]
     // async fn test_get_cap_claim() -> SourceChainResult<()> {
@@ -2178,6 +2646,17 @@ pub mod tests {
                 ),
                 1,
             ),
+            // Entry hashes alone select the actions of those entries.
+            (
+                (
+                    None,
+                    None,
+                    vec![records[2].action().entry_hash().unwrap().clone()],
+                    false,
+                ),
+                1,
+            ),
+            ((None, None, vec![fixt!(EntryHash)], true), 0),
             (
                 (
                     Some(vec![ActionType::Create, ActionType::Dna]),
@@ -2215,6 +2694,7 @@ pub mod tests {
                     entry_hashes: entry_hashes.clone(),
                     include_entries,
                     order_descending: false,
+                    ..Default::default()
                 };
                 if sequence_range != ChainQueryFilterRange::Unbounded
                     && (action_type.is_some()
@@ -2235,6 +2715,7 @@ pub mod tests {
                         "Expected {} items but got {} with filter {:?}",
                         num_expected, actual, query
                     );
+                    assert_eq!(num_expected, chain.query_count(query).await.unwrap());
                 }
             }
         }
@@ -2281,6 +2762,322 @@ pub mod tests {
         assert_eq!(asc, desc_sorted);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn source_chain_query_pagination() {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        let dna_hash = fixt!(DnaHash);
+
+        genesis(
+            vault.clone().into(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            dna_hash.clone(),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let chain = SourceChain::new(vault, dht_db.to_db(), dht_db_cache, keystore, alice.clone())
+            .await
+            .unwrap();
+
+        let all = chain.query(ChainQueryFilter::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(
+            chain
+                .query_count(ChainQueryFilter::default())
+                .await
+                .unwrap(),
+            3
+        );
+
+        let first = chain
+            .query(ChainQueryFilter::default().limit(2))
+            .await
+            .unwrap();
+        assert_eq!(first, all[..2]);
+
+        let rest = chain
+            .query(
+                ChainQueryFilter::default()
+                    .limit(2)
+                    .cursor(first[1].action_address().clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rest, all[2..]);
+
+        let desc = chain
+            .query(
+                ChainQueryFilter::default()
+                    .descending()
+                    .cursor(all[2].action_address().clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(desc, vec![all[1].clone(), all[0].clone()]);
+
+        // A cursor which isn't on the chain gives nothing.
+        let unknown = chain
+            .query(ChainQueryFilter::default().cursor(fixt!(ActionHash)))
+            .await
+            .unwrap();
+        assert!(unknown.is_empty());
+
+        // Counting isn't limited, but continues from the cursor.
+        assert_eq!(
+            chain
+                .query_count(
+                    ChainQueryFilter::default()
+                        .limit(1)
+                        .cursor(first[0].action_address().clone())
+                )
+                .await
+                .unwrap(),
+            2
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn source_chain_query_links_by_linked_agent_and_time() {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+        let dna_hash = fixt!(DnaHash);
+
+        genesis(
+            vault.clone().into(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            dna_hash.clone(),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let chain = SourceChain::new(vault, dht_db.to_db(), dht_db_cache, keystore, alice.clone())
+            .await
+            .unwrap();
+        let genesis_records = chain.query(ChainQueryFilter::default()).await.unwrap();
+        let link = |base: AnyLinkableHash, target: AnyLinkableHash| {
+            builder::CreateLink::new(base, target, 0.into(), 0.into(), ().into())
+        };
+        let put = |builder| chain.put_weightless(builder, None, ChainTopOrdering::Strict);
+
+        // From an action of alice.
+        let from_action = put(link(
+            genesis_records[2].action_address().clone().into(),
+            fixt!(AnyLinkableHash),
+        ))
+        .await
+        .unwrap();
+        // Between hashes alice didn't author.
+        put(link(fixt!(AnyLinkableHash), fixt!(AnyLinkableHash)))
+            .await
+            .unwrap();
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+        chain.flush(&mock).await.unwrap();
+
+        // To alice's agent entry, still in the scratch.
+        let to_entry = put(link(
+            fixt!(AnyLinkableHash),
+            EntryHash::from(alice.clone()).into(),
+        ))
+        .await
+        .unwrap();
+        // To alice's key.
+        let to_agent = put(link(fixt!(AnyLinkableHash), alice.clone().into()))
+            .await
+            .unwrap();
+
+        let query = ChainQueryFilter::default().linked_agent(alice.clone());
+        let linked: Vec<_> = chain
+            .query(query.clone())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.action_address().clone())
+            .collect();
+        assert_eq!(linked, vec![from_action, to_entry, to_agent]);
+        assert_eq!(chain.query_count(query).await.unwrap(), 3);
+        assert_eq!(
+            chain
+                .query_count(ChainQueryFilter::default().linked_agent(fixt!(AgentPubKey)))
+                .await
+                .unwrap(),
+            0
+        );
+
+        let all = ChainQueryFilter::default().timestamp_range(Timestamp(0), Timestamp::now());
+        assert_eq!(chain.query(all.clone()).await.unwrap().len(), 7);
+        assert_eq!(chain.query_count(all).await.unwrap(), 7);
+        let none = ChainQueryFilter::default().timestamp_range(Timestamp(0), Timestamp(1));
+        assert_eq!(chain.query_count(none).await.unwrap(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn links_written_before_the_target_hash_column_are_backfilled() {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+
+        genesis(
+            vault.clone().into(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fixt!(DnaHash),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let chain = SourceChain::new(
+            vault.clone(),
+            dht_db.to_db(),
+            dht_db_cache,
+            keystore,
+            alice.clone(),
+        )
+        .await
+        .unwrap();
+        chain
+            .put_weightless(
+                builder::CreateLink::new(
+                    fixt!(AnyLinkableHash),
+                    alice.clone().into(),
+                    0.into(),
+                    0.into(),
+                    ().into(),
+                ),
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await
+            .unwrap();
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+        chain.flush(&mock).await.unwrap();
+
+        // As if the link was written before the migration.
+        vault
+            .async_commit(|txn| {
+                txn.execute("UPDATE Action SET target_hash = NULL", [])?;
+                StateMutationResult::Ok(())
+            })
+            .await
+            .unwrap();
+        let query = ChainQueryFilter::default().linked_agent(alice.clone());
+        assert_eq!(chain.query_count(query.clone()).await.unwrap(), 0);
+
+        let filled = vault
+            .async_commit(backfill_link_target_hashes)
+            .await
+            .unwrap();
+        assert_eq!(filled, 1);
+        assert_eq!(chain.query_count(query).await.unwrap(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn countersigning_session_is_kept_until_abandoned() {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let vault = test_db.to_db();
+        let alice = keystore.new_sign_keypair_random().await.unwrap();
+
+        genesis(
+            vault.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fixt!(DnaHash),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let chain = SourceChain::new(
+            vault.clone(),
+            dht_db.to_db(),
+            dht_db_cache,
+            keystore,
+            alice.clone(),
+        )
+        .await
+        .unwrap();
+
+        let now = Timestamp::now();
+        let preflight_request = PreflightRequest::try_new(
+            fixt!(EntryHash),
+            vec![(alice.clone(), vec![]), (fixt!(AgentPubKey), vec![])],
+            vec![],
+            0,
+            false,
+            CounterSigningSessionTimes::try_new(
+                now,
+                (now + std::time::Duration::from_secs(60)).unwrap(),
+            )
+            .unwrap(),
+            ActionBase::Create(CreateBase::new(EntryType::CapGrant)),
+            PreflightBytes(vec![]),
+        )
+        .unwrap();
+        chain
+            .accept_countersigning_preflight_request(preflight_request.clone(), 0)
+            .await
+            .unwrap();
+
+        // The session is stored alongside the lock.
+        let get_session = |alice: AgentPubKey| {
+            vault.async_reader(move |txn| {
+                crate::chain_lock::get_countersigning_session(&txn, &alice)
+            })
+        };
+        assert_eq!(
+            get_session(alice.clone()).await.unwrap(),
+            Some(preflight_request)
+        );
+        assert!(chain.is_chain_locked(vec![]).await.unwrap());
+
+        // Nothing was committed for the session so there is no entry to remove.
+        let abandoned = vault
+            .async_commit({
+                let alice = alice.clone();
+                move |txn| abandon_countersigning_session(txn, Arc::new(alice))
+            })
+            .await
+            .unwrap();
+        assert_eq!(abandoned, None);
+        assert_eq!(get_session(alice).await.unwrap(), None);
+        assert!(!chain.is_chain_locked(vec![]).await.unwrap());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn init_zomes_complete() {
         let test_db = test_authored_db();
@@ -78,7 +78,7 @@ use tracing_subscriber::{
     filter::EnvFilter,
     fmt::{format::FmtSpan, time::UtcTime},
     registry::LookupSpan,
-    FmtSubscriber,
+    reload, FmtSubscriber,
 };
 
 use std::{str::FromStr, sync::Once};
@@ -127,6 +127,11 @@ pub type ParseError = String;
 
 static INIT: Once = Once::new();
 
+/// Swaps the filter of the subscriber set up by [`init_fmt`].
+type ReloadFilter = Box<dyn Fn(EnvFilter) -> Result<(), reload::Error> + Send + Sync>;
+
+static RELOAD_FILTER: once_cell::sync::OnceCell<ReloadFilter> = once_cell::sync::OnceCell::new();
+
 impl FromStr for Output {
     type Err = ParseError;
     fn from_str(day: &str) -> Result<Self, Self::Err> {
@@ -223,10 +228,7 @@ pub fn test_run_timed_ice(path: Option<&str>) -> Result<Option<impl Drop>, error
 /// This checks RUST_LOG for a filter but doesn't complain if there is none or it doesn't parse.
 /// It then checks for CUSTOM_FILTER which if set will output an error if it doesn't parse.
 pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
-    let mut filter = match std::env::var("RUST_LOG") {
-        Ok(_) => EnvFilter::from_default_env(),
-        Err(_) => EnvFilter::from_default_env().add_directive("[wasm_debug]=debug".parse()?),
-    };
+    let mut filter = default_filter()?;
     if std::env::var("CUSTOM_FILTER").is_ok() {
         EnvFilter::try_from_env("CUSTOM_FILTER")
             .map_err(|e| eprintln!("Failed to parse CUSTOM_FILTER {:?}", e))
@@ -248,7 +250,9 @@ pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
                 .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
                 .json()
-                .event_format(FormatEvent);
+                .event_format(FormatEvent)
+                .with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
             finish(subscriber.finish())
         }
         Output::JsonTimed => {
@@ -257,20 +261,32 @@ pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
                 .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
                 .json()
-                .event_format(FormatEvent);
+                .event_format(FormatEvent)
+                .with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
+            finish(subscriber.finish())
+        }
+        Output::Log => {
+            let subscriber = subscriber.with_env_filter(filter).with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
             finish(subscriber.finish())
         }
-        Output::Log => finish(subscriber.with_env_filter(filter).finish()),
         Output::LogTimed => {
-            let subscriber = subscriber.with_span_events(FmtSpan::CLOSE);
-            finish(subscriber.with_env_filter(filter).finish())
+            let subscriber = subscriber
+                .with_span_events(FmtSpan::CLOSE)
+                .with_env_filter(filter)
+                .with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
+            finish(subscriber.finish())
         }
         Output::FlameTimed => {
             let subscriber = subscriber
                 .with_span_events(FmtSpan::CLOSE)
                 .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
-                .event_format(FormatEventFlame);
+                .event_format(FormatEventFlame)
+                .with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
             finish(subscriber.finish())
         }
         Output::IceTimed => {
@@ -278,12 +294,18 @@ pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
                 .with_span_events(FmtSpan::CLOSE)
                 .with_env_filter(filter)
                 .with_timer(UtcTime::rfc_3339())
-                .event_format(FormatEventIce);
+                .event_format(FormatEventIce)
+                .with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
             finish(subscriber.finish())
         }
         Output::Compact => {
-            let subscriber = subscriber.compact();
-            finish(subscriber.with_env_filter(filter).finish())
+            let subscriber = subscriber
+                .compact()
+                .with_env_filter(filter)
+                .with_filter_reloading();
+            set_reload_handle(subscriber.reload_handle());
+            finish(subscriber.finish())
         }
         // Output::OpenTel => {
         //     #[cfg(feature = "opentelemetry-on")]
@@ -312,6 +334,33 @@ pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
     }
 }
 
+/// Replace the filter of the subscriber set up by [`init_fmt`] while it is running.
+/// The filter is parsed from `custom_filter` just as it would be from CUSTOM_FILTER,
+/// or taken from RUST_LOG if there is none.
+pub fn reload_filter(custom_filter: Option<&str>) -> Result<(), errors::TracingError> {
+    let reload = RELOAD_FILTER
+        .get()
+        .ok_or(errors::TracingError::NotReloadable)?;
+    let filter = match custom_filter {
+        Some(custom_filter) => EnvFilter::try_new(custom_filter)?,
+        None => default_filter()?,
+    };
+    reload(filter).map_err(|_| errors::TracingError::NotReloadable)
+}
+
+fn default_filter() -> Result<EnvFilter, errors::TracingError> {
+    Ok(match std::env::var("RUST_LOG") {
+        Ok(_) => EnvFilter::from_default_env(),
+        Err(_) => EnvFilter::from_default_env().add_directive("[wasm_debug]=debug".parse()?),
+    })
+}
+
+fn set_reload_handle<S: 'static>(handle: reload::Handle<EnvFilter, S>) {
+    RELOAD_FILTER
+        .set(Box::new(move |filter| handle.reload(filter)))
+        .ok();
+}
+
 fn finish<S>(subscriber: S) -> Result<(), errors::TracingError>
 where
     S: Subscriber + Send + Sync + for<'span> LookupSpan<'span>,
@@ -338,5 +387,7 @@ pub mod errors {
         TracingFlame,
         #[error(transparent)]
         BadDirective(#[from] tracing_subscriber::filter::ParseError),
+        #[error("Tracing was not set up with a filter that can be reloaded")]
+        NotReloadable,
     }
 }
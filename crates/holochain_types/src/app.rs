@@ -148,6 +148,35 @@ pub struct InstallAppPayload {
     /// The app can still use existing Cells, i.e. this does not require that
     /// all Cells have DNAs with the same overridden DNA.
     pub network_seed: Option<NetworkSeed>,
+
+    /// Limits on the resources the app may use on this conductor.
+    #[serde(default)]
+    pub quotas: AppQuotas,
+}
+
+/// Limits on the resources an app may use on the conductor it is installed in.
+/// Resources without a limit are unlimited.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AppQuotas {
+    /// The maximum number of zome calls into the app's cells which may run at once.
+    /// Calls beyond this are refused rather than queued.
+    pub max_concurrent_zome_calls: Option<u32>,
+    /// The maximum number of bytes of actions and entries the app's agents
+    /// may author. Zome calls whose writes would exceed it are refused.
+    pub max_authored_bytes: Option<u64>,
+    /// The maximum size in bytes of the app's share of the caches of its
+    /// DNAs, each cache being shared equally by the apps with cells of its DNA.
+    /// Ops are evicted from the caches to keep the app within it.
+    pub max_cache_bytes: Option<u64>,
+    /// The maximum number of clone cells the app may have, enabled or disabled.
+    pub max_clone_cells: Option<u32>,
+}
+
+impl AppQuotas {
+    /// Whether any resource is limited.
+    pub fn is_limited(&self) -> bool {
+        *self != Self::default()
+    }
 }
 
 /// The possible locations of an AppBundle
@@ -410,6 +439,9 @@ pub struct InstalledAppCommon {
     role_assignments: HashMap<RoleName, AppRoleAssignment>,
     /// The manifest used to install the app.
     manifest: AppManifest,
+    /// Limits on the resources the app may use.
+    #[serde(default)]
+    quotas: AppQuotas,
 }
 
 impl InstalledAppCommon {
@@ -433,6 +465,7 @@ impl InstalledAppCommon {
             agent_key,
             role_assignments,
             manifest,
+            quotas: AppQuotas::default(),
         })
     }
 
@@ -441,6 +474,16 @@ impl InstalledAppCommon {
         &self.installed_app_id
     }
 
+    /// Accessor
+    pub fn quotas(&self) -> &AppQuotas {
+        &self.quotas
+    }
+
+    /// Replace the limits on the resources the app may use.
+    pub fn set_quotas(&mut self, quotas: AppQuotas) {
+        self.quotas = quotas;
+    }
+
     /// Accessor
     pub fn provisioned_cells(&self) -> impl Iterator<Item = (&RoleName, &CellId)> {
         self.role_assignments
@@ -780,6 +823,7 @@ impl InstalledAppCommon {
             agent_key: _agent_key,
             role_assignments,
             manifest,
+            quotas: AppQuotas::default(),
         })
     }
 
@@ -8,6 +8,7 @@ use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
 use holo_hash::HasHash;
 use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::prelude::ChainFork;
 use holochain_zome_types::prelude::ChainStatus;
 use holochain_zome_types::ActionHashed;
 use holochain_zome_types::ChainFilter;
@@ -162,6 +163,8 @@ pub enum MustGetAgentActivityResponse {
     ChainTopNotFound(ActionHash),
     /// The filter produces an empty range.
     EmptyRange,
+    /// The author has forked their chain within the requested range.
+    Forked(ChainFork),
 }
 
 impl<I: AsRef<A>, A: ChainItem> ChainFilterIter<I, A> {
@@ -384,6 +387,19 @@ impl ChainFilterRange {
     ) -> MustGetAgentActivityResponse {
         let until_hashes = self.filter.get_until().cloned();
 
+        // Keep every action in the range so the filtered chain can be
+        // checked for forks.
+        let in_range: Vec<_> = chain
+            .iter()
+            .map(|a| {
+                (
+                    a.action.action().action_seq(),
+                    a.action.action_address().clone(),
+                )
+            })
+            .filter(|(seq, _)| self.range.contains(seq))
+            .collect();
+
         // Create the filter iterator and collect the filtered actions.
         let out: Vec<_> = ChainFilterIter::new(self.filter, chain).collect();
 
@@ -404,6 +420,23 @@ impl ChainFilterRange {
                     }
                 }
 
+                // Any other action at the same sequence as an action in the
+                // chain is a fork. Report the lowest one.
+                let fork = out.iter().rev().find_map(|a| {
+                    let fork_seq = a.action.action().action_seq();
+                    in_range
+                        .iter()
+                        .find(|(seq, hash)| *seq == fork_seq && hash != a.action.action_address())
+                        .map(|(_, hash)| ChainFork {
+                            fork_seq,
+                            first_action: a.action.action_address().clone(),
+                            second_action: hash.clone(),
+                        })
+                });
+                if let Some(fork) = fork {
+                    return MustGetAgentActivityResponse::Forked(fork);
+                }
+
                 // The constraints are met the activity can be returned.
                 MustGetAgentActivityResponse::Activity(out)
             }
@@ -110,10 +110,13 @@ fn matches_chain(a: &Vec<RegisterAgentActivity>, seq: &[u32]) -> bool {
     => matches MustGetAgentActivityResponse::Activity(a) if matches_chain(&a, &[7, 6, 5]) ; "chain_top 7 until 0 take 3 chain 0 to 3 then 5 to 10")]
 #[test_case(
     forked_chain(&[0..6, 3..8]), ChainFilter::new(action_hash(&[5])).until(action_hash(&[0])).take(8), hash_to_seq(&[0, 5])
-    => matches MustGetAgentActivityResponse::Activity(a) if matches_chain(&a, &[5, 4, 3, 2, 1, 0]) ; "chain_top 5 until 0 take 8 chain 0 to 5 and 3 to 7")]
+    => matches MustGetAgentActivityResponse::Forked(f) if f.fork_seq == 3 ; "chain_top 5 until 0 take 8 chain 0 to 5 and 3 to 7")]
 #[test_case(
     forked_chain(&[0..6, 3..8]), ChainFilter::new(action_hash(&[7, 1])).take(8), |_| Some(7)
-    => matches MustGetAgentActivityResponse::Activity(a) if matches_chain(&a, &[7, 6, 5, 4, 3, 2, 1, 0]) ; "chain_top (7,1) take 8 chain 0 to 5 and 3 to 7")]
+    => matches MustGetAgentActivityResponse::Forked(f) if f.fork_seq == 3 && f.first_action == action_hash(&[3, 1]) ; "chain_top (7,1) take 8 chain 0 to 5 and 3 to 7")]
+#[test_case(
+    forked_chain(&[0..6, 3..8]), ChainFilter::new(action_hash(&[2])), |_| Some(2)
+    => matches MustGetAgentActivityResponse::Activity(a) if matches_chain(&a, &[2, 1, 0]) ; "chain_top 2 chain 0 to 5 and 3 to 7 forks outside the range")]
 #[test_case(
     forked_chain(&[4..6, 3..8]), ChainFilter::new(action_hash(&[5, 0])).until(action_hash(&[4, 1])), |h| if *h == action_hash(&[5, 0]) { Some(5) } else { Some(4) }
     => matches MustGetAgentActivityResponse::IncompleteChain ; "chain_top (5,0) until (4,1) chain (0,0) to (5,0) and (3,1) to (7,1)")]
@@ -0,0 +1,86 @@
+//! Types for tracking which actions a countersigning session is waiting on.
+//!
+//! A session is complete once every required signer has authored their
+//! action and at least `minimum_optional_signing_agents` of the optional
+//! signers who responded to the preflight request have authored theirs.
+
+use holo_hash::ActionHash;
+use holo_hash::EntryHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::prelude::*;
+
+/// The hashes of the actions that complete a countersigning session.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountersigningSessionActions {
+    /// The actions of the required signers, all of which must be present.
+    pub required: Vec<ActionHash>,
+    /// The actions of the optional signers with a response in the session.
+    pub optional: Vec<ActionHash>,
+    /// How many of the optional actions must be present.
+    pub minimum_optional: usize,
+}
+
+impl CountersigningSessionActions {
+    /// Build the session actions for a countersigned entry.
+    pub fn from_session_data(
+        session_data: &CounterSigningSessionData,
+        entry_hash: EntryHash,
+        weight: EntryRateWeight,
+    ) -> Result<Self, CounterSigningError> {
+        let hash = |actions: Vec<Action>| -> Vec<ActionHash> {
+            actions.iter().map(ActionHash::with_data_sync).collect()
+        };
+        Ok(Self {
+            required: hash(
+                session_data.build_required_action_set(entry_hash.clone(), weight.clone())?,
+            ),
+            optional: hash(session_data.build_optional_action_set(entry_hash, weight)?),
+            minimum_optional: session_data
+                .preflight_request()
+                .minimum_optional_signing_agents as usize,
+        })
+    }
+
+    /// Is this action one of the session's actions?
+    pub fn contains(&self, action_hash: &ActionHash) -> bool {
+        self.required.contains(action_hash) || self.optional.contains(action_hash)
+    }
+
+    /// Is the session complete, given which of its actions are present?
+    pub fn is_complete(&self, is_present: impl Fn(&ActionHash) -> bool) -> bool {
+        self.required.iter().all(&is_present)
+            && self.optional.iter().filter(|h| is_present(h)).count() >= self.minimum_optional
+    }
+}
+
+impl From<Vec<ActionHash>> for CountersigningSessionActions {
+    fn from(required: Vec<ActionHash>) -> Self {
+        Self {
+            required,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_with_minimum_optional_actions() {
+        let hash = |i: u8| ActionHash::from_raw_36(vec![i; 36]);
+        let actions = CountersigningSessionActions {
+            required: vec![hash(0), hash(1)],
+            optional: vec![hash(0), hash(2), hash(3)],
+            minimum_optional: 2,
+        };
+
+        // The enzyme is both required and optional so alone it is not enough.
+        assert!(!actions.is_complete(|h| [hash(0), hash(1)].contains(h)));
+        assert!(actions.is_complete(|h| [hash(0), hash(1), hash(3)].contains(h)));
+        // Every required action must be present.
+        assert!(!actions.is_complete(|h| [hash(0), hash(2), hash(3)].contains(h)));
+        assert!(actions.contains(&hash(2)));
+        assert!(!actions.contains(&hash(4)));
+    }
+}
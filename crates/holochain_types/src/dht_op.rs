@@ -23,6 +23,7 @@ use serde::Serialize;
 
 #[allow(missing_docs)]
 pub mod error;
+pub mod export;
 
 #[cfg(test)]
 pub mod tests;
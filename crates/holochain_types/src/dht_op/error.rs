@@ -20,6 +20,8 @@ pub enum DhtOpError {
     OpActionMismatch(DhtOpType, ActionType),
     #[error("Link requests without tags require a tag in the response")]
     LinkKeyTagMissing,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
 }
 
 pub type DhtOpResult<T> = Result<T, DhtOpError>;
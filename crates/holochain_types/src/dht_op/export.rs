@@ -0,0 +1,97 @@
+//! Types for moving DHT ops between conductors without a network connection.
+//!
+//! The ops of a DNA are exported from the DHT database of one conductor into
+//! a signed, compressed file, which can then be carried to another conductor
+//! and imported there, where the ops are validated just as if they had been
+//! received through gossip.
+
+use super::error::DhtOpResult;
+use super::DhtOp;
+use holo_hash::AgentPubKey;
+use holo_hash::DnaHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::prelude::*;
+use kitsune_p2p_dht::region::RegionBounds;
+
+/// Restricts which ops are included in an export.
+///
+/// Both ranges are inclusive, and leaving a range unset includes all ops.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DhtOpExportFilter {
+    /// Only include ops whose storage location lies within this range
+    /// of the DHT. If the start is greater than the end, the range
+    /// wraps around the end of the DHT.
+    #[serde(default)]
+    pub storage_loc: Option<(u32, u32)>,
+    /// Only include ops which were authored within this time window.
+    #[serde(default)]
+    pub authored_time: Option<(Timestamp, Timestamp)>,
+}
+
+impl DhtOpExportFilter {
+    /// The region of the DHT which this filter selects.
+    pub fn region_bounds(&self) -> RegionBounds {
+        RegionBounds::new(
+            self.storage_loc.unwrap_or((0, u32::MAX)),
+            self.authored_time
+                .unwrap_or((Timestamp::MIN, Timestamp::MAX)),
+        )
+    }
+}
+
+/// A set of ops exported from the DHT database of a conductor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct DhtOpExport {
+    /// The DNA which all the ops belong to.
+    pub dna_hash: DnaHash,
+    /// When the export was made.
+    pub exported_at: Timestamp,
+    /// The exported ops.
+    pub ops: Vec<DhtOp>,
+}
+
+impl DhtOpExport {
+    /// Serialize the export into the bytes which get signed.
+    pub fn to_bytes(&self) -> DhtOpResult<Vec<u8>> {
+        Ok(holochain_serialized_bytes::encode(self)?)
+    }
+}
+
+/// A serialized [`DhtOpExport`] together with the signature of the agent
+/// who made it. This is what an export file contains, once decompressed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct SignedDhtOpExport {
+    /// The agent who signed the export.
+    pub signer: AgentPubKey,
+    /// The signature of `export_bytes` by the signer.
+    pub signature: Signature,
+    /// The serialized [`DhtOpExport`].
+    #[serde(with = "serde_bytes")]
+    pub export_bytes: Vec<u8>,
+}
+
+impl SignedDhtOpExport {
+    /// Deserialize the signed export.
+    /// Note that this does not check the signature.
+    pub fn export(&self) -> DhtOpResult<DhtOpExport> {
+        Ok(holochain_serialized_bytes::decode(&self.export_bytes)?)
+    }
+
+    /// Serialize and gzip this into the content of an export file.
+    pub fn to_file_content(&self) -> DhtOpResult<Vec<u8>> {
+        use std::io::Write;
+        let data = holochain_serialized_bytes::encode(self)?;
+        let mut enc = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        enc.write_all(&data)?;
+        Ok(enc.finish()?)
+    }
+
+    /// Read this back from the content of an export file.
+    pub fn from_file_content(data: &[u8]) -> DhtOpResult<Self> {
+        use std::io::Read;
+        let mut gz = flate2::read::GzDecoder::new(data);
+        let mut bytes = Vec::new();
+        gz.read_to_end(&mut bytes)?;
+        Ok(holochain_serialized_bytes::decode(&bytes)?)
+    }
+}
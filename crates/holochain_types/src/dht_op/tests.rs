@@ -462,3 +462,32 @@ fn test_all_ops_basis() {
         check_all_ops(record);
     }
 }
+
+#[test]
+fn export_file_roundtrip() {
+    let ops: Vec<DhtOp> = all_records()
+        .iter()
+        .flat_map(|record| produce_ops_from_record(record).unwrap())
+        .collect();
+    let export = DhtOpExport {
+        dna_hash: holo_hash::fixt::DnaHashFixturator::new(Predictable)
+            .next()
+            .unwrap(),
+        exported_at: Timestamp::now(),
+        ops,
+    };
+    let signed = SignedDhtOpExport {
+        signer: holo_hash::fixt::AgentPubKeyFixturator::new(Predictable)
+            .next()
+            .unwrap(),
+        signature: fixt!(Signature),
+        export_bytes: export.to_bytes().unwrap(),
+    };
+    let content = signed.to_file_content().unwrap();
+    let read = SignedDhtOpExport::from_file_content(&content).unwrap();
+    assert_eq!(read, signed);
+    assert_eq!(read.export().unwrap(), export);
+
+    // Garbage is not mistaken for an export
+    assert!(SignedDhtOpExport::from_file_content(&content[1..]).is_err());
+}
@@ -20,6 +20,7 @@ pub mod autonomic;
 pub mod chain;
 pub mod chc;
 pub mod combinators;
+pub mod countersigning;
 pub mod db;
 pub mod db_cache;
 pub mod dht_op;
@@ -39,6 +40,7 @@ pub mod signal;
 pub mod sql;
 pub mod wasmer_types;
 pub mod web_app;
+pub mod zome_call_recording;
 pub mod zome_types;
 
 #[cfg(feature = "test_utils")]
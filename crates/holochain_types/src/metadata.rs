@@ -1,9 +1,14 @@
 //! Types for getting and storing metadata
 
 use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holochain_serialized_bytes::prelude::*;
+pub use holochain_zome_types::metadata::DhtMetadata;
 pub use holochain_zome_types::metadata::EntryDhtStatus;
+pub use holochain_zome_types::metadata::MetadataAction;
+use holochain_zome_types::ValidationStatus;
 use holochain_zome_types::{ActionHashed, Timestamp};
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 
 /// Timestamp of when the action was created with the actions hash.
@@ -17,13 +22,13 @@ pub struct TimedActionHash {
 
 /// Metadata returned from a GetMeta request.
 /// The Ord derive on TimedActionHash means each set is ordered by time.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
 pub struct MetadataSet {
     /// Actions that created or updated an entry.
     /// These are the actions that show the entry exists.
     pub actions: BTreeSet<TimedActionHash>,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Actions that created or updated an entry
+    /// but were rejected by validation.
     pub invalid_actions: BTreeSet<TimedActionHash>,
     /// Deletes on an action
     pub deletes: BTreeSet<TimedActionHash>,
@@ -33,6 +38,98 @@ pub struct MetadataSet {
     /// This is simply a faster way of determining if
     /// there are any live actions on an entry.
     pub entry_dht_status: Option<EntryDhtStatus>,
+    /// The validation status the authority holds for the
+    /// basis hash, if it holds a record or entry for it.
+    #[serde(default)]
+    pub validation_status: Option<ValidationStatus>,
+    /// The authors of all the actions in this set.
+    #[serde(default)]
+    pub authors: BTreeMap<ActionHash, AgentPubKey>,
+    /// The number of links on the basis hash that
+    /// have not been deleted.
+    #[serde(default)]
+    pub link_count: usize,
+}
+
+impl MetadataSet {
+    /// Does the authority that returned this set hold anything for the basis hash?
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+            && self.invalid_actions.is_empty()
+            && self.deletes.is_empty()
+            && self.updates.is_empty()
+            && self.validation_status.is_none()
+            && self.link_count == 0
+    }
+
+    /// Merge the sets returned by several authorities into
+    /// a single view of the metadata for a basis hash.
+    ///
+    /// Returns `None` if none of the authorities hold anything.
+    pub fn merge(sets: impl IntoIterator<Item = MetadataSet>) -> Option<DhtMetadata> {
+        let mut merged = MetadataSet::default();
+        let mut holders = 0;
+        for set in sets {
+            if set.is_empty() {
+                continue;
+            }
+            holders += 1;
+            merged.actions.extend(set.actions);
+            merged.invalid_actions.extend(set.invalid_actions);
+            merged.deletes.extend(set.deletes);
+            merged.updates.extend(set.updates);
+            merged.authors.extend(set.authors);
+            merged.link_count = merged.link_count.max(set.link_count);
+            merged.validation_status =
+                merge_validation_status(merged.validation_status, set.validation_status);
+            merged.entry_dht_status = match (merged.entry_dht_status, set.entry_dht_status) {
+                (Some(EntryDhtStatus::Live), _) | (_, Some(EntryDhtStatus::Live)) => {
+                    Some(EntryDhtStatus::Live)
+                }
+                (a, b) => a.or(b),
+            };
+        }
+        if holders == 0 {
+            return None;
+        }
+        let authors = &merged.authors;
+        let to_vec = |set: BTreeSet<TimedActionHash>| -> Vec<MetadataAction> {
+            set.into_iter()
+                .map(|t| MetadataAction {
+                    author: authors.get(&t.action_hash).cloned(),
+                    action_hash: t.action_hash,
+                    timestamp: t.timestamp,
+                })
+                .collect()
+        };
+        Some(DhtMetadata {
+            actions: to_vec(merged.actions),
+            rejected_actions: to_vec(merged.invalid_actions),
+            updates: to_vec(merged.updates),
+            deletes: to_vec(merged.deletes),
+            validation_status: merged.validation_status,
+            entry_dht_status: merged.entry_dht_status,
+            link_count: merged.link_count,
+            holders,
+        })
+    }
+}
+
+/// Combine two validation statuses, preferring
+/// `Valid` over `Rejected` over `Abandoned`.
+pub fn merge_validation_status(
+    a: Option<ValidationStatus>,
+    b: Option<ValidationStatus>,
+) -> Option<ValidationStatus> {
+    let rank = |s: &ValidationStatus| match s {
+        ValidationStatus::Valid => 2,
+        ValidationStatus::Rejected => 1,
+        ValidationStatus::Abandoned => 0,
+    };
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if rank(&b) > rank(&a) { b } else { a }),
+        (a, b) => a.or(b),
+    }
 }
 
 impl From<ActionHashed> for TimedActionHash {
@@ -16,8 +16,10 @@ pub use crate::autonomic::*;
 pub use crate::chain::*;
 pub use crate::chc::*;
 pub use crate::combinators::*;
+pub use crate::countersigning::*;
 pub use crate::db::*;
 pub use crate::dht_op::error::*;
+pub use crate::dht_op::export::*;
 pub use crate::dht_op::*;
 pub use crate::dna::error::*;
 pub use crate::dna::wasm::*;
@@ -30,6 +32,7 @@ pub use crate::record::error::*;
 pub use crate::record::*;
 pub use crate::signal::*;
 pub use crate::wasmer_types::*;
+pub use crate::zome_call_recording::*;
 
 #[cfg(feature = "fixturators")]
 pub use crate::fixt::TimestampFixturator;
@@ -36,6 +36,8 @@ pub enum SystemSignal {
     Test(String),
     /// A countersigning session has successfully completed.
     SuccessfulCountersigning(holo_hash::EntryHash),
+    /// A countersigning session was abandoned through the admin interface.
+    AbandonedCountersigning(holo_hash::EntryHash),
 }
 
 /// Create a test signal
@@ -0,0 +1,39 @@
+//! Types for recording zome calls, so that they can be inspected and replayed.
+//!
+//! When recording is switched on for a cell, each zome call made to it is
+//! recorded along with every host function call made by the zome during the
+//! call, and the result each of those host function calls returned.
+//! Feeding the recorded host function results back to the zome in place of
+//! the real host functions re-executes the call deterministically.
+
+use holochain_serialized_bytes::prelude::*;
+use holochain_wasmer_host::prelude::WasmError;
+use holochain_zome_types::prelude::*;
+
+/// A single call from a zome to a host function, and its result.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedHostCall {
+    /// The name of the host function which was called.
+    pub host_fn: String,
+    /// The serialized input to the host function.
+    pub input: ExternIO,
+    /// The serialized output of the host function, or the error it returned
+    /// to the zome.
+    pub output: Result<ExternIO, WasmError>,
+}
+
+/// A zome call made to a cell with recording switched on.
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct ZomeCallRecording {
+    /// The call as it was made, minus the signature.
+    pub call: ZomeCallUnsigned,
+    /// The signature of the call by its provenance.
+    pub signature: Signature,
+    /// When the call was started.
+    pub recorded_at: Timestamp,
+    /// Every host function called by the zome during the call, in the order
+    /// they were called.
+    pub host_calls: Vec<RecordedHostCall>,
+    /// The response to the call, or the error it failed with.
+    pub response: Result<ZomeCallResponse, String>,
+}
@@ -24,9 +24,6 @@
 //! information needed to refer to the capability as well as the secret needed
 //! to send to the Grantor.
 
-mod grant;
-pub use grant::*;
-
 pub use holochain_integrity_types::capability::*;
 use serde::{Deserialize, Serialize};
 
@@ -317,7 +317,7 @@ fixturator!(
                     fns.insert(GrantedFunctionFixturator::new(Empty).next().unwrap());
                 }
                 GrantedFunctions::Listed(fns)
-            }, // CurryPayloadsFixturator::new(Empty).next().unwrap(),
+            },
         )
     };
     curve Unpredictable {
@@ -338,7 +338,6 @@ fixturator!(
                 }
                 GrantedFunctions::Listed(fns)
             },
-            // CurryPayloadsFixturator::new(Unpredictable).next().unwrap(),
         )
     };
     curve Predictable {
@@ -3,6 +3,9 @@ use crate::record::Record;
 use crate::record::SignedActionHashed;
 use crate::validate::ValidationStatus;
 use crate::Entry;
+use crate::Timestamp;
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holochain_serialized_bytes::prelude::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
@@ -91,3 +94,40 @@ pub enum EntryDhtStatus {
     /// **not implemented** We have agreed to drop this [Entry] content from the system. Action can stay with no entry
     Purged,
 }
+
+/// The metadata the DHT holds for any hash, as returned by `get_meta`.
+///
+/// This is gathered from the authorities for the hash without
+/// fetching the actions or entries themselves.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+pub struct DhtMetadata {
+    /// Valid actions that created or updated the entry or record.
+    pub actions: Vec<MetadataAction>,
+    /// Actions that created or updated the entry or record
+    /// but were rejected by validation.
+    pub rejected_actions: Vec<MetadataAction>,
+    /// Updates on the entry or record.
+    pub updates: Vec<MetadataAction>,
+    /// Deletes on the entry or record.
+    pub deletes: Vec<MetadataAction>,
+    /// The best validation status reported by any authority.
+    /// `None` if no authority holds a record or entry for the hash.
+    pub validation_status: Option<ValidationStatus>,
+    /// The status of the entry, if the hash is an entry hash.
+    pub entry_dht_status: Option<EntryDhtStatus>,
+    /// The number of links on the hash that have not been deleted.
+    pub link_count: usize,
+    /// The number of authorities that hold data for the hash.
+    pub holders: usize,
+}
+
+/// An action referenced by [`DhtMetadata`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MetadataAction {
+    /// The hash of the action.
+    pub action_hash: ActionHash,
+    /// The author of the action, if the authority reported it.
+    pub author: Option<AgentPubKey>,
+    /// When the action was authored.
+    pub timestamp: Timestamp,
+}
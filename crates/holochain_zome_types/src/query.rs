@@ -3,12 +3,16 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+use crate::action::Action;
 use crate::action::ActionType;
 use crate::action::EntryType;
+use crate::timestamp::Timestamp;
 use crate::warrant::Warrant;
 use crate::ActionHashed;
 use crate::Record;
 use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holo_hash::AnyLinkableHash;
 use holo_hash::EntryHash;
 use holo_hash::HasHash;
 pub use holochain_serialized_bytes::prelude::*;
@@ -84,6 +88,26 @@ pub struct ChainQueryFilter {
     /// The query should be ordered in descending order (default is ascending),
     /// when run as a database query. There is no provisioning for in-memory ordering.
     pub order_descending: bool,
+    /// Filter by the time the actions were authored.
+    /// Inclusive start, inclusive end.
+    #[serde(default)]
+    pub timestamp_range: Option<(Timestamp, Timestamp)>,
+    /// Only include links whose base or target is an entry or action authored
+    /// by this agent, or the agent's key itself.
+    /// Authorship is resolved against the actions being queried, so from a
+    /// source chain this finds the links to the chain's own entries.
+    /// `DeleteLink` actions never match, as they don't carry the link target.
+    #[serde(default)]
+    pub linked_agent: Option<AgentPubKey>,
+    /// Continue a previous query from the action with this hash, which is
+    /// itself excluded. Passing the hash of the last record returned by a query
+    /// with a `limit` fetches the next page of results.
+    /// A cursor which isn't among the actions being queried matches nothing.
+    #[serde(default)]
+    pub cursor: Option<ActionHash>,
+    /// Return at most this many records.
+    #[serde(default)]
+    pub limit: Option<u32>,
 }
 
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -237,12 +261,46 @@ impl ChainQueryFilter {
         self
     }
 
-    /// Set the order to ascending.
+    /// Set the order to descending.
     pub fn descending(mut self) -> Self {
         self.order_descending = true;
         self
     }
 
+    /// Filter on the time the actions were authored, inclusive at both ends.
+    pub fn timestamp_range(mut self, start: Timestamp, end: Timestamp) -> Self {
+        self.timestamp_range = Some((start, end));
+        self
+    }
+
+    /// Only include links from or to entries and actions authored by the given agent.
+    pub fn linked_agent(mut self, agent: AgentPubKey) -> Self {
+        self.linked_agent = Some(agent);
+        self
+    }
+
+    /// Continue from after the action with this hash.
+    pub fn cursor(mut self, cursor: ActionHash) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// Return at most this many records.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Is the sequence range bounded by action hashes? Such ranges can only
+    /// be applied to a complete set of actions, because of fork disambiguation.
+    pub fn is_hash_bounded(&self) -> bool {
+        matches!(
+            self.sequence_range,
+            ChainQueryFilterRange::ActionHashRange(_, _)
+                | ChainQueryFilterRange::ActionHashTerminated(_, _)
+        )
+    }
+
     /// If the sequence range supports fork disambiguation, apply it to remove
     /// actions that are not in the correct branch.
     /// Numerical range bounds do NOT support fork disambiguation, and neither
@@ -300,35 +358,111 @@ impl ChainQueryFilter {
         }
     }
 
+    /// Check an action against every filter of the query except the sequence
+    /// range, which can only be applied to a set of actions, and the cursor and
+    /// limit, which depend on the order of the results.
+    /// `authored_by_linked_agent` tells whether a link base or target was
+    /// authored by the `linked_agent`.
+    pub fn matches_action(
+        &self,
+        action: &Action,
+        authored_by_linked_agent: impl Fn(&AnyLinkableHash) -> bool,
+    ) -> bool {
+        self.action_type
+            .as_ref()
+            .map(|action_types| action_types.contains(&action.action_type()))
+            .unwrap_or(true)
+            && self
+                .entry_type
+                .as_ref()
+                .map(|entry_types| {
+                    action
+                        .entry_type()
+                        .map(|entry_type| entry_types.contains(entry_type))
+                        .unwrap_or(false)
+                })
+                .unwrap_or(true)
+            && self
+                .entry_hashes
+                .as_ref()
+                .map(|entry_hashes| match action.entry_hash() {
+                    Some(entry_hash) => entry_hashes.contains(entry_hash),
+                    None => false,
+                })
+                .unwrap_or(true)
+            && self
+                .timestamp_range
+                .map(|(start, end)| start <= action.timestamp() && action.timestamp() <= end)
+                .unwrap_or(true)
+            && self
+                .linked_agent
+                .as_ref()
+                .map(|agent| match action {
+                    Action::CreateLink(create_link) => {
+                        [&create_link.base_address, &create_link.target_address]
+                            .into_iter()
+                            .any(|linked| {
+                                *linked == AnyLinkableHash::from(agent.clone())
+                                    || authored_by_linked_agent(linked)
+                            })
+                    }
+                    _ => false,
+                })
+                .unwrap_or(true)
+    }
+
+    /// The hashes of the entries and actions among a set of actions which
+    /// were authored by the `linked_agent`.
+    fn linked_agent_hashes(&self, actions: &[ActionHashed]) -> HashSet<AnyLinkableHash> {
+        let agent = match &self.linked_agent {
+            Some(agent) => agent,
+            None => return HashSet::new(),
+        };
+        actions
+            .iter()
+            .filter(|action| action.author() == agent)
+            .flat_map(|action| {
+                std::iter::once(action.as_hash().clone().into())
+                    .chain(action.entry_hash().cloned().map(Into::into))
+            })
+            .collect()
+    }
+
     /// Filter a vector of hashed actions according to the query.
+    /// The cursor and limit are applied in the order the actions are given.
     pub fn filter_actions(&self, actions: Vec<ActionHashed>) -> Vec<ActionHashed> {
-        self.disambiguate_forks(actions)
-            .into_iter()
-            .filter(|action| {
-                self.action_type
-                    .as_ref()
-                    .map(|action_types| action_types.contains(&action.as_ref().action_type()))
-                    .unwrap_or(true)
-                    && self
-                        .entry_type
-                        .as_ref()
-                        .map(|entry_types| {
-                            action
-                                .entry_type()
-                                .map(|entry_type| entry_types.contains(entry_type))
-                                .unwrap_or(false)
-                        })
-                        .unwrap_or(true)
-                    && self
-                        .entry_hashes
-                        .as_ref()
-                        .map(|entry_hashes| match action.entry_hash() {
-                            Some(entry_hash) => entry_hashes.contains(entry_hash),
-                            None => false,
-                        })
-                        .unwrap_or(true)
+        let linked_agent_hashes = self.linked_agent_hashes(&actions);
+        let mut actions = actions;
+        if let Some(cursor) = &self.cursor {
+            actions = match actions.iter().position(|action| action.as_hash() == cursor) {
+                Some(i) => actions.split_off(i + 1),
+                None => Vec::new(),
+            };
+        }
+        // Disambiguating walks the chain backwards, so keep the given order
+        // for the limit to apply to.
+        let actions = if self.is_hash_bounded() {
+            let in_range = self
+                .disambiguate_forks(actions.clone())
+                .into_iter()
+                .map(|action| action.into_hash())
+                .collect::<HashSet<ActionHash>>();
+            actions
+                .into_iter()
+                .filter(|action| in_range.contains(action.as_hash()))
+                .collect()
+        } else {
+            self.disambiguate_forks(actions)
+        };
+        let actions = actions.into_iter().filter(|action| {
+            self.matches_action(action.as_content(), |linked| {
+                linked_agent_hashes.contains(linked)
             })
-            .collect()
+        });
+        match self.limit {
+            Some(limit) => actions.take(limit as usize).collect(),
+            None => actions.collect(),
+        }
     }
 
     /// Filter a vector of records according to the query.
@@ -354,12 +488,15 @@ impl ChainQueryFilter {
 #[cfg(feature = "fixturators")]
 mod tests {
     use super::ChainQueryFilter;
+    use crate::action::Action;
     use crate::action::EntryType;
     use crate::fixt::AppEntryDefFixturator;
     use crate::fixt::*;
+    use crate::timestamp::Timestamp;
     use crate::ActionHashed;
     use crate::ChainQueryFilterRange;
     use ::fixt::prelude::*;
+    use holo_hash::AnyLinkableHash;
     use holo_hash::HasHash;
 
     /// Create three Actions with various properties.
@@ -652,4 +789,89 @@ mod tests {
             [true, false, false, false, true, true, false].to_vec()
         );
     }
+
+    #[test]
+    fn filter_by_timestamp_range() {
+        let mut actions = fixtures();
+        for (i, action) in actions.iter_mut().enumerate() {
+            let mut content = action.as_content().clone();
+            match &mut content {
+                Action::Create(create) => create.timestamp = Timestamp(i as i64),
+                Action::Update(update) => update.timestamp = Timestamp(i as i64),
+                Action::CreateLink(create_link) => create_link.timestamp = Timestamp(i as i64),
+                _ => unreachable!(),
+            }
+            *action = ActionHashed::from_content_sync(content);
+        }
+
+        assert_eq!(
+            map_query(
+                &ChainQueryFilter::new().timestamp_range(Timestamp(2), Timestamp(4)),
+                &actions
+            ),
+            [false, false, true, true, true, false, false].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_by_linked_agent() {
+        let agent = fixt!(AgentPubKey);
+        let mut own = fixt!(Create);
+        own.author = agent.clone();
+        let own = ActionHashed::from_content_sync(own.into());
+        let others = ActionHashed::from_content_sync(fixt!(Create).into());
+
+        let link_to = |base: AnyLinkableHash, target: AnyLinkableHash| {
+            let mut create_link = fixt!(CreateLink);
+            create_link.base_address = base;
+            create_link.target_address = target;
+            ActionHashed::from_content_sync(create_link.into())
+        };
+        let actions = vec![
+            own.clone(),
+            others.clone(),
+            // To the agent's entry.
+            link_to(
+                fixt!(AnyLinkableHash),
+                own.entry_hash().unwrap().clone().into(),
+            ),
+            // From the agent's action.
+            link_to(own.as_hash().clone().into(), fixt!(AnyLinkableHash)),
+            // To the agent itself.
+            link_to(fixt!(AnyLinkableHash), agent.clone().into()),
+            // Between entries of someone else.
+            link_to(
+                others.as_hash().clone().into(),
+                others.entry_hash().unwrap().clone().into(),
+            ),
+        ];
+
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().linked_agent(agent), &actions),
+            [false, false, true, true, true, false].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_with_cursor_and_limit() {
+        let actions = fixtures();
+
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().limit(2), &actions),
+            [true, true, false, false, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(
+                &ChainQueryFilter::new()
+                    .cursor(actions[1].as_hash().clone())
+                    .limit(2),
+                &actions
+            ),
+            [false, false, true, true, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().cursor(fixt!(ActionHash)), &actions),
+            [false; 7].to_vec()
+        );
+    }
 }
@@ -28,4 +28,9 @@ pub struct RemoteSignal {
     pub agents: Vec<AgentPubKey>,
     /// The signal to send.
     pub signal: crate::ExternIO,
+    /// If true, signals that cannot be delivered are queued by the sending
+    /// conductor and retried when the recipient comes back online, and the
+    /// recipient acknowledges delivery.
+    #[serde(default)]
+    pub reliable: bool,
 }
@@ -88,6 +88,7 @@ wasm_io_types! {
     fn get_agent_activity (zt::agent_activity::GetAgentActivityInput) -> zt::query::AgentActivity;
 
     fn get_details (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::Details>>;
+    fn get_meta (Vec<zt::entry::GetInput>) -> Vec<Option<zt::metadata::DhtMetadata>>;
 
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
@@ -114,6 +115,9 @@ wasm_io_types! {
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<crate::Record>;
 
+    // Count the records a query of the source chain would return.
+    fn query_count (zt::query::ChainQueryFilter) -> usize;
+
     // the length of random bytes to create
     fn random_bytes (u32) -> zt::bytes::Bytes;
 
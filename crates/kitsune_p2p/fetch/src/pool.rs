@@ -9,7 +9,14 @@
 //! from the current moment. The items thus returned are not guaranteed to be returned in
 //! order of last_fetch time, but they are guaranteed to be at least as old as the specified
 //! interval.
+//!
+//! Items carry a [`FetchPriority`], determined by the host from the item's context.
+//! Higher priority items are always offered before lower priority ones.
+//!
+//! Sources which fail to respond are backed off exponentially across all items,
+//! and the number of items held for any one space can be capped by the host.
 
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::time::{Duration, Instant};
 
@@ -74,25 +81,72 @@ pub trait FetchPoolConfig: 'static + Send + Sync {
         std::time::Duration::from_secs(5 * 60)
     }
 
+    /// The maximum amount of time a source which keeps failing will be backed off for.
+    /// Each consecutive failure doubles the backoff, starting at `source_retry_delay`.
+    fn source_max_backoff(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(60 * 60)
+    }
+
+    /// The maximum number of items which may be held in the pool for a single space.
+    /// New items for a space which is at its quota are dropped, and will be
+    /// rediscovered through gossip later. `None` means no limit.
+    fn space_quota(&self) -> Option<usize> {
+        None
+    }
+
+    /// Determine the priority of an item from its (merged) context.
+    fn fetch_priority(&self, _context: Option<FetchContext>) -> FetchPriority {
+        FetchPriority::Normal
+    }
+
     /// When a fetch key is added twice, this determines how the two different contexts
     /// get reconciled.
     fn merge_fetch_contexts(&self, a: u32, b: u32) -> u32;
 }
 
+/// The relative importance of an item in the pool.
+/// Items of higher priority are always offered before items of lower priority.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+pub enum FetchPriority {
+    /// Fetch only once nothing more important is pending
+    Low,
+    /// The priority of ops discovered through gossip
+    #[default]
+    Normal,
+    /// Fetch before anything else, e.g. ops we are an authority for
+    High,
+}
+
+impl FetchPriority {
+    /// All priorities, from highest to lowest
+    pub const DESCENDING: [FetchPriority; 3] = [
+        FetchPriority::High,
+        FetchPriority::Normal,
+        FetchPriority::Low,
+    ];
+}
+
 /// The actual inner state of the FetchPool, from which items can be obtained
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct State {
     /// Items ready to be fetched
     queue: LinkedHashMap<FetchKey, FetchPoolItem>,
-}
-
-#[allow(clippy::derivable_impls)]
-impl Default for State {
-    fn default() -> Self {
-        Self {
-            queue: Default::default(),
-        }
-    }
+    /// Number of items held for each space, used to enforce quotas
+    space_counts: HashMap<KSpace, usize>,
+    /// Failure backoff for sources, tracked across all items
+    backoff: HashMap<FetchSource, SourceBackoff>,
 }
 
 // TODO: move this to host, but for now, for convenience, we just use this one config
@@ -163,6 +217,38 @@ impl FetchPool {
             out
         })
     }
+
+    /// Record that a fetch request to this source failed.
+    /// The source will be backed off exponentially for all items.
+    pub fn source_failed(&self, source: &FetchSource) {
+        self.state
+            .share_mut(|s| s.source_failed(&*self.config, source.clone()));
+    }
+
+    /// Record that a fetch request to this source succeeded,
+    /// clearing any backoff for the source.
+    pub fn source_succeeded(&self, source: &FetchSource) {
+        self.state.share_mut(|s| {
+            s.backoff.remove(source);
+        });
+    }
+
+    /// Get a serializable snapshot of all items for a space,
+    /// suitable for persisting the pool across restarts.
+    pub fn records(&self, space: &KSpace) -> Vec<FetchPoolRecord> {
+        self.state.share_ref(|s| s.records(space))
+    }
+
+    /// Restore items for a space which were previously obtained through [`FetchPool::records`].
+    /// Records for keys which are already in the pool are ignored.
+    pub fn restore(&self, space: KSpace, records: Vec<FetchPoolRecord>) {
+        self.state.share_mut(|s| {
+            for record in records {
+                s.restore(&*self.config, space.clone(), record);
+            }
+            tracing::debug!("FetchPool (size = {}) items restored", s.queue.len());
+        })
+    }
 }
 
 impl State {
@@ -182,6 +268,16 @@ impl State {
 
         match self.queue.entry(key) {
             Entry::Vacant(e) => {
+                let count = self.space_counts.entry(space.clone()).or_default();
+                if config.space_quota().map(|q| *count >= q).unwrap_or(false) {
+                    tracing::debug!(
+                        "FetchPool space quota reached, dropping item: {:?}",
+                        e.key()
+                    );
+                    return;
+                }
+                *count += 1;
+
                 let sources = if let Some(author) = author {
                     Sources(vec![SourceRecord::new(source), SourceRecord::agent(author)])
                 } else {
@@ -192,6 +288,7 @@ impl State {
                     space,
                     size,
                     context,
+                    priority: config.fetch_priority(context),
                     last_fetch: None,
                 };
                 e.insert(item);
@@ -202,25 +299,102 @@ impl State {
                 v.context = match (v.context.take(), context) {
                     (Some(a), Some(b)) => Some(config.merge_fetch_contexts(*a, *b).into()),
                     (a, b) => a.and(b),
-                }
+                };
+                v.priority = v.priority.max(config.fetch_priority(v.context));
             }
         }
     }
 
+    /// Restore a single persisted record. If the key is already present,
+    /// the record is ignored.
+    pub fn restore(
+        &mut self,
+        config: &dyn FetchPoolConfig,
+        space: KSpace,
+        record: FetchPoolRecord,
+    ) {
+        let FetchPoolRecord {
+            key,
+            sources,
+            size,
+            context,
+        } = record;
+        if self.queue.contains_key(&key) {
+            return;
+        }
+        // Sources are pushed to the front, so restore them in reverse
+        // to preserve the original order.
+        for source in sources.into_iter().rev() {
+            self.push(
+                config,
+                FetchPoolPush {
+                    key: key.clone(),
+                    space: space.clone(),
+                    source,
+                    size,
+                    author: None,
+                    context,
+                },
+            );
+        }
+    }
+
+    /// Get a serializable snapshot of all items for a space.
+    pub fn records(&self, space: &KSpace) -> Vec<FetchPoolRecord> {
+        self.queue
+            .iter()
+            .filter(|(_, v)| v.space == *space)
+            .map(|(k, v)| FetchPoolRecord {
+                key: k.clone(),
+                sources: v.sources.0.iter().map(|s| s.source.clone()).collect(),
+                size: v.size,
+                context: v.context,
+            })
+            .collect()
+    }
+
+    /// Record a failed request to a source, doubling its backoff.
+    pub fn source_failed(&mut self, config: &dyn FetchPoolConfig, source: FetchSource) {
+        let backoff = self.backoff.entry(source).or_insert(SourceBackoff {
+            failures: 0,
+            until: Instant::now(),
+        });
+        backoff.failures = backoff.failures.saturating_add(1);
+        let delay = config
+            .source_retry_delay()
+            .checked_mul(2u32.saturating_pow(backoff.failures - 1))
+            .unwrap_or(Duration::MAX)
+            .min(config.source_max_backoff());
+        backoff.until = Instant::now() + delay;
+    }
+
     /// Access queue items through mutable iteration. Items accessed will be moved
     /// to the end of the queue.
     ///
     /// Only items whose `last_fetch` is more than `interval` ago will be returned.
     pub fn iter_mut<'a>(&'a mut self, config: &'a dyn FetchPoolConfig) -> StateIter {
+        // Group the keys by priority once, in queue order, so that each call
+        // to `next` only looks at items of the priority being offered.
+        let mut keys: BTreeMap<FetchPriority, VecDeque<FetchKey>> = BTreeMap::new();
+        for (key, item) in self.queue.iter() {
+            keys.entry(item.priority)
+                .or_default()
+                .push_back(key.clone());
+        }
         StateIter {
             state: self,
             config,
+            keys,
         }
     }
 
     /// When an item has been successfully fetched, we can remove it from the queue.
     pub fn remove(&mut self, key: &FetchKey) -> Option<FetchPoolItem> {
-        self.queue.remove(key)
+        let removed = self.queue.remove(key);
+        if let Some(item) = &removed {
+            decrement(&mut self.space_counts, &item.space);
+        }
+        removed
     }
 
     /// Get a string summary of the queue's contents
@@ -267,30 +441,38 @@ impl State {
 pub struct StateIter<'a> {
     state: &'a mut State,
     config: &'a dyn FetchPoolConfig,
+    /// The keys not yet checked, by priority
+    keys: BTreeMap<FetchPriority, VecDeque<FetchKey>>,
 }
 
 impl<'a> Iterator for StateIter<'a> {
     type Item = (FetchKey, KSpace, FetchSource, Option<FetchContext>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let keys: Vec<_> = self
-            .state
-            .queue
-            .keys()
-            .take(NUM_ITEMS_PER_POLL)
-            .cloned()
-            .collect();
-        for key in keys {
-            let item = self.state.queue.get_refresh(&key)?;
-            let item_not_recently_fetched = item
-                .last_fetch
-                .map(|t| t.elapsed() >= self.config.item_retry_delay())
-                .unwrap_or(true);
-            if item_not_recently_fetched {
-                if let Some(source) = item.sources.next(self.config.source_retry_delay()) {
-                    let space = item.space.clone();
-                    item.last_fetch = Some(Instant::now());
-                    return Some((key, space, source, item.context));
+        // Highest priority first.
+        for keys in self.keys.values_mut().rev() {
+            for _ in 0..NUM_ITEMS_PER_POLL {
+                let key = match keys.pop_front() {
+                    Some(key) => key,
+                    None => break,
+                };
+                let item = match self.state.queue.get_refresh(&key) {
+                    Some(item) => item,
+                    None => continue,
+                };
+                let item_not_recently_fetched = item
+                    .last_fetch
+                    .map(|t| t.elapsed() >= self.config.item_retry_delay())
+                    .unwrap_or(true);
+                if item_not_recently_fetched {
+                    if let Some(source) = item
+                        .sources
+                        .next(self.config.source_retry_delay(), &self.state.backoff)
+                    {
+                        let space = item.space.clone();
+                        item.last_fetch = Some(Instant::now());
+                        return Some((key, space, source, item.context));
+                    }
                 }
             }
         }
@@ -298,6 +480,16 @@ impl<'a> Iterator for StateIter<'a> {
     }
 }
 
+/// Decrement a count, removing the entry when it reaches zero.
+fn decrement<K: Eq + std::hash::Hash>(counts: &mut HashMap<K, usize>, key: &K) {
+    if let Some(count) = counts.get_mut(key) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            counts.remove(key);
+        }
+    }
+}
+
 /// An item in the queue, corresponding to a single op or region to fetch
 #[derive(Debug, PartialEq, Eq)]
 pub struct FetchPoolItem {
@@ -310,10 +502,35 @@ pub struct FetchPoolItem {
     size: Option<RoughInt>,
     /// Opaque user data specified by the host
     pub context: Option<FetchContext>,
+    /// The priority of this item, as determined by the host from its context
+    priority: FetchPriority,
     /// The last time we tried fetching this item from any source
     last_fetch: Option<Instant>,
 }
 
+/// A serializable snapshot of an item in the pool, without any timing information.
+/// Used by the host to persist the pool across restarts.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FetchPoolRecord {
+    /// Description of what to fetch.
+    pub key: FetchKey,
+    /// Known sources from whom we can fetch this item, in order.
+    pub sources: Vec<FetchSource>,
+    /// The approximate size of the item
+    pub size: Option<RoughInt>,
+    /// Opaque "context" to be provided and interpreted by the host.
+    pub context: Option<FetchContext>,
+}
+
+/// Exponential backoff state for a source which has failed to respond.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SourceBackoff {
+    /// Number of consecutive failures
+    failures: u32,
+    /// The source will not be used again until this time
+    until: Instant,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct SourceRecord {
     source: FetchSource,
@@ -341,7 +558,12 @@ impl SourceRecord {
 struct Sources(Vec<SourceRecord>);
 
 impl Sources {
-    fn next(&mut self, interval: Duration) -> Option<FetchSource> {
+    fn next(
+        &mut self,
+        interval: Duration,
+        backoff: &HashMap<FetchSource, SourceBackoff>,
+    ) -> Option<FetchSource> {
+        let now = Instant::now();
         if let Some((i, agent)) = self
             .0
             .iter()
@@ -350,6 +572,10 @@ impl Sources {
                 s.last_request
                     .map(|t| t.elapsed() >= interval)
                     .unwrap_or(true)
+                    && backoff
+                        .get(&s.source)
+                        .map(|b| b.until <= now)
+                        .unwrap_or(true)
             })
             .map(|(i, s)| (i, s.source.clone()))
         {
@@ -363,7 +589,7 @@ impl Sources {
 }
 
 /// A source to fetch from: either a node, or an agent on a node
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum FetchSource {
     /// An agent on a node
     Agent(KAgent),
@@ -420,10 +646,21 @@ mod tests {
             space: Arc::new(KitsuneSpace::new(vec![0; 36])),
             context,
             size: None,
+            priority: FetchPriority::Normal,
             last_fetch: None,
         }
     }
 
+    /// Construct a State from a queue, with consistent counts
+    pub(super) fn state(queue: LinkedHashMap<FetchKey, FetchPoolItem>) -> State {
+        let mut state = State::default();
+        for item in queue.values() {
+            *state.space_counts.entry(item.space.clone()).or_default() += 1;
+        }
+        state.queue = queue;
+        state
+    }
+
     pub(super) fn space(i: u8) -> KSpace {
         Arc::new(KitsuneSpace::new(vec![i; 36]))
     }
@@ -443,6 +680,7 @@ mod tests {
     #[tokio::test(start_paused = true)]
     async fn source_rotation() {
         let sec1 = Duration::from_secs(10);
+        let no_backoff = HashMap::new();
         let mut ss = Sources(vec![
             SourceRecord {
                 source: source(1),
@@ -458,25 +696,25 @@ mod tests {
 
         tokio::time::advance(Duration::from_secs(1)).await;
 
-        assert_eq!(ss.next(sec1), Some(source(2)));
-        assert_eq!(ss.next(sec1), None);
+        assert_eq!(ss.next(sec1, &no_backoff), Some(source(2)));
+        assert_eq!(ss.next(sec1, &no_backoff), None);
 
         tokio::time::advance(Duration::from_secs(9)).await;
 
-        assert_eq!(ss.next(sec1), Some(source(1)));
+        assert_eq!(ss.next(sec1, &no_backoff), Some(source(1)));
 
         tokio::time::advance(Duration::from_secs(1)).await;
 
-        assert_eq!(ss.next(sec1), Some(source(2)));
+        assert_eq!(ss.next(sec1, &no_backoff), Some(source(2)));
         // source 1 has already had its delay backed off to 20s
         // due to a retry, so it returns None
-        assert_eq!(ss.next(sec1), None);
+        assert_eq!(ss.next(sec1, &no_backoff), None);
 
         tokio::time::advance(Duration::from_secs(20)).await;
 
-        assert_eq!(ss.next(sec1), Some(source(1)));
-        assert_eq!(ss.next(sec1), Some(source(2)));
-        assert_eq!(ss.next(sec1), None);
+        assert_eq!(ss.next(sec1, &no_backoff), Some(source(1)));
+        assert_eq!(ss.next(sec1, &no_backoff), Some(source(2)));
+        assert_eq!(ss.next(sec1, &no_backoff), None);
     }
 
     #[test]
@@ -513,8 +751,7 @@ mod tests {
             // so it won't show up in next() right away
             queue[1].1.sources.0[1].last_request = Some(Instant::now() - Duration::from_secs(3));
 
-            let queue = queue.into_iter().collect();
-            State { queue }
+            state(queue.into_iter().collect())
         };
 
         // We can try fetching items one source at a time by waiting 1 sec in between
@@ -546,4 +783,137 @@ mod tests {
 
         assert_eq!(q.iter_mut(&cfg).count(), 3);
     }
+
+    struct PriorityConfig;
+
+    impl FetchPoolConfig for PriorityConfig {
+        fn merge_fetch_contexts(&self, a: u32, b: u32) -> u32 {
+            a | b
+        }
+
+        fn item_retry_delay(&self) -> Duration {
+            Duration::from_secs(1)
+        }
+
+        fn source_retry_delay(&self) -> Duration {
+            Duration::from_secs(1)
+        }
+
+        fn source_max_backoff(&self) -> Duration {
+            Duration::from_secs(4)
+        }
+
+        fn space_quota(&self) -> Option<usize> {
+            Some(2)
+        }
+
+        fn fetch_priority(&self, context: Option<FetchContext>) -> FetchPriority {
+            match context.map(|c| *c) {
+                Some(c) if c > 0 => FetchPriority::High,
+                _ => FetchPriority::Normal,
+            }
+        }
+    }
+
+    #[test]
+    fn queue_priority() {
+        let cfg = PriorityConfig;
+        let mut q = State::default();
+
+        q.push(&cfg, req(1, None, source(1)));
+        q.push(&cfg, req(2, ctx(1), source(1)));
+
+        // The high priority item is offered first, even though it was added last
+        assert_eq!(
+            q.iter_mut(&cfg).map(|(k, ..)| k).collect::<Vec<_>>(),
+            vec![key_op(2), key_op(1)]
+        );
+
+        // Merging in a context can raise the priority of an existing item
+        q.remove(&key_op(1));
+        q.push(&cfg, req(3, ctx(0), source(1)));
+        assert_eq!(q.queue[&key_op(3)].priority, FetchPriority::Normal);
+        q.push(&cfg, req(3, ctx(1), source(2)));
+        assert_eq!(q.queue[&key_op(3)].priority, FetchPriority::High);
+    }
+
+    #[test]
+    fn queue_priority_drains_every_item_once() {
+        let cfg = Config(1, 10);
+        // More items than are checked on one poll, with the high priority
+        // ones at the back of the queue.
+        let mut q = state(
+            (0..=250)
+                .map(|i| {
+                    let mut item = item(&cfg, sources([i]), None);
+                    if i >= 200 {
+                        item.priority = FetchPriority::High;
+                    }
+                    (key_op(i), item)
+                })
+                .collect(),
+        );
+
+        let offered: Vec<_> = q.iter_mut(&cfg).map(|(k, ..)| k).collect();
+        let expected: Vec<_> = (200..=250).chain(0..200).map(key_op).collect();
+        assert_eq!(offered, expected);
+        assert_eq!(q.iter_mut(&cfg).count(), 0);
+    }
+
+    #[test]
+    fn queue_space_quota() {
+        let cfg = PriorityConfig;
+        let mut q = State::default();
+
+        q.push(&cfg, req(1, None, source(1)));
+        q.push(&cfg, req(2, None, source(1)));
+        q.push(&cfg, req(3, None, source(1)));
+        assert_eq!(q.queue.len(), 2);
+
+        q.remove(&key_op(1));
+        q.push(&cfg, req(3, None, source(1)));
+        assert_eq!(q.queue.len(), 2);
+        assert!(q.queue.contains_key(&key_op(3)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn source_backoff() {
+        let cfg = PriorityConfig;
+        let mut q = State::default();
+
+        q.push(&cfg, req(1, None, source(1)));
+        q.source_failed(&cfg, source(1));
+        assert_eq!(q.iter_mut(&cfg).count(), 0);
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(q.iter_mut(&cfg).count(), 1);
+
+        // Consecutive failures double the backoff, up to the max
+        q.source_failed(&cfg, source(1));
+        q.source_failed(&cfg, source(1));
+        q.source_failed(&cfg, source(1));
+        tokio::time::advance(Duration::from_secs(3)).await;
+        assert_eq!(q.iter_mut(&cfg).count(), 0);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(q.iter_mut(&cfg).count(), 1);
+    }
+
+    #[test]
+    fn records_roundtrip() {
+        let cfg = Config(1, 1);
+        let mut q = State::default();
+        q.push(&cfg, req(1, ctx(1), source(1)));
+        q.push(&cfg, req(1, ctx(1), source(2)));
+        q.push(&cfg, req(2, None, source(3)));
+
+        let records = q.records(&space(0));
+        assert_eq!(records.len(), 2);
+        assert!(q.records(&space(1)).is_empty());
+
+        let mut restored = State::default();
+        for record in records {
+            restored.restore(&cfg, space(0), record);
+        }
+        assert_eq!(restored.queue, q.queue);
+    }
 }
@@ -41,7 +41,7 @@ mod tests {
 
     use kitsune_p2p_types::tx2::tx2_utils::ShareOpen;
 
-    use crate::{pool::tests::*, State};
+    use crate::pool::tests::*;
 
     use super::*;
 
@@ -58,10 +58,9 @@ mod tests {
             queue[0].1.size = Some(100.into());
             queue[1].1.size = Some(1000.into());
 
-            let queue = queue.into_iter().collect();
             FetchPoolReader(FetchPool {
                 config: Arc::new(cfg),
-                state: ShareOpen::new(State { queue }),
+                state: ShareOpen::new(state(queue.into_iter().collect())),
             })
         };
         let info = q.info([space(0)].into_iter().collect());
@@ -54,7 +54,9 @@ pub struct KitsuneP2pConfig {
     /// a warning will be printed in the tracing log.
     #[serde(default)]
     pub tuning_params: KitsuneP2pTuningParams,
-    /// The network used for connecting to other peers
+    /// The network used for connecting to other peers.
+    /// With [`NetworkType::QuicMdns`] a `bootstrap_service` may still be
+    /// given, in which case both discovery methods are used.
     pub network_type: NetworkType,
 }
 
@@ -78,6 +80,19 @@ fn cnv_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
 }
 
 impl KitsuneP2pConfig {
+    /// Should agent infos be published to and pulled from the bootstrap service?
+    pub fn uses_bootstrap(&self) -> bool {
+        match self.network_type {
+            NetworkType::QuicBootstrap => true,
+            NetworkType::QuicMdns => self.bootstrap_service.is_some(),
+        }
+    }
+
+    /// Should agent infos be broadcast and discovered over mdns on the LAN?
+    pub fn uses_mdns(&self) -> bool {
+        matches!(self.network_type, NetworkType::QuicMdns)
+    }
+
     #[allow(dead_code)] // because of feature flipping
     pub(crate) fn is_tx2(&self) -> bool {
         #[cfg(feature = "tx2")]
@@ -319,7 +334,14 @@ pub enum NetworkType {
     /// Via bootstrap server to the WAN
     // MAYBE: Remove the "Quic" from this?
     QuicBootstrap,
-    /// Via MDNS to the LAN
+    /// Via MDNS to the LAN, and additionally via the bootstrap server
+    /// if a `bootstrap_service` is configured.
+    /// Discovered agent infos are only stored once their signature
+    /// has been verified by the host.
+    /// Only the agent info is broadcast, so this works with tx2 and tx5,
+    /// but peers still connect through the urls in their agent infos.
+    /// With tx5 these go through the signal server, which must therefore
+    /// be reachable on the LAN.
     // MAYBE: Remove the "Quic" from this?
     QuicMdns,
 }
@@ -259,6 +259,10 @@ impl ShardedGossip {
         let timeout = self.gossip.tuning_params.implicit_timeout();
 
         self.bandwidth.outgoing_bytes(bytes).await;
+        let _ = self.gossip.inner.share_mut(|i, _| {
+            i.metrics.write().record_gossip_bytes_out(&cert, bytes);
+            Ok(())
+        });
 
         let con = match how.clone() {
             HowToConnect::Con(con, remote_url) => {
@@ -303,6 +307,12 @@ impl ShardedGossip {
 
         if let Some((con, remote_url, msg, bytes)) = incoming {
             self.bandwidth.incoming_bytes(bytes).await;
+            let _ = self.gossip.inner.share_mut(|i, _| {
+                i.metrics
+                    .write()
+                    .record_gossip_bytes_in(&con.peer_id(), bytes);
+                Ok(())
+            });
             let variant_type = msg
                 .variant_type()
                 .to_string()
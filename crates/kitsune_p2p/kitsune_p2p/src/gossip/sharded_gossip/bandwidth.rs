@@ -33,6 +33,21 @@ impl BandwidthThrottles {
         }
     }
 
+    /// Replace the limits of all throttles with those from the configuration.
+    /// The gossip loops pick up the new limits with the next chunk of bytes.
+    pub fn set_limits(&self, tuning_params: &KitsuneP2pTuningParams) {
+        self.recent.set_limits(
+            tuning_params.gossip_inbound_target_mbps,
+            tuning_params.gossip_outbound_target_mbps,
+            tuning_params.gossip_burst_ratio,
+        );
+        self.historic.set_limits(
+            tuning_params.gossip_historic_inbound_target_mbps,
+            tuning_params.gossip_historic_outbound_target_mbps,
+            tuning_params.gossip_burst_ratio,
+        );
+    }
+
     /// Get the throttle for the recent loop.
     pub fn recent(&self) -> Arc<BandwidthThrottle> {
         self.recent.clone()
@@ -52,8 +67,8 @@ where
     C: Clock,
 {
     clock: C,
-    inbound: Option<RateLimiter<NotKeyed, InMemoryState, C>>,
-    outbound: Option<RateLimiter<NotKeyed, InMemoryState, C>>,
+    inbound: parking_lot::RwLock<Option<Arc<RateLimiter<NotKeyed, InMemoryState, C>>>>,
+    outbound: parking_lot::RwLock<Option<Arc<RateLimiter<NotKeyed, InMemoryState, C>>>>,
     start_time: Instant,
     bits_inbound: AtomicUsize,
     peak_inbound: AtomicUsize,
@@ -92,25 +107,12 @@ where
     C: Clock,
 {
     fn new_inner(inbound_mbps: f64, outbound_mbps: f64, burst_ratio: f64, clock: C) -> Self {
-        // Convert to bits per second.
-        let inbound_bps = inbound_mbps * 1000.0 * 1000.0;
-        let outbound_bps = outbound_mbps * 1000.0 * 1000.0;
-
-        let inbound = NonZeroU32::new(inbound_bps as u32).map(|bps| {
-            let burst = NonZeroU32::new((inbound_bps * burst_ratio) as u32)
-                .expect("burst_ratio cannot be 0");
-            RateLimiter::direct_with_clock(Quota::per_second(bps).allow_burst(burst), &clock)
-        });
-
-        let outbound = NonZeroU32::new(outbound_bps as u32).map(|bps| {
-            let burst = NonZeroU32::new((outbound_bps * burst_ratio) as u32)
-                .expect("burst_ratio cannot be 0");
-            RateLimiter::direct_with_clock(Quota::per_second(bps).allow_burst(burst), &clock)
-        });
+        let inbound = Self::limiter(inbound_mbps, burst_ratio, &clock);
+        let outbound = Self::limiter(outbound_mbps, burst_ratio, &clock);
         Self {
             clock,
-            inbound,
-            outbound,
+            inbound: parking_lot::RwLock::new(inbound),
+            outbound: parking_lot::RwLock::new(outbound),
             start_time: Instant::now(),
             bits_inbound: AtomicUsize::new(0),
             peak_inbound: AtomicUsize::new(0),
@@ -121,6 +123,31 @@ where
         }
     }
 
+    fn limiter(
+        mbps: f64,
+        burst_ratio: f64,
+        clock: &C,
+    ) -> Option<Arc<RateLimiter<NotKeyed, InMemoryState, C>>> {
+        // Convert to bits per second.
+        let bps = mbps * 1000.0 * 1000.0;
+
+        NonZeroU32::new(bps as u32).map(|n| {
+            let burst =
+                NonZeroU32::new((bps * burst_ratio) as u32).expect("burst_ratio cannot be 0");
+            Arc::new(RateLimiter::direct_with_clock(
+                Quota::per_second(n).allow_burst(burst),
+                clock,
+            ))
+        })
+    }
+
+    /// Replace the inbound and outbound bandwidth limits in megabits per second.
+    /// Bytes already waiting for bandwidth keep waiting on the old limits.
+    pub fn set_limits(&self, inbound_mbps: f64, outbound_mbps: f64, burst_ratio: f64) {
+        *self.inbound.write() = Self::limiter(inbound_mbps, burst_ratio, &self.clock);
+        *self.outbound.write() = Self::limiter(outbound_mbps, burst_ratio, &self.clock);
+    }
+
     async fn try_throttle(
         &self,
         verb: &str,
@@ -169,8 +196,9 @@ where
     /// Wait until there's enough bandwidth to send this many bytes.
     pub async fn outgoing_bytes(&self, bytes: usize) {
         if let Some(bits) = NonZeroU32::new(bytes as u32 * 8) {
-            if let Some(outbound) = &self.outbound {
-                self.try_throttle("send", outbound, bytes, bits).await;
+            let outbound = self.outbound.read().clone();
+            if let Some(outbound) = outbound {
+                self.try_throttle("send", &outbound, bytes, bits).await;
             }
             let el = self.start_time.elapsed();
             let last_s = self
@@ -206,8 +234,9 @@ where
     /// Wait until there's enough bandwidth to receive this many bytes.
     pub async fn incoming_bytes(&self, bytes: usize) {
         if let Some(bits) = NonZeroU32::new(bytes as u32 * 8) {
-            if let Some(inbound) = &self.inbound {
-                self.try_throttle("receive", inbound, bytes, bits).await;
+            let inbound = self.inbound.read().clone();
+            if let Some(inbound) = inbound {
+                self.try_throttle("receive", &inbound, bytes, bits).await;
             }
             let el = self.start_time.elapsed();
             let last_s = self
@@ -291,4 +320,21 @@ mod tests {
         // Allow for small rounding error.
         assert!(mbps < 0.11);
     }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_set_limits() {
+        let clock = governor::clock::FakeRelativeClock::default();
+        let bandwidth = BandwidthThrottle::test(0.1, 0.1, 1.0, clock);
+        // The burst of 0.1 mbps lets 100_000 bits through at once.
+        bandwidth.outgoing_bytes(12_500).await;
+        let r =
+            tokio::time::timeout(Duration::from_secs(10), bandwidth.outgoing_bytes(12_500)).await;
+        assert!(r.is_err());
+
+        // A limit of 0 means no limit.
+        bandwidth.set_limits(0.0, 0.0, 1.0);
+        let r =
+            tokio::time::timeout(Duration::from_secs(10), bandwidth.outgoing_bytes(12_500)).await;
+        assert!(r.is_ok());
+    }
 }
@@ -138,6 +138,13 @@ impl KitsuneHost for StandardResponsesHostApi {
     ) -> crate::KitsuneHostResult<Vec<OpHashSized>> {
         todo!()
     }
+
+    fn verify_agent_info_signed(
+        &self,
+        _agent_info: crate::types::agent_store::AgentInfoSigned,
+    ) -> crate::KitsuneHostResult<bool> {
+        todo!()
+    }
 }
 
 // TODO: integrate with `HandlerBuilder`
@@ -1,9 +1,10 @@
-use kitsune_p2p_fetch::OpHashSized;
+use kitsune_p2p_fetch::{FetchPoolConfig, FetchPoolRecord, OpHashSized};
 use kitsune_p2p_timestamp::Timestamp;
 use must_future::MustBoxFuture;
 use std::sync::Arc;
 
 use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned,
     bin_types::KitsuneSpace,
     dependencies::lair_keystore_api,
     dht::{
@@ -23,7 +24,7 @@ pub type KitsuneHostResult<'a, T> =
 
 /// The interface to be implemented by the host, which handles various requests
 /// for data
-pub trait KitsuneHost: 'static + Send + Sync {
+pub trait KitsuneHost: 'static + Send + Sync + FetchPoolConfig {
     /// We are requesting a block.
     fn block(&self, input: kitsune_p2p_block::Block) -> KitsuneHostResult<()>;
 
@@ -104,6 +105,27 @@ pub trait KitsuneHost: 'static + Send + Sync {
         .into()
     }
 
+    /// Persist the fetch pool items for a space, replacing any previously persisted items.
+    fn persist_fetch_pool(
+        &self,
+        space: Arc<KitsuneSpace>,
+        records: Vec<FetchPoolRecord>,
+    ) -> KitsuneHostResult<()> {
+        let _ = (space, records);
+        futures::FutureExt::boxed(async move { Ok(()) }).into()
+    }
+
+    /// Load the fetch pool items previously persisted for a space.
+    fn load_fetch_pool(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Vec<FetchPoolRecord>> {
+        let _space = space;
+        futures::FutureExt::boxed(async move { Ok(vec![]) }).into()
+    }
+
+    /// Verify that an agent info was signed by the agent it describes.
+    /// Agent infos discovered over mdns are only stored if this returns true.
+    /// Kitsune doesn't know how agents sign, so the host has to check it.
+    fn verify_agent_info_signed(&self, agent_info: AgentInfoSigned) -> KitsuneHostResult<bool>;
+
     /// Get the lair "tag" identifying the id seed to use for crypto signing.
     /// (this is currently only used in tx5/WebRTC if that feature is enabled.)
     fn lair_tag(&self) -> Option<Arc<str>> {
@@ -144,6 +144,15 @@ pub trait KitsuneHostDefaultError: KitsuneHost + FetchPoolConfig {
         .into()))
     }
 
+    fn verify_agent_info_signed(&self, _agent_info: AgentInfoSigned) -> KitsuneHostResult<bool> {
+        box_fut(Err(format!(
+            "error for unimplemented KitsuneHost test behavior: method {} of {}",
+            "verify_agent_info_signed",
+            Self::NAME
+        )
+        .into()))
+    }
+
     fn merge_fetch_contexts(&self, _a: u32, _b: u32) -> u32 {
         0
     }
@@ -225,4 +234,8 @@ impl<T: KitsuneHostDefaultError> KitsuneHost for T {
     ) -> KitsuneHostResult<Vec<OpHashSized>> {
         KitsuneHostDefaultError::query_op_hashes_by_region(self, space, region)
     }
+
+    fn verify_agent_info_signed(&self, agent_info: AgentInfoSigned) -> KitsuneHostResult<bool> {
+        KitsuneHostDefaultError::verify_agent_info_signed(self, agent_info)
+    }
 }
@@ -128,6 +128,10 @@ impl KitsuneHost for HostStub {
         KitsuneHostDefaultError::query_op_hashes_by_region(&self.err, space, region)
     }
 
+    fn verify_agent_info_signed(&self, agent_info: AgentInfoSigned) -> KitsuneHostResult<bool> {
+        KitsuneHostDefaultError::verify_agent_info_signed(&self.err, agent_info)
+    }
+
     fn check_op_data(
         &self,
         space: Arc<KitsuneSpace>,
@@ -140,6 +140,12 @@ pub struct PeerNodeHistory {
 
     /// Detailed info about rounds completed with this node
     pub completed_rounds: VecDeque<CompletedRound>,
+
+    /// The bytes of gossip received from this node
+    pub bytes_in: u64,
+
+    /// The bytes of gossip sent to this node
+    pub bytes_out: u64,
 }
 
 /// Info about a completed gossip round
@@ -577,6 +583,18 @@ impl Metrics {
         }
     }
 
+    /// Record the bytes of a gossip message received from a node.
+    pub fn record_gossip_bytes_in(&mut self, node: &NodeId, bytes: usize) {
+        let history = self.node_history.entry(node.clone()).or_default();
+        history.bytes_in = history.bytes_in.saturating_add(bytes as u64);
+    }
+
+    /// Record the bytes of a gossip message sent to a node.
+    pub fn record_gossip_bytes_out(&mut self, node: &NodeId, bytes: usize) {
+        let history = self.node_history.entry(node.clone()).or_default();
+        history.bytes_out = history.bytes_out.saturating_add(bytes as u64);
+    }
+
     /// Record that we should force initiate the next few rounds.
     pub fn record_force_initiate(&mut self) {
         self.force_initiates = MAX_TRIGGERS;
@@ -227,12 +227,40 @@ impl KitsuneP2pActor {
             }
         }
 
+        /// Delegates the fetch pool configuration to the host.
+        struct HostFetchPoolConfig(HostApi);
+
+        impl FetchPoolConfig for HostFetchPoolConfig {
+            fn item_retry_delay(&self) -> std::time::Duration {
+                self.0.item_retry_delay()
+            }
+
+            fn source_retry_delay(&self) -> std::time::Duration {
+                self.0.source_retry_delay()
+            }
+
+            fn source_max_backoff(&self) -> std::time::Duration {
+                self.0.source_max_backoff()
+            }
+
+            fn space_quota(&self) -> Option<usize> {
+                self.0.space_quota()
+            }
+
+            fn fetch_priority(&self, context: Option<FetchContext>) -> FetchPriority {
+                self.0.fetch_priority(context)
+            }
+
+            fn merge_fetch_contexts(&self, a: u32, b: u32) -> u32 {
+                self.0.merge_fetch_contexts(a, b)
+            }
+        }
+
         let fetch_response_queue = kitsune_p2p_fetch::FetchResponseQueue::new(FetchResponseConfig(
             config.tuning_params.clone(),
         ));
 
-        // TODO - use a real config
-        let fetch_pool = FetchPool::new_bitwise_or();
+        let fetch_pool = FetchPool::new(Arc::new(HostFetchPoolConfig(host.clone())));
 
         // Start a loop to handle our fetch queue fetch items.
         {
@@ -256,8 +284,12 @@ impl KitsuneP2pActor {
                             }
                         }
 
-                        if let Err(err) = i_s.fetch(key, space, source).await {
-                            tracing::debug!(?err);
+                        match i_s.fetch(key, space, source.clone()).await {
+                            Ok(_) => fetch_pool.source_succeeded(&source),
+                            Err(err) => {
+                                tracing::debug!(?err);
+                                fetch_pool.source_failed(&source);
+                            }
                         }
                     }
 
@@ -1288,6 +1320,15 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         Ok(async move { Ok(fut.await?) }.boxed().into())
     }
 
+    fn handle_set_gossip_bandwidth(
+        &mut self,
+        tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    ) -> KitsuneP2pHandlerResult<()> {
+        // The throttles are shared by the gossip loops of all spaces.
+        self.bandwidth_throttles.set_limits(&tuning_params);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
+
     fn handle_get_diagnostics(
         &mut self,
         space: KSpace,
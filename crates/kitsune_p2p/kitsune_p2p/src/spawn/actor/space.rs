@@ -22,6 +22,9 @@ use metric_exchange::*;
 
 mod rpc_multi_logic;
 
+#[cfg(test)]
+mod tests;
+
 type KSpace = Arc<KitsuneSpace>;
 type KAgent = Arc<KitsuneAgent>;
 type KBasis = Arc<KitsuneBasis>;
@@ -30,6 +33,7 @@ type WireConHnd = MetaNetCon;
 type Payload = Box<[u8]>;
 type OpHashList = Vec<OpHashSized>;
 type MaybeDelegate = Option<(KBasis, u32, u32)>;
+type MdnsHandles = Arc<parking_lot::Mutex<HashMap<Vec<u8>, Arc<AtomicBool>>>>;
 
 ghost_actor::ghost_chan! {
     #[allow(clippy::too_many_arguments)]
@@ -178,8 +182,9 @@ impl SpaceInternalHandler for Space {
 
     fn handle_update_agent_info(&mut self) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
-        let network_type = self.config.network_type.clone();
+        let mdns_handles = self.mdns_handles.clone();
+        let use_mdns = self.config.uses_mdns();
+        let use_bootstrap = self.config.uses_bootstrap();
         let mut agent_list = Vec::with_capacity(self.local_joined_agents.len());
         for agent in self.local_joined_agents.keys().cloned() {
             let arc = self.get_agent_arc(&agent);
@@ -205,8 +210,9 @@ impl SpaceInternalHandler for Space {
                     urls: &urls,
                     evt_sender: &evt_sender,
                     internal_sender: &internal_sender,
-                    network_type: network_type.clone(),
-                    mdns_handles: &mut mdns_handles,
+                    use_mdns,
+                    use_bootstrap,
+                    mdns_handles: &mdns_handles,
                     bootstrap_service: &bootstrap_service,
                     dynamic_arcs,
                 };
@@ -230,8 +236,9 @@ impl SpaceInternalHandler for Space {
     ) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
         let bootstrap_net = self.ro_inner.bootstrap_net;
-        let mut mdns_handles = self.mdns_handles.clone();
-        let network_type = self.config.network_type.clone();
+        let mdns_handles = self.mdns_handles.clone();
+        let use_mdns = self.config.uses_mdns();
+        let use_bootstrap = self.config.uses_bootstrap();
         let ep_hnd = self.ro_inner.ep_hnd.clone();
         let evt_sender = self.evt_sender.clone();
         let internal_sender = self.i_s.clone();
@@ -251,8 +258,9 @@ impl SpaceInternalHandler for Space {
                 urls: &urls,
                 evt_sender: &evt_sender,
                 internal_sender: &internal_sender,
-                network_type: network_type.clone(),
-                mdns_handles: &mut mdns_handles,
+                use_mdns,
+                use_bootstrap,
+                mdns_handles: &mdns_handles,
                 bootstrap_service: &bootstrap_service,
                 dynamic_arcs,
             };
@@ -623,8 +631,9 @@ struct UpdateAgentInfoInput<'borrow> {
     urls: &'borrow Vec<TxUrl>,
     evt_sender: &'borrow futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     internal_sender: &'borrow ghost_actor::GhostSender<SpaceInternal>,
-    network_type: NetworkType,
-    mdns_handles: &'borrow mut HashMap<Vec<u8>, Arc<AtomicBool>>,
+    use_mdns: bool,
+    use_bootstrap: bool,
+    mdns_handles: &'borrow MdnsHandles,
     bootstrap_service: &'borrow Option<Url2>,
     dynamic_arcs: bool,
 }
@@ -661,7 +670,8 @@ async fn update_single_agent_info(
         urls,
         evt_sender,
         internal_sender,
-        network_type,
+        use_mdns,
+        use_bootstrap,
         mdns_handles,
         bootstrap_service,
         dynamic_arcs,
@@ -705,41 +715,127 @@ async fn update_single_agent_info(
     tracing::debug!(?agent_info_signed);
 
     // Push to the network as well
-    match network_type {
-        NetworkType::QuicMdns => {
-            // Broadcast only valid AgentInfo
-            if !urls.is_empty() {
-                // Kill previous broadcast for this space + agent
-                let key = [space.get_bytes(), agent.get_bytes()].concat();
-                if let Some(current_handle) = mdns_handles.get(&key) {
-                    mdns_kill_thread(current_handle.to_owned());
-                }
-                // Broadcast by using Space as service type and Agent as service name
-                let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
-                let agent_b64 = base64::encode_config(&agent[..], base64::URL_SAFE_NO_PAD);
-                //println!("(MDNS) - Broadcasting of Agent {:?} ({}) in space {:?} ({} ; {})",
-                // agent, agent.get_bytes().len(), space, space.get_bytes().len(), space_b64.len());
-                // Broadcast rmp encoded agent_info_signed
-                let mut buffer = Vec::new();
-                rmp_encode(&mut buffer, &agent_info_signed)?;
-                tracing::trace!(?space_b64, ?agent_b64);
-                let handle = mdns_create_broadcast_thread(space_b64, agent_b64, &buffer);
-                // store handle in self
-                mdns_handles.insert(key, handle);
+    if use_mdns {
+        // Broadcast only valid AgentInfo
+        if !urls.is_empty() {
+            // Kill previous broadcast for this space + agent
+            let key = [space.get_bytes(), agent.get_bytes()].concat();
+            let mut mdns_handles = mdns_handles.lock();
+            if let Some(current_handle) = mdns_handles.remove(&key) {
+                mdns_kill_thread(current_handle);
             }
-        }
-        NetworkType::QuicBootstrap => {
-            crate::spawn::actor::bootstrap::put(
-                bootstrap_service.clone(),
-                agent_info_signed.clone(),
-                bootstrap_net,
-            )
-            .await?;
+            // Broadcast by using Space as service type and Agent as service name
+            let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
+            let agent_b64 = base64::encode_config(&agent[..], base64::URL_SAFE_NO_PAD);
+            // Broadcast rmp encoded agent_info_signed
+            let mut buffer = Vec::new();
+            rmp_encode(&mut buffer, &agent_info_signed)?;
+            tracing::trace!(?space_b64, ?agent_b64);
+            let handle = mdns_create_broadcast_thread(space_b64, agent_b64, &buffer);
+            // store handle so the broadcast can be replaced or stopped later
+            mdns_handles.insert(key, handle);
         }
     }
+    if use_bootstrap {
+        crate::spawn::actor::bootstrap::put(
+            bootstrap_service.clone(),
+            agent_info_signed.clone(),
+            bootstrap_net,
+        )
+        .await?;
+    }
     Ok(agent_info_signed)
 }
 
+/// Read the agent infos from a stream of mdns responses for this space, such
+/// as the one returned by [`mdns_listen`], and add those which pass
+/// [`is_valid_mdns_peer`] to the peer store, just as the bootstrap pull does
+/// for agent infos from the bootstrap service.
+fn spawn_mdns_discovery(
+    space: Arc<KitsuneSpace>,
+    stream: impl futures::stream::Stream<Item = Result<MdnsResponse, MdnsError>> + Send + 'static,
+    evt_sender: futures::channel::mpsc::Sender<KitsuneP2pEvent>,
+    host: HostApi,
+    internal_sender: ghost_actor::GhostSender<SpaceInternal>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::task::spawn(async move {
+        tokio::pin!(stream);
+        while let Some(maybe_response) = stream.next().await {
+            let response = match maybe_response {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::error!(msg = "Failed to get peers from MDNS", ?e);
+                    continue;
+                }
+            };
+            tracing::trace!(msg = "Peer found via MDNS", ?response);
+            let remote_agent_info_signed: AgentInfoSigned = match rmp_decode(&mut &*response.buffer)
+            {
+                Ok(info) => info,
+                Err(e) => {
+                    tracing::error!(msg = "Failed to decode MDNS peer", ?e);
+                    continue;
+                }
+            };
+            match is_valid_mdns_peer(&space, &remote_agent_info_signed, &host, &internal_sender)
+                .await
+            {
+                Ok(true) => (),
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::error!(msg = "Failed to check MDNS peer", ?e);
+                    continue;
+                }
+            }
+            // Add to local storage
+            if let Err(e) = evt_sender
+                .put_agent_info_signed(PutAgentInfoSignedEvt {
+                    space: space.clone(),
+                    peer_data: vec![remote_agent_info_signed],
+                })
+                .await
+            {
+                tracing::error!(msg = "Failed to store MDNS peer", ?e);
+            }
+        }
+    })
+}
+
+/// An agent info discovered over mdns is only stored if it is for this space,
+/// has not expired, is not one of our own agents, and is correctly signed by
+/// the agent it describes.
+async fn is_valid_mdns_peer(
+    space: &Arc<KitsuneSpace>,
+    agent_info_signed: &AgentInfoSigned,
+    host: &HostApi,
+    internal_sender: &ghost_actor::GhostSender<SpaceInternal>,
+) -> KitsuneP2pResult<bool> {
+    if agent_info_signed.space != *space {
+        return Ok(false);
+    }
+    let now_ms = kitsune_p2p_timestamp::Timestamp::now().as_millis() as u64;
+    if agent_info_signed.expires_at_ms <= now_ms {
+        return Ok(false);
+    }
+    if internal_sender
+        .is_agent_local(agent_info_signed.agent.clone())
+        .await?
+    {
+        return Ok(false);
+    }
+    let valid = host
+        .verify_agent_info_signed(agent_info_signed.clone())
+        .await
+        .map_err(KitsuneP2pError::other)?;
+    if !valid {
+        tracing::warn!(
+            msg = "Ignoring MDNS peer with invalid signature",
+            agent = ?agent_info_signed.agent
+        );
+    }
+    Ok(valid)
+}
+
 use ghost_actor::dependencies::must_future::MustBoxFuture;
 impl ghost_actor::GhostControlHandler for Space {
     fn handle_ghost_actor_shutdown(mut self) -> MustBoxFuture<'static, ()> {
@@ -788,52 +884,22 @@ impl KitsuneP2pHandler for Space {
         }
         let fut = self.i_s.update_single_agent_info(agent);
         let evt_sender = self.evt_sender.clone();
-        match self.config.network_type {
-            NetworkType::QuicMdns => {
-                // Listen to MDNS service that has that space as service type
-                let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
-                if !self.mdns_listened_spaces.contains(&space_b64) {
-                    self.mdns_listened_spaces.insert(space_b64.clone());
-                    tokio::task::spawn(async move {
-                        let stream = mdns_listen(space_b64);
-                        tokio::pin!(stream);
-                        while let Some(maybe_response) = stream.next().await {
-                            match maybe_response {
-                                Ok(response) => {
-                                    tracing::trace!(msg = "Peer found via MDNS", ?response);
-                                    // Decode response
-                                    let maybe_agent_info_signed =
-                                        rmp_decode(&mut &*response.buffer);
-                                    if let Err(e) = maybe_agent_info_signed {
-                                        tracing::error!(msg = "Failed to decode MDNS peer", ?e);
-                                        continue;
-                                    }
-                                    if let Ok(remote_agent_info_signed) = maybe_agent_info_signed {
-                                        // Add to local storage
-                                        if let Err(e) = evt_sender
-                                            .put_agent_info_signed(PutAgentInfoSignedEvt {
-                                                space: space.clone(),
-                                                peer_data: vec![remote_agent_info_signed],
-                                            })
-                                            .await
-                                        {
-                                            tracing::error!(msg = "Failed to store MDNS peer", ?e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    tracing::error!(msg = "Failed to get peers from MDNS", ?e);
-                                }
-                            }
-                        }
-                    });
-                }
-            }
-            NetworkType::QuicBootstrap => {
-                // quic bootstrap is managed for the whole space
-                // see the Space::new() constructor
+        if self.config.uses_mdns() {
+            // Listen to MDNS service that has that space as service type
+            let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
+            if !self.mdns_listened_spaces.contains(&space_b64) {
+                self.mdns_listened_spaces.insert(space_b64.clone());
+                spawn_mdns_discovery(
+                    space,
+                    mdns_listen(space_b64),
+                    evt_sender,
+                    self.host_api.clone(),
+                    self.i_s.clone(),
+                );
             }
         }
+        // quic bootstrap is managed for the whole space
+        // see the Space::new() constructor
 
         Ok(async move { fut.await }.boxed().into())
     }
@@ -1239,6 +1305,14 @@ impl KitsuneP2pHandler for Space {
         unreachable!()
     }
 
+    fn handle_set_gossip_bandwidth(
+        &mut self,
+        _tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    ) -> KitsuneP2pHandlerResult<()> {
+        // call handled by parent actor and never delegated to spaces
+        unreachable!()
+    }
+
     fn handle_get_diagnostics(
         &mut self,
         _space: KSpace,
@@ -1336,7 +1410,7 @@ pub(crate) struct Space {
     pub(crate) local_joined_agents: HashMap<Arc<KitsuneAgent>, Option<AgentInfoSigned>>,
     pub(crate) agent_arcs: HashMap<Arc<KitsuneAgent>, DhtArc>,
     pub(crate) config: Arc<KitsuneP2pConfig>,
-    mdns_handles: HashMap<Vec<u8>, Arc<AtomicBool>>,
+    mdns_handles: MdnsHandles,
     mdns_listened_spaces: HashSet<String>,
     gossip_mod: HashMap<GossipModuleType, GossipModule>,
 }
@@ -1376,6 +1450,39 @@ impl Space {
             });
         }
 
+        let fetch_pool_persist_interval_ms = config.tuning_params.fetch_pool_persist_interval_ms;
+        if fetch_pool_persist_interval_ms > 0 {
+            let space = space.clone();
+            let fetch_pool = fetch_pool.clone();
+            let host = host_api.clone();
+            let i_s_c = i_s.clone();
+            tokio::task::spawn(async move {
+                match host.load_fetch_pool(space.clone()).await {
+                    Ok(records) => fetch_pool.restore(space.clone(), records),
+                    Err(err) => tracing::warn!(?err, "failed to load persisted fetch pool"),
+                }
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_millis(
+                        fetch_pool_persist_interval_ms as u64,
+                    ))
+                    .await;
+
+                    // Stop once the space has shut down.
+                    use ghost_actor::GhostControlSender;
+                    if !i_s_c.ghost_actor_is_active() {
+                        break;
+                    }
+
+                    let records = fetch_pool.records(&space);
+
+                    if let Err(err) = host.persist_fetch_pool(space.clone(), records).await {
+                        tracing::warn!(?err, "failed to persist fetch pool");
+                    }
+                }
+                tracing::debug!("fetch pool persist loop ending");
+            });
+        }
+
         let metric_exchange = MetricExchangeSync::spawn(
             space.clone(),
             config.tuning_params.clone(),
@@ -1444,7 +1551,7 @@ impl Space {
             }
         });
 
-        if let NetworkType::QuicBootstrap = &config.network_type {
+        if config.uses_bootstrap() {
             // spawn the periodic bootstrap pull
             let i_s_c = i_s.clone();
             let evt_s_c = evt_sender.clone();
@@ -1537,7 +1644,7 @@ impl Space {
             local_joined_agents: HashMap::new(),
             agent_arcs: HashMap::new(),
             config,
-            mdns_handles: HashMap::new(),
+            mdns_handles: MdnsHandles::default(),
             mdns_listened_spaces: HashSet::new(),
             gossip_mod,
         }
@@ -1557,13 +1664,19 @@ impl Space {
         agent: Arc<KitsuneAgent>,
     ) -> KitsuneP2pHandlerResult<()> {
         let space = self.space.clone();
-        let network_type = self.config.network_type.clone();
+        let use_bootstrap = self.config.uses_bootstrap();
         let bootstrap_net = self.ro_inner.bootstrap_net;
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
         let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
         let host = self.host_api.clone();
 
+        // Stop broadcasting this agent over mdns
+        let key = [space.get_bytes(), agent.get_bytes()].concat();
+        if let Some(handle) = self.mdns_handles.lock().remove(&key) {
+            mdns_kill_thread(handle);
+        }
+
         Ok(async move {
             let signed_at_ms =
                 crate::spawn::actor::bootstrap::now_once(None, bootstrap_net).await?;
@@ -1602,17 +1715,16 @@ impl Space {
                 .await
                 .map_err(KitsuneP2pError::other)?;
 
-            // Push to the network as well
-            match network_type {
-                NetworkType::QuicMdns => tracing::warn!("NOT publishing leaves to mdns"),
-                NetworkType::QuicBootstrap => {
-                    crate::spawn::actor::bootstrap::put(
-                        bootstrap_service.clone(),
-                        agent_info_signed,
-                        bootstrap_net,
-                    )
-                    .await?;
-                }
+            // Push to the network as well.
+            // Leaves are not broadcast over mdns, the broadcast of
+            // this agent was already stopped above.
+            if use_bootstrap {
+                crate::spawn::actor::bootstrap::put(
+                    bootstrap_service.clone(),
+                    agent_info_signed,
+                    bootstrap_net,
+                )
+                .await?;
             }
 
             Ok(())
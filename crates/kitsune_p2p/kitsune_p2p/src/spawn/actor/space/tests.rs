@@ -0,0 +1,98 @@
+use super::*;
+
+/// A host which takes an agent info to be correctly signed
+/// if its signature is the first byte of its agent repeated.
+struct SignatureHost;
+
+impl FetchPoolConfig for SignatureHost {
+    fn merge_fetch_contexts(&self, _a: u32, _b: u32) -> u32 {
+        unimplemented!()
+    }
+}
+
+impl KitsuneHostDefaultError for SignatureHost {
+    const NAME: &'static str = "SignatureHost";
+
+    fn verify_agent_info_signed(&self, agent_info: AgentInfoSigned) -> KitsuneHostResult<bool> {
+        let valid = agent_info
+            .signature
+            .0
+            .iter()
+            .all(|b| *b == agent_info.agent.0[0]);
+        box_fut(Ok(valid))
+    }
+}
+
+/// An agent info for this space, signed with the given byte.
+fn agent_info(space: Arc<KitsuneSpace>, agent: u8, signature: u8) -> AgentInfoSigned {
+    let expires_at_ms = kitsune_p2p_timestamp::Timestamp::now().as_millis() as u64 + 60_000;
+    futures::executor::block_on(AgentInfoSigned::sign(
+        space,
+        Arc::new(KitsuneAgent(vec![agent; 36])),
+        u32::MAX,
+        vec![format!("fake://{}", agent).into()],
+        expires_at_ms - 60_000,
+        expires_at_ms,
+        |_| async move { Ok(Arc::new(vec![signature; 64].into())) },
+    ))
+    .unwrap()
+}
+
+/// The mdns response broadcasting an agent info.
+fn announcement(agent_info: &AgentInfoSigned) -> Result<MdnsResponse, MdnsError> {
+    let mut buffer = Vec::new();
+    rmp_encode(&mut buffer, agent_info).unwrap();
+    Ok(MdnsResponse {
+        service_type: String::new(),
+        service_name: String::new(),
+        addr: std::net::Ipv4Addr::LOCALHOST.into(),
+        buffer,
+    })
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mdns_discovery_stores_only_correctly_signed_agent_infos() {
+    let space = Arc::new(KitsuneSpace(vec![0; 36]));
+
+    let mut m = MockSpaceInternalHandler::new();
+    m.expect_handle_is_agent_local()
+        .returning(|_| ok_fut(Ok(false)));
+    let b = ghost_actor::actor_builder::GhostActorBuilder::new();
+    let i_s = b
+        .channel_factory()
+        .create_channel::<SpaceInternal>()
+        .await
+        .unwrap();
+    tokio::task::spawn(b.spawn(m));
+
+    let stored = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let mut m = MockKitsuneP2pEventHandler::new();
+    m.expect_handle_put_agent_info_signed().returning({
+        let stored = stored.clone();
+        move |evt| {
+            stored.lock().extend(evt.peer_data);
+            ok_fut(Ok(()))
+        }
+    });
+    let b = ghost_actor::actor_builder::GhostActorBuilder::new();
+    let (evt_sender, r) = futures::channel::mpsc::channel::<KitsuneP2pEvent>(4096);
+    b.channel_factory().attach_receiver(r).await.unwrap();
+    tokio::task::spawn(b.spawn(m));
+
+    let valid = agent_info(space.clone(), 1, 1);
+    // Agent 2's info signed by someone else.
+    let forged = agent_info(space.clone(), 2, 3);
+    let responses = vec![announcement(&valid), announcement(&forged)];
+
+    spawn_mdns_discovery(
+        space,
+        futures::stream::iter(responses),
+        evt_sender,
+        Arc::new(SignatureHost),
+        i_s,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(*stored.lock(), vec![valid]);
+}
@@ -213,6 +213,13 @@ impl KitsuneHost for SwitchboardEventHandler {
     ) -> crate::KitsuneHostResult<Vec<OpHashSized>> {
         todo!()
     }
+
+    fn verify_agent_info_signed(
+        &self,
+        _agent_info: crate::types::agent_store::AgentInfoSigned,
+    ) -> crate::KitsuneHostResult<bool> {
+        todo!()
+    }
 }
 
 #[allow(warnings)]
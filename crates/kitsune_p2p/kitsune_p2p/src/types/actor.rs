@@ -165,5 +165,8 @@ ghost_actor::ghost_chan! {
 
         /// Get data for diagnostics
         fn get_diagnostics(space: KSpace) -> KitsuneDiagnostics;
+
+        /// Replace the gossip bandwidth limits with those in these tuning params.
+        fn set_gossip_bandwidth(tuning_params: KitsuneP2pTuningParams) -> ();
     }
 }
@@ -145,6 +145,17 @@ pub mod tuning_params_struct {
         /// Should gossip dynamically resize storage arcs?
         gossip_dynamic_arcs: bool = true,
 
+        /// How often the fetch pool of each space is persisted through the host,
+        /// so that pending fetches survive a restart. Persisted items are
+        /// restored when the space is created.
+        /// A value of 0 disables persistence. [Default: 0]
+        fetch_pool_persist_interval_ms: u32 = 0,
+
+        /// The maximum number of items held in the fetch pool for a single space.
+        /// Items discovered beyond this are dropped, to be rediscovered through
+        /// gossip later. A value of 0 means no limit. [Default: 0]
+        fetch_pool_space_quota: u32 = 0,
+
         /// Default timeout for rpc single. [Default: 60s]
         default_rpc_single_timeout_ms: u32 = 1000 * 60,
 
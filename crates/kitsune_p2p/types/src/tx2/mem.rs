@@ -50,6 +50,46 @@ pub async fn tx2_mem_adapter(config: MemConfig) -> KitsuneResult<AdapterFactory>
     MemBackendAdapt::new(config).await
 }
 
+/// Faults to inject on frames sent over a mem link,
+/// i.e. from one mem endpoint to another.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemLinkFault {
+    /// Drop every frame sent over the link and refuse new connections.
+    pub partitioned: bool,
+
+    /// Delay every frame sent over the link by this long.
+    pub latency: Option<std::time::Duration>,
+
+    /// The fraction of frames to drop, from 0.0 to 1.0.
+    /// Drops are spread evenly over the frames rather than
+    /// chosen at random, so test runs are reproducible.
+    pub drop_rate: f64,
+}
+
+/// Get the faults currently injected on frames sent from the endpoint
+/// with the cert `from` to the endpoint with the cert `to`.
+pub fn mem_link_fault(from: &Tx2Cert, to: &Tx2Cert) -> MemLinkFault {
+    MEM_LINK_FAULTS
+        .lock()
+        .get(&(from.clone(), to.clone()))
+        .map(|(fault, _)| fault.clone())
+        .unwrap_or_default()
+}
+
+/// Update the faults injected on frames sent from the endpoint
+/// with the cert `from` to the endpoint with the cert `to`.
+/// Endpoints are identified by cert rather than url so faults
+/// survive reconnects.
+pub fn mem_update_link_fault(from: Tx2Cert, to: Tx2Cert, f: impl FnOnce(&mut MemLinkFault)) {
+    let mut faults = MEM_LINK_FAULTS.lock();
+    let key = (from, to);
+    let (fault, _) = faults.entry(key.clone()).or_default();
+    f(fault);
+    if *fault == MemLinkFault::default() {
+        faults.remove(&key);
+    }
+}
+
 // -- private -- //
 
 static NEXT_MEM_ID: atomic::AtomicU64 = atomic::AtomicU64::new(1);
@@ -63,6 +103,67 @@ type EndpointItem = (ConSend, Active, Tx2Cert);
 static MEM_ENDPOINTS: Lazy<Mutex<HashMap<u64, EndpointItem>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+/// The faults on each link along with how many frames are owed to the drop rate.
+type LinkFaultItem = (MemLinkFault, f64);
+static MEM_LINK_FAULTS: Lazy<Mutex<HashMap<(Tx2Cert, Tx2Cert), LinkFaultItem>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// What to do with a frame sent over a mem link.
+enum LinkAction {
+    Deliver(Option<std::time::Duration>),
+    Drop,
+}
+
+fn link_action(from: &Tx2Cert, to: &Tx2Cert) -> LinkAction {
+    let mut faults = MEM_LINK_FAULTS.lock();
+    let (fault, owed) = match faults.get_mut(&(from.clone(), to.clone())) {
+        None => return LinkAction::Deliver(None),
+        Some(item) => item,
+    };
+    if fault.partitioned {
+        return LinkAction::Drop;
+    }
+    *owed += fault.drop_rate.clamp(0.0, 1.0);
+    if *owed >= 1.0 {
+        *owed -= 1.0;
+        return LinkAction::Drop;
+    }
+    LinkAction::Deliver(fault.latency)
+}
+
+fn is_partitioned(a: &Tx2Cert, b: &Tx2Cert) -> bool {
+    let faults = MEM_LINK_FAULTS.lock();
+    let partitioned = |from: &Tx2Cert, to: &Tx2Cert| matches!(faults.get(&(from.clone(), to.clone())), Some((f, _)) if f.partitioned);
+    partitioned(a, b) || partitioned(b, a)
+}
+
+/// Applies the injected link faults to frames written to an out channel.
+struct MemFaultWriter {
+    sub: OutChan,
+    local_cert: Tx2Cert,
+    peer_cert: Tx2Cert,
+}
+
+impl AsFramedWriter for MemFaultWriter {
+    fn write(
+        &mut self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'_, KitsuneResult<()>> {
+        match link_action(&self.local_cert, &self.peer_cert) {
+            LinkAction::Drop => async move { Ok(()) }.boxed(),
+            LinkAction::Deliver(latency) => async move {
+                if let Some(latency) = latency {
+                    tokio::time::sleep(latency).await;
+                }
+                self.sub.write(msg_id, data, timeout).await
+            }
+            .boxed(),
+        }
+    }
+}
+
 struct MemInChanRecvAdapt(BoxStream<'static, InChanFut>);
 
 impl MemInChanRecvAdapt {
@@ -105,6 +206,7 @@ struct MemConAdaptInner {
     uniq: Uniq,
     dir: Tx2ConDir,
     peer_addr: TxUrl,
+    local_cert: Tx2Cert,
     peer_cert: Tx2Cert,
     chan_send: ChanSend,
     con_active: Active,
@@ -117,6 +219,7 @@ impl MemConAdapt {
     fn new(
         dir: Tx2ConDir,
         peer_addr: TxUrl,
+        local_cert: Tx2Cert,
         peer_cert: Tx2Cert,
         chan_send: ChanSend,
         con_active: Active,
@@ -126,6 +229,7 @@ impl MemConAdapt {
             uniq: Uniq::default(),
             dir,
             peer_addr,
+            local_cert,
             peer_cert,
             chan_send,
             con_active,
@@ -153,9 +257,15 @@ impl ConAdapt for MemConAdapt {
 
     fn out_chan(&self, _timeout: KitsuneTimeout) -> OutChanFut {
         let sender = self.0.chan_send.clone();
+        let local_cert = self.0.local_cert.clone();
+        let peer_cert = self.0.peer_cert.clone();
         let (send, recv) = bound_async_mem_channel(4096, Some(&self.0.mix_active));
         async move {
-            let send: OutChan = Box::new(FramedWriter::new(send));
+            let send: OutChan = Box::new(MemFaultWriter {
+                sub: Box::new(FramedWriter::new(send)),
+                local_cert,
+                peer_cert,
+            });
             let recv: InChan = Box::new(FramedReader::new(recv));
             if sender.send(recv).await.is_err() {
                 return Err("failed to create out channel".into());
@@ -320,6 +430,10 @@ impl EndpointAdapt for MemEndpointAdapt {
                 Some((s, a, d)) => (s.clone(), a.clone(), d.clone()),
             };
 
+            if is_partitioned(&local_cert, &remote_cert) {
+                return Err(format!("partitioned from: {}", url).into());
+            }
+
             let con_active = Active::new();
             let mix_ep_active = this_ep_active.mix(&oth_ep_active);
             let mix_active = con_active.mix(&mix_ep_active);
@@ -330,7 +444,8 @@ impl EndpointAdapt for MemEndpointAdapt {
             let oth_con = MemConAdapt::new(
                 Tx2ConDir::Incoming,
                 format!("{}/{}", this_url, con_id).into(),
-                local_cert,
+                remote_cert.clone(),
+                local_cert.clone(),
                 oth_send,
                 con_active.clone(),
                 mix_active.clone(),
@@ -340,6 +455,7 @@ impl EndpointAdapt for MemEndpointAdapt {
             let con = MemConAdapt::new(
                 Tx2ConDir::Outgoing,
                 format!("{}/{}", url, con_id).into(),
+                local_cert,
                 remote_cert,
                 send,
                 con_active,
@@ -613,4 +729,47 @@ mod tests {
 
         rt.await.unwrap().unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tx2_mem_link_faults() {
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let back1 = MemBackendAdapt::new(MemConfig::default()).await.unwrap();
+        let back2 = MemBackendAdapt::new(MemConfig::default()).await.unwrap();
+        let (cert1, cert2) = (back1.local_cert(), back2.local_cert());
+        let (ep1, _con_recv1) = back1.bind("none:".into(), t).await.unwrap();
+        let (ep2, mut con_recv2) = back2.bind("none:".into(), t).await.unwrap();
+        let addr2 = ep2.local_addr().unwrap();
+
+        mem_update_link_fault(cert2.clone(), cert1.clone(), |f| f.partitioned = true);
+        assert!(ep1.connect(addr2.clone(), t).await.is_err());
+        mem_update_link_fault(cert2.clone(), cert1.clone(), |f| f.partitioned = false);
+        assert_eq!(MemLinkFault::default(), mem_link_fault(&cert2, &cert1));
+
+        let (con1, _chan_recv1) = ep1.connect(addr2, t).await.unwrap();
+        let (_con2, mut chan_recv2) = con_recv2.next().await.unwrap().await.unwrap();
+
+        mem_update_link_fault(cert1.clone(), cert2.clone(), |f| f.drop_rate = 0.5);
+        let mut out_chan = con1.out_chan(t).await.unwrap();
+        for msg in ["0", "1", "2", "3"] {
+            let mut buf = PoolBuf::new();
+            buf.extend_from_slice(msg.as_bytes());
+            out_chan.write(0.into(), buf, t).await.unwrap();
+        }
+        mem_update_link_fault(cert1.clone(), cert2.clone(), |f| f.drop_rate = 0.0);
+        let mut buf = PoolBuf::new();
+        buf.extend_from_slice(b"end");
+        out_chan.write(0.into(), buf, t).await.unwrap();
+
+        let mut in_chan = chan_recv2.next().await.unwrap().await.unwrap();
+        let mut received = Vec::new();
+        while received.last().map(String::as_str) != Some("end") {
+            let (_, buf) = in_chan.read(t).await.unwrap();
+            received.push(String::from_utf8_lossy(&buf).to_string());
+        }
+        assert_eq!(vec!["0", "2", "end"], received);
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+    }
 }
@@ -15,6 +15,9 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     })?;
 
     Ok(InitCallbackResult::Pass)
@@ -56,6 +59,9 @@ fn cap_grant_entry(secret: CapSecret) -> ExternResult<CapGrantEntry> {
         tag: "".into(),
         access: secret.into(),
         functions,
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     })
 }
 
@@ -124,6 +130,9 @@ fn send_assigned_cap_claim(agent: AgentPubKey) -> ExternResult<()> {
         access: (secret, agent.clone()).into(),
         functions,
         tag: tag.clone(),
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     })?;
 
     // send the assigned cap token
@@ -136,3 +145,17 @@ fn send_assigned_cap_claim(agent: AgentPubKey) -> ExternResult<()> {
     )?;
     Ok(())
 }
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct Transfer {
+    pub from: String,
+    pub to: String,
+    pub amount: u32,
+}
+
+/// Returns the transfer it was called with, including any fields
+/// curried into it by the grant the call was made with.
+#[hdk_extern]
+fn echo_transfer(transfer: Transfer) -> ExternResult<Transfer> {
+    Ok(transfer)
+}
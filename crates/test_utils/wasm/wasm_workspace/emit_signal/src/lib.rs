@@ -11,6 +11,11 @@ fn signal_others(signal: RemoteSignal) -> ExternResult<()> {
     remote_signal(&signal.signal, signal.agents)
 }
 
+#[hdk_extern]
+fn signal_others_reliably(signal: RemoteSignal) -> ExternResult<()> {
+    remote_signal_reliable(&signal.signal, signal.agents)
+}
+
 #[hdk_extern]
 fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
     HDK.with(|h| h.borrow().emit_signal(AppSignal::new(signal)))
@@ -26,6 +31,9 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     })?;
 
     Ok(InitCallbackResult::Pass)
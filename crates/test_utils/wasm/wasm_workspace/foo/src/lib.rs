@@ -12,6 +12,9 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     })?;
 
     Ok(InitCallbackResult::Pass)
@@ -22,6 +22,9 @@ fn set_access(_: ()) -> ExternResult<()> {
         // empty access converts to unrestricted
         access: ().into(),
         functions,
+        expires_at: None,
+        max_uses: None,
+        curry_payloads: Default::default(),
     })?;
 
     Ok(())